@@ -0,0 +1,32 @@
+pub mod maze;
+pub mod maze_3d;
+pub mod maze_algorithm;
+pub mod maze_cell;
+pub mod maze_challenge;
+pub mod maze_config;
+pub mod maze_delta;
+pub mod maze_distance;
+pub mod maze_editor;
+pub mod maze_enemy;
+pub mod maze_error;
+pub mod maze_file;
+pub mod maze_gif;
+pub mod maze_grid;
+pub mod maze_hex;
+pub mod maze_items;
+pub mod maze_leaderboard;
+pub mod maze_mask;
+pub mod maze_play;
+pub mod maze_polar;
+pub mod maze_regenerate;
+pub mod maze_region;
+pub mod maze_registry;
+pub mod maze_render;
+pub mod maze_replay;
+pub mod maze_room;
+pub mod maze_solver;
+pub mod maze_terrain;
+pub mod maze_validator;
+pub mod maze_visibility;
+pub mod maze_wall;
+pub mod maze_weave;
@@ -1,24 +1,530 @@
-mod maze;
-use maze::Maze;
-mod maze_algorithm;
-use maze_algorithm::{DepthFirstSearch, PrimsAlgorithm, KruskalsAlgorithm};
-mod maze_cell;
-mod maze_wall;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal};
+use std::process::{self, Command, Stdio};
+use std::thread;
+use std::time::Instant;
+
+use maze_game::maze::{Maze, Wraparound};
+use maze_game::maze_3d::{Maze3D, Point3D};
+use maze_game::maze_algorithm::{DepthFirstSearch, PrimsAlgorithm, KruskalsAlgorithm};
+use maze_game::maze_challenge::{self, Challenge, ChallengeOptions};
+use maze_game::maze_config::AlgorithmConfig;
+use maze_game::maze_delta::DeltaMaze;
+use maze_game::maze_distance::{DistanceMap, Heatmap};
+use maze_game::maze_editor::{self, EditorSession};
+use maze_game::maze_enemy::Behaviour;
+use maze_game::maze_error::MazeError;
+use maze_game::maze_file::MazeFile;
+use maze_game::maze_gif::{self, GifOptions};
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_hex::HexMaze;
+use maze_game::maze_items::ItemOptions;
+use maze_game::maze_leaderboard::Leaderboard;
+use maze_game::maze_mask::MazeMask;
+use maze_game::maze_polar::PolarMaze;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_replay::{self, GameSetup, Replay};
+use maze_game::maze_render::{self, MazeDrawing, RenderOptions};
+use maze_game::maze_visibility::SightOptions;
+use maze_game::maze_weave::WeaveMaze;
+
+//Where challenge scores are kept, in the directory the game is run from
+const LEADERBOARD_FILE: &str = "leaderboard.json";
+
+//Where the last game played is recorded
+const REPLAY_FILE: &str = "last_game.replay.json";
 
 fn main() {
-    let mut maze = Maze::new(15, 15, Box::new(DepthFirstSearch));
+    let args: Vec<String> = env::args().collect();
+
+    //maze_game gif <generate|solve> <output file> [setting=value ...]
+    if args.len() > 1 && args[1] == "gif" {
+        export_gif(&args[2..]);
+        return;
+    }
+
+    //maze_game <svg|png> <square|terrain|cylinder|torus|weave|hex|delta|polar|3d> <output file>
+    if args.len() > 1 && (args[1] == "svg" || args[1] == "png") {
+        export_image(&args[1..]);
+        return;
+    }
+
+    //maze_game heatmap [<svg|png> <output file>]
+    if args.len() > 1 && args[1] == "heatmap" {
+        heatmap(&args[2..]);
+        return;
+    }
+
+    //maze_game play [fog] [items] [monsters] [width height [floors]]
+    if args.len() > 1 && args[1] == "play" {
+        play(&args[2..]);
+        return;
+    }
+
+    //maze_game replay <replay file> [speed]
+    if args.len() > 2 && args[1] == "replay" {
+        replay(&args[2], &args[3..]);
+        return;
+    }
+
+    //maze_game challenge <name> [seed]
+    if args.len() > 2 && args[1] == "challenge" {
+        challenge(&args[2], &args[3..]);
+        return;
+    }
+
+    //maze_game leaderboard
+    if args.len() > 1 && args[1] == "leaderboard" {
+        match Leaderboard::load(LEADERBOARD_FILE) {
+            Ok(leaderboard) => print!("{}", leaderboard),
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    //maze_game edit <maze file> [width height]
+    if args.len() > 2 && args[1] == "edit" {
+        edit(&args[2], &args[3..]);
+        return;
+    }
+
+    //maze_game algorithms [name]
+    if args.len() > 1 && args[1] == "algorithms" {
+        algorithms(&args[2..]);
+        return;
+    }
+
+    //maze_game config <TOML or JSON file>
+    if args.len() > 2 && args[1] == "config" {
+        match AlgorithmConfig::load(&args[2]).and_then(|config| config.build()) {
+            Ok(algorithm) => println!("{}", Maze::new(15, 15, algorithm)),
+            Err(error) => {
+                eprintln!("Could not load {}: {}", args[2], error);
+                process::exit(1);
+            }
+        }
+        return;
+    }
+
+    //maze_game 3d
+    if args.len() > 1 && args[1] == "3d" {
+        println!("{}", Maze3D::new(8, 8, 3, Box::new(DepthFirstSearch::new())));
+        return;
+    }
+
+    //maze_game hex
+    if args.len() > 1 && args[1] == "hex" {
+        println!("{}", HexMaze::new(10, 8, Box::new(DepthFirstSearch::new())));
+        return;
+    }
+
+    //maze_game mask <ASCII or PBM file>
+    let mut maze = if args.len() > 2 && args[1] == "mask" {
+        match MazeMask::load(&args[2]).map_err(MazeError::from).and_then(|mask| Maze::try_with_mask(mask, Box::new(DepthFirstSearch::new()))) {
+            Ok(maze) => maze,
+            Err(error) => {
+                eprintln!("Could not load {}: {}", args[2], error);
+                process::exit(1);
+            }
+        }
+    }
+    else {
+        Maze::new(15, 15, Box::new(DepthFirstSearch::new()))
+    };
 
     println!("===Maze Generator Test===");
     println!("\nDepth First Search:");
     println!("{}", maze);
 
-    maze.set_algorithm(Box::new(PrimsAlgorithm));
+    maze.set_algorithm(Box::new(PrimsAlgorithm::new()));
     maze.regenerate();
     println!("\nPrim's Algorithm");
     println!("{}", maze);
 
-    maze.set_algorithm(Box::new(KruskalsAlgorithm));
+    maze.set_algorithm(Box::new(KruskalsAlgorithm::new()));
     maze.regenerate();
     println!("\nKruskal's Algorithm");
     println!("{}", maze);
 }
+
+//Lists every registered algorithm, or prints a maze made with the one named
+fn algorithms(args: &[String]) {
+    let registry = AlgorithmRegistry::with_builtins();
+
+    match args.first() {
+        None => {
+            for info in registry.algorithms() {
+                println!("{:<20}{}", info.name, info.description);
+
+                for param in info.params.iter() {
+                    println!("{:<4}{:<20}{} ({}, default {})", "", param.name, param.description, param.kind, param.default);
+                }
+            }
+        }
+        Some(name) => match registry.create_default(name) {
+            Ok(algorithm) => println!("{}", Maze::new(15, 15, algorithm)),
+            Err(error) => {
+                eprintln!("{}", error);
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn play(args: &[String]) {
+    let usage = || {
+        eprintln!("Usage: maze_game play [fog] [items] [monsters] [width height [floors]]");
+        process::exit(1);
+    };
+
+    //With fog, the player only sees what's in sight. With items, there are coins to collect and doors to unlock.
+    //With monsters, one of each kind roams the maze.
+    let options = args.iter().take_while(|arg| ["fog", "items", "monsters"].contains(&arg.as_str())).count();
+    let fog = args[..options].iter().any(|arg| arg == "fog");
+    let items = args[..options].iter().any(|arg| arg == "items");
+    let monsters = args[..options].iter().any(|arg| arg == "monsters");
+    let args = &args[options..];
+
+    let size: Vec<usize> = args.iter().filter_map(|arg| arg.parse().ok()).collect();
+    let (width, height, floors) = match size.as_slice() {
+        [] => (10, 10, 1),
+        [width, height] => (*width, *height, 1),
+        [width, height, floors] => (*width, *height, *floors),
+        _ => usage(),
+    };
+
+    if size.len() != args.len() {
+        usage();
+    }
+
+    let mut setup = GameSetup::new(width, height, floors, "depth_first_search", rand::random());
+    if fog { setup.sight = Some(SightOptions::default()); }
+    if items { setup.items = Some(ItemOptions::default()); }
+    if monsters {
+        //The patroller guards the way between the exit and the middle of its floor
+        let goal = Point3D { x: width.saturating_sub(1), y: height.saturating_sub(1), z: floors.saturating_sub(1) };
+        let middle = Point3D { x: width / 2, y: height / 2, z: goal.z };
+        setup.monsters = vec![Behaviour::RandomWalker, Behaviour::WallFollower, Behaviour::Pursuer, Behaviour::Patrol(vec![goal, middle])];
+    }
+
+    let mut game = match setup.build(&AlgorithmRegistry::with_builtins()) {
+        Ok(value) => value,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    };
+
+    //Every game is recorded, so it can be played back or shared
+    let mut replay = Replay::new(setup);
+    let started = Instant::now();
+    let stdin = io::stdin();
+
+    if let Err(error) = maze_replay::record(&mut replay, &mut game, stdin.lock(), io::stdout(), &mut || started.elapsed()) {
+        eprintln!("Game stopped: {}", error);
+        process::exit(1);
+    }
+
+    match replay.save(REPLAY_FILE) {
+        Ok(()) => println!("The game was recorded to {}. Watch it with: maze_game replay {}", REPLAY_FILE, REPLAY_FILE),
+        Err(error) => eprintln!("Could not save the replay: {}", error),
+    }
+}
+
+//Plays back a recorded game. speed is how many times faster than it was played, 0 for no pauses at all.
+fn replay(file: &str, args: &[String]) {
+    let speed = match args {
+        [] => 1.0,
+        [speed] => match speed.parse::<f64>() {
+            Ok(value) if value >= 0.0 && value.is_finite() => value,
+            _ => {
+                eprintln!("Usage: maze_game replay <replay file> [speed]");
+                process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("Usage: maze_game replay <replay file> [speed]");
+            process::exit(1);
+        }
+    };
+
+    let result = Replay::load(file).and_then(|replay| {
+        maze_replay::play_back(&replay, &AlgorithmRegistry::with_builtins(), io::stdout(), speed, &mut |pause| thread::sleep(pause))
+    });
+
+    if let Err(error) = result {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+//Plays a timed challenge as name, then puts it on the leaderboard. A seed from an earlier challenge plays the same levels again.
+fn challenge(name: &str, args: &[String]) {
+    let seed = match args {
+        [] => rand::random(),
+        [seed] => match seed.parse() {
+            Ok(value) => value,
+            Err(_) => {
+                eprintln!("Usage: maze_game challenge <name> [seed]");
+                process::exit(1);
+            }
+        },
+        _ => {
+            eprintln!("Usage: maze_game challenge <name> [seed]");
+            process::exit(1);
+        }
+    };
+
+    let mut leaderboard = match Leaderboard::load(LEADERBOARD_FILE) {
+        Ok(value) => value,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(1);
+        }
+    };
+
+    let options = ChallengeOptions::default();
+    let mut challenge = match Challenge::new(options, seed) {
+        Ok(value) => value,
+        Err(error) => {
+            eprintln!("Could not build challenge: {}", error);
+            process::exit(1);
+        }
+    };
+
+    let started = Instant::now();
+    let stdin = io::stdin();
+    if let Err(error) = maze_challenge::run(&mut challenge, stdin.lock(), io::stdout(), &mut || started.elapsed()) {
+        eprintln!("Challenge stopped: {}", error);
+        process::exit(1);
+    }
+
+    //Earlier runs of the same levels, to compare with
+    for entry in leaderboard.same_levels(seed, &options) {
+        println!("{} scored {} on these levels.", entry.name, entry.score);
+    }
+
+    match leaderboard.add(challenge.entry(name)) {
+        Some(place) => println!("You're number {} on the leaderboard.", place + 1),
+        None => println!("Not enough for the leaderboard this time."),
+    }
+    if let Err(error) = leaderboard.save(LEADERBOARD_FILE) {
+        eprintln!("Could not save the leaderboard: {}", error);
+        process::exit(1);
+    }
+}
+
+//Edits the maze in file, or a new maze of the size given if the file doesn't exist yet
+fn edit(file: &str, args: &[String]) {
+    let size: Vec<usize> = args.iter().filter_map(|arg| arg.parse().ok()).collect();
+    let (width, height) = match size.as_slice() {
+        [] => (10, 10),
+        [width, height] if size.len() == args.len() => (*width, *height),
+        _ => {
+            eprintln!("Usage: maze_game edit <maze file> [width height]");
+            process::exit(1);
+        }
+    };
+
+    let opened = if fs::metadata(file).is_ok() {
+        MazeFile::load(file, Box::new(DepthFirstSearch::new())).map_err(|error| error.to_string())
+    }
+    else {
+        Maze::try_new(width, height, Box::new(DepthFirstSearch::new())).map(MazeFile::new).map_err(|error| error.to_string())
+    };
+
+    let mut session = match opened {
+        Ok(value) => EditorSession::new(value),
+        Err(error) => {
+            eprintln!("Could not open {}: {}", file, error);
+            process::exit(1);
+        }
+    };
+
+    let stdin = io::stdin();
+
+    //In a terminal every key is acted on as it's pressed. Piped in commands are read a line at a time.
+    let result = if stdin.is_terminal() {
+        let _keys = KeyMode::enter();
+        maze_editor::run_live(&mut session, file, stdin.lock(), io::stdout())
+    }
+    else {
+        maze_editor::run(&mut session, file, stdin.lock(), io::stdout())
+    };
+
+    if let Err(error) = result {
+        eprintln!("Editor stopped: {}", error);
+        process::exit(1);
+    }
+}
+
+//Puts the terminal in a mode where keys are handed over as they're pressed, without being echoed,
+//and puts back the settings it had when dropped. Does nothing where stty isn't around.
+struct KeyMode {
+    saved: Option<String>,
+}
+
+impl KeyMode {
+    fn enter() -> Self {
+        let saved = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+
+        if saved.is_some() {
+            let _ = Command::new("stty").args(["-icanon", "-echo", "-isig", "min", "1"]).stdin(Stdio::inherit()).status();
+        }
+
+        Self { saved }
+    }
+}
+
+impl Drop for KeyMode {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            let _ = Command::new("stty").arg(saved).stdin(Stdio::inherit()).status();
+        }
+    }
+}
+
+fn heatmap(args: &[String]) {
+    let maze = Maze::new(15, 15, Box::new(DepthFirstSearch::new()));
+    let source = maze.exits().map(|(entrance, _)| entrance).unwrap_or(0);
+    let distances = DistanceMap::new(&maze, source);
+    let heatmap = Heatmap::new(&maze, &distances);
+
+    match args {
+        [] => print!("{}", heatmap.ansi_string()),
+        [format, file] if format == "svg" || format == "png" => export_drawing(&heatmap, format, file),
+        _ => {
+            eprintln!("Usage: maze_game heatmap [<svg|png> <output file>]");
+            process::exit(1);
+        }
+    }
+}
+
+const GIF_USAGE: &str = "Usage: maze_game gif <generate|solve> <output file> [setting=value ...]
+Settings: scale, steps_per_frame, frame_delay and final_delay (in hundredths of a second),
+and the colours background, wall, visited, frontier, carved, path and outside as RRGGBB hex";
+
+fn export_gif(args: &[String]) {
+    if args.len() < 2 {
+        eprintln!("{}", GIF_USAGE);
+        process::exit(1);
+    }
+
+    let mut options = GifOptions::default();
+    for setting in &args[2..] {
+        if let Err(error) = set_gif_option(&mut options, setting) {
+            eprintln!("{}\n{}", error, GIF_USAGE);
+            process::exit(1);
+        }
+    }
+
+    let file = match File::create(&args[1]) {
+        Ok(file) => BufWriter::new(file),
+        Err(error) => {
+            eprintln!("Could not create {}: {}", args[1], error);
+            process::exit(1);
+        }
+    };
+
+    let mut maze = Maze::new(15, 15, Box::new(DepthFirstSearch::new()));
+
+    let result = match args[0].as_str() {
+        "generate" => maze_gif::export_generation(&mut maze, &options, file),
+        "solve" => maze_gif::export_solve(&maze, &options, file),
+        other => {
+            eprintln!("Unknown run \"{}\", expected generate or solve", other);
+            process::exit(1);
+        }
+    };
+
+    if let Err(error) = result {
+        eprintln!("Could not export GIF: {}", error);
+        process::exit(1);
+    }
+}
+
+//Changes one of options from a name=value setting given on the command line
+fn set_gif_option(options: &mut GifOptions, setting: &str) -> Result<(), String> {
+    let (name, value) = setting.split_once('=').ok_or_else(|| format!("Expected setting=value, got \"{}\"", setting))?;
+    let number = || value.parse::<u16>().map_err(|_| format!("{} must be a whole number, got \"{}\"", name, value));
+
+    let colour = match name {
+        "scale" => { options.scale = number()?; return Ok(()); }
+        "steps_per_frame" => { options.steps_per_frame = usize::from(number()?); return Ok(()); }
+        "frame_delay" => { options.frame_delay = number()?; return Ok(()); }
+        "final_delay" => { options.final_delay = number()?; return Ok(()); }
+        "background" => &mut options.background,
+        "wall" => &mut options.wall,
+        "visited" => &mut options.visited,
+        "frontier" => &mut options.frontier,
+        "carved" => &mut options.carved,
+        "path" => &mut options.path,
+        "outside" => &mut options.outside,
+        other => return Err(format!("Unknown setting \"{}\"", other)),
+    };
+
+    //Colours are written as RRGGBB, optionally with a leading #
+    let hex = value.trim_start_matches('#');
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|digits| u8::from_str_radix(digits, 16).ok());
+    match (hex.len(), channel(0), channel(2), channel(4)) {
+        (6, Some(red), Some(green), Some(blue)) => *colour = [red, green, blue],
+        _ => return Err(format!("{} must be a colour like ff8000, got \"{}\"", name, value)),
+    }
+
+    Ok(())
+}
+
+fn export_image(args: &[String]) {
+    if args.len() != 3 {
+        eprintln!("Usage: maze_game <svg|png> <square|terrain|cylinder|torus|weave|hex|delta|polar|3d> <output file>");
+        process::exit(1);
+    }
+
+    let drawing: Box<dyn MazeDrawing> = match args[1].as_str() {
+        "square" => Box::new(Maze::new(15, 15, Box::new(DepthFirstSearch::new()))),
+        "terrain" => {
+            let mut maze = Maze::new(15, 15, Box::new(DepthFirstSearch::new()));
+            maze.randomize_terrain(0.3, &mut rand::thread_rng());
+            Box::new(maze)
+        }
+        "cylinder" => Box::new(Maze::with_wraparound(15, 15, Wraparound::Cylinder, Box::new(DepthFirstSearch::new()))),
+        "torus" => Box::new(Maze::with_wraparound(15, 15, Wraparound::Torus, Box::new(DepthFirstSearch::new()))),
+        "weave" => Box::new(WeaveMaze::new(15, 15)),
+        "hex" => Box::new(HexMaze::new(15, 15, Box::new(DepthFirstSearch::new()))),
+        "delta" => Box::new(DeltaMaze::new(29, 15, Box::new(DepthFirstSearch::new()))),
+        "polar" => Box::new(PolarMaze::new(10, Box::new(DepthFirstSearch::new()))),
+        "3d" => Box::new(Maze3D::new(8, 8, 3, Box::new(DepthFirstSearch::new()))),
+        other => {
+            eprintln!("Unknown maze shape \"{}\", expected square, terrain, cylinder, torus, weave, hex, delta, polar or 3d", other);
+            process::exit(1);
+        }
+    };
+
+    export_drawing(drawing.as_ref(), &args[0], &args[2]);
+}
+
+//Writes drawing to file as an SVG if format is svg, or as a PNG otherwise
+fn export_drawing(drawing: &dyn MazeDrawing, format: &str, file: &str) {
+    let options = RenderOptions::default();
+
+    let result = if format == "svg" {
+        fs::write(file, maze_render::render_svg(drawing, &options)).map_err(|error| error.to_string())
+    }
+    else {
+        File::create(file)
+            .map_err(|error| error.to_string())
+            .and_then(|value| maze_render::render_png(drawing, &options, BufWriter::new(value)).map_err(|error| error.to_string()))
+    };
+
+    if let Err(error) = result {
+        eprintln!("Could not export {}: {}", file, error);
+        process::exit(1);
+    }
+}
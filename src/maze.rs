@@ -1,15 +1,50 @@
+extern crate rand;
+
 use std::fmt;
 use std::rc::Rc;
 use std::cell::RefCell;
 
+use rand::{Rng, RngCore, SeedableRng};
+use rand::rngs::StdRng;
+
 use super::maze_cell::MazeCell;
 use super::maze_wall::MazeWall;
-use super::maze_algorithm::MazeAlgorithm;
+use super::maze_algorithm::{MazeAlgorithm, MazeStep, check_locked_walls};
+use super::maze_grid::{MazeGrid, Point, Axis, Direction, FilteredGrid};
+use super::maze_regenerate::Regenerate;
+use super::maze_mask::{MazeMask, MaskError};
+use super::maze_error::{MazeError, check_area, check_dimensions};
+use super::maze_render::{MazeDrawing, Stroke, Fill};
+use super::maze_terrain::Terrain;
+use super::maze_room::{self, Room, RoomLayout};
+use super::maze_region::{self, RegionLayout};
+use super::maze_visibility::{self, SightOptions};
+
+//Which edges of a maze are joined together. A cylinder joins the left and right edges,
+//and a torus also joins the top and bottom edges, so passages can wrap around the maze.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wraparound {
+    None,
+    Cylinder,
+    Torus,
+}
+
+impl Wraparound {
+    pub fn wraps_horizontally(self) -> bool { self != Wraparound::None }
+    pub fn wraps_vertically(self) -> bool { self == Wraparound::Torus }
+}
 
 pub struct Maze {
     maze_algorithm: Box<dyn MazeAlgorithm>,
     cells: Vec<Vec<MazeCell>>,
     walls: Vec<Rc<RefCell<MazeWall>>>,
+    seed: u64,
+    mask: Option<MazeMask>,
+    wraparound: Wraparound,
+    terrain: Vec<Vec<Terrain>>,
+    room_layout: Option<RoomLayout>,
+    rooms: Vec<Room>,
+    region_layout: Option<RegionLayout>,
     width: usize,
     height: usize,
 }
@@ -21,6 +56,13 @@ impl Maze {
             maze_algorithm,
             cells: Vec::new(),
             walls: Vec::new(),
+            seed: 0,
+            mask: None,
+            wraparound: Wraparound::None,
+            terrain: Vec::new(),
+            room_layout: None,
+            rooms: Vec::new(),
+            region_layout: None,
             width,
             height,
         };
@@ -31,18 +73,99 @@ impl Maze {
         tmp
     }
 
-    //Resets all walls in maze to active. Primarily used to reset a maze for regeneration.
-    pub fn reset(&mut self) {
-        for wall in self.walls.iter_mut() {
-            wall.borrow_mut().active = true;
+    //Constructor for a maze in the shape of mask. Cells disabled by the mask are left out of the maze.
+    //If the mask is split into separate pieces, each piece gets a maze of its own and the pieces stay
+    //cut off from each other, so the maze won't validate. try_with_mask turns such masks down.
+    pub fn with_mask(mask: MazeMask, maze_algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        let mut tmp = Self {
+            maze_algorithm,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            seed: 0,
+            wraparound: Wraparound::None,
+            terrain: Vec::new(),
+            room_layout: None,
+            rooms: Vec::new(),
+            region_layout: None,
+            width: mask.width(),
+            height: mask.height(),
+            mask: Some(mask),
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor for a maze whose edges are joined together as given by wraparound
+    pub fn with_wraparound(width: usize, height: usize, wraparound: Wraparound, maze_algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        let mut tmp = Self {
+            maze_algorithm,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            seed: 0,
+            mask: None,
+            wraparound,
+            terrain: Vec::new(),
+            room_layout: None,
+            rooms: Vec::new(),
+            region_layout: None,
+            width,
+            height,
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor that checks the dimensions first. Fails if either is zero or the maze would be too large.
+    pub fn try_new(width: usize, height: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[width, height])?;
+        Ok(Self::new(width, height, maze_algorithm))
+    }
+
+    //Same as with_mask, but fails if the mask is too large, has no enabled cells or is split into separate pieces
+    pub fn try_with_mask(mask: MazeMask, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[mask.width(), mask.height()])?;
+        if mask.is_empty() {
+            return Err(MaskError::Empty.into());
         }
+        if let Some((x, y)) = mask.unreachable_cell() {
+            return Err(MaskError::Disconnected(x, y).into());
+        }
+
+        Ok(Self::with_mask(mask, maze_algorithm))
+    }
+
+    //Same as with_wraparound, but fails if either dimension is zero or the maze would be too large
+    pub fn try_with_wraparound(width: usize, height: usize, wraparound: Wraparound, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[width, height])?;
+        Ok(Self::with_wraparound(width, height, wraparound, maze_algorithm))
     }
 
     //Reconstructs maze with the dimensions width and height. Used to build a maze. No algorithm is applied on the Maze.
+    //All terrain is reset to floor.
     pub fn reconstruct(&mut self) {
         let width = self.width;
         let height = self.height;
         self.cells = vec![vec![MazeCell::new(); height]; width];
+        self.walls = Vec::new();
+        self.terrain = vec![vec![Terrain::Floor; height]; width];
+
+        //Disable the cells that are outside of the mask
+        if let Some(mask) = &self.mask {
+            for x in 0..width {
+                for y in 0..height {
+                    self.cells[x][y].set_enabled(mask.is_enabled(x, y));
+                }
+            }
+        }
+
+        //A maze with no cells has no walls either
+        if width == 0 || height == 0 { return; }
 
         //Build Walls
 
@@ -69,89 +192,592 @@ impl Maze {
             }
         }
 
-        //Add walls to the bottom row and right column
+        //Add walls to the bottom row and right column.
+        //Joined edges reuse the walls on the opposite side instead of getting border walls.
         for column in self.cells.iter_mut() {
+            if self.wraparound.wraps_vertically() && height > 1 {
+                let top_wall = Rc::clone(column[0].top_wall_mut());
+                column[height - 1].set_bottom_wall(top_wall);
+                continue;
+            }
+
             let bottom_wall = Rc::new(RefCell::new(MazeWall::new(true)));
             column[height - 1].set_bottom_wall(Rc::clone(&bottom_wall));
             self.walls.push(Rc::clone(&bottom_wall));
         }
 
-        for row in self.cells[width - 1].iter_mut() {
+        for y in 0..height {
+            if self.wraparound.wraps_horizontally() && width > 1 {
+                let left_wall = Rc::clone(self.cells[0][y].left_wall_mut());
+                self.cells[width - 1][y].set_right_wall(left_wall);
+                continue;
+            }
+
             let right_wall = Rc::new(RefCell::new(MazeWall::new(true)));
-            row.set_right_wall(Rc::clone(&right_wall));
+            self.cells[width - 1][y].set_right_wall(Rc::clone(&right_wall));
             self.walls.push(Rc::clone(&right_wall));
         }
     }
 
-    pub fn regenerate(&mut self) {
-        self.reset();
-        self.maze_algorithm.generate(&mut self.cells, &mut self.walls);
+    //Carves the region at index again from seed, leaving the rest of the maze as it is, and stitches it back in.
+    //Rooms and their doors are left alone. Afterwards the maze can no longer be rebuilt from its seed alone.
+    pub fn regenerate_region(&mut self, index: usize, seed: u64) -> Result<(), MazeError> {
+        let layout = match &self.region_layout {
+            Some(value) if index < value.regions.len() => value,
+            _ => return Err(MazeError::NoSuchRegion(index)),
+        };
+
+        self.carve_part(&self.part_of(), index, layout.regions[index].algorithm.as_ref(), layout.extra_connections, seed);
+        Ok(())
+    }
+
+    //Same as regenerate_region, but for any rectangle of the maze and with the algorithm given.
+    //x and y are the top left cell. Fails if the rectangle reaches outside the maze.
+    pub fn regenerate_area(&mut self, x: usize, y: usize, width: usize, height: usize, algorithm: &dyn MazeAlgorithm, seed: u64) -> Result<(), MazeError> {
+        check_area(x, y, width, height, self.width, self.height)?;
+
+        let part_of: Vec<usize> = (0..self.cell_count()).map(|cell| {
+            let point = self.point(cell);
+            if point.x >= x && point.x < x + width && point.y >= y && point.y < y + height { 0 } else { 1 }
+        }).collect();
+
+        self.carve_part(&part_of, 0, algorithm, 0, seed);
+        Ok(())
+    }
+
+    //Cuts the cells of part out of the maze, carves them again with algorithm and stitches them back in.
+    //part_of gives the part every cell is in. Rooms and their doors are left alone.
+    fn carve_part(&self, part_of: &[usize], part: usize, algorithm: &dyn MazeAlgorithm, extra_connections: usize, seed: u64) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let room_of: Vec<Option<usize>> = (0..self.cell_count()).map(|cell| self.room_at(self.point(cell))).collect();
+
+        //Walls inside the part and on its boundary are closed again
+        for cell in 0..self.cell_count() {
+            if part_of[cell] != part || room_of[cell].is_some() || !self.is_enabled(cell) { continue; }
+
+            for (neighbor, wall) in self.neighbors(cell) {
+                if room_of[neighbor].is_none() {
+                    wall.borrow_mut().reset();
+                }
+            }
+        }
+
+        let corridors = FilteredGrid::new(self, &|cell| room_of[cell].is_none());
+        let area = FilteredGrid::new(&corridors, &|cell| part_of[cell] == part);
+
+        algorithm.generate(&area, &mut rng);
+        maze_region::stitch(&corridors, part_of, &[part], extra_connections, &mut rng, &mut |_| {});
+    }
+
+    //Index of the region each cell is in. Cells outside every region get the number of regions.
+    fn part_of(&self) -> Vec<usize> {
+        let count = self.region_layout.as_ref().map_or(0, |layout| layout.regions.len());
+        (0..self.cell_count()).map(|cell| self.region_at(self.point(cell)).unwrap_or(count)).collect()
     }
 
     pub fn set_algorithm(&mut self, new_algorithm: Box<dyn MazeAlgorithm>) {
         self.maze_algorithm = new_algorithm;
     }
-}
 
-impl fmt::Display for Maze {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    //Reshapes the maze to the mask, or back to a full rectangle if mask is None.
+    //The maze is reconstructed, so it has to be regenerated afterwards.
+    pub fn set_mask(&mut self, mask: Option<MazeMask>) {
+        if let Some(value) = &mask {
+            self.width = value.width();
+            self.height = value.height();
+        }
+
+        self.mask = mask;
+        self.reconstruct();
+    }
+
+    //Sets the rooms the maze is generated with, or takes them all away if layout is None.
+    //Fails if a listed room is outside the maze, covers a cell the mask disables, or overlaps another listed room.
+    //The maze has to be regenerated afterwards.
+    pub fn set_room_layout(&mut self, layout: Option<RoomLayout>) -> Result<(), MazeError> {
+        if let Some(value) = &layout {
+            for (index, room) in value.rooms.iter().enumerate() {
+                let inside = room.width > 0 && room.height > 0 && check_area(room.x, room.y, room.width, room.height, self.width, self.height).is_ok();
+                let enabled = inside && room.points().into_iter().all(|point| self.is_enabled_at(point));
+                let overlaps = value.rooms[..index].iter().any(|other| room.overlaps(other, 0));
+
+                if !enabled || overlaps {
+                    return Err(MazeError::InvalidRoom(*room));
+                }
+            }
+        }
+
+        self.room_layout = layout;
+        Ok(())
+    }
+
+    //Splits the maze into regions that are each generated with their own algorithm, or makes it whole again if layout is None.
+    //Fails if a region is empty, reaches outside the maze or overlaps another region. The maze has to be regenerated afterwards.
+    pub fn set_region_layout(&mut self, layout: Option<RegionLayout>) -> Result<(), MazeError> {
+        if let Some(value) = &layout {
+            for (index, region) in value.regions.iter().enumerate() {
+                let inside = region.width > 0 && region.height > 0 && check_area(region.x, region.y, region.width, region.height, self.width, self.height).is_ok();
+                let overlaps = value.regions[..index].iter().any(|other| region.overlaps(other));
+
+                if !inside || overlaps {
+                    return Err(MazeError::InvalidRegion(index));
+                }
+            }
+        }
+
+        self.region_layout = layout;
+        Ok(())
+    }
+
+    //Locks the wall between two neighboring cells closed, so every algorithm leaves it standing.
+    //Fails if the cells aren't enabled neighbors, or if the wall would cut the maze in two.
+    //Locked walls last until they're unlocked or the maze is reconstructed. The maze has to be regenerated afterwards.
+    pub fn force_wall(&mut self, one: Point, two: Point) -> Result<(), MazeError> {
+        self.lock_wall(one, two, true)
+    }
+
+    //Locks the wall between two neighboring cells open, so every algorithm keeps the passage between them.
+    //Fails if the cells aren't enabled neighbors, or if the passage would make a loop with the other forced passages.
+    pub fn force_passage(&mut self, one: Point, two: Point) -> Result<(), MazeError> {
+        self.lock_wall(one, two, false)
+    }
+
+    //Lets the algorithms decide on the wall between two neighboring cells again
+    pub fn unlock_wall(&mut self, one: Point, two: Point) -> Result<(), MazeError> {
+        self.wall_between(one, two)?.borrow_mut().locked = false;
+        Ok(())
+    }
+
+    //Unlocks every wall in the maze
+    pub fn unlock_all_walls(&mut self) {
+        for wall in self.walls.iter_mut() {
+            wall.borrow_mut().locked = false;
+        }
+    }
+
+    //True if the wall between two neighboring cells is locked
+    pub fn is_locked(&self, one: Point, two: Point) -> bool {
+        self.wall_between(one, two).is_ok_and(|wall| wall.borrow().locked)
+    }
+
+    //The wall two enabled neighboring cells share
+    fn wall_between(&self, one: Point, two: Point) -> Result<Rc<RefCell<MazeWall>>, MazeError> {
+        if self.is_enabled_at(one) && self.is_enabled_at(two) {
+            let neighbor = self.cell_id(two);
+            if let Some((_, wall)) = self.neighbors(self.cell_id(one)).into_iter().find(|(cell, _)| *cell == neighbor) {
+                return Ok(wall);
+            }
+        }
+
+        Err(MazeError::NotNeighbors(one, two))
+    }
+
+    //Locks the wall between two neighboring cells as active or not. The wall is put back the way it was
+    //if the locked walls would leave no way to generate a perfect maze.
+    fn lock_wall(&mut self, one: Point, two: Point, active: bool) -> Result<(), MazeError> {
+        let wall = self.wall_between(one, two)?;
+        let (was_active, was_locked) = (wall.borrow().active, wall.borrow().locked);
+
+        wall.borrow_mut().active = active;
+        wall.borrow_mut().locked = true;
+
+        if let Err(error) = check_locked_walls(&*self) {
+            wall.borrow_mut().active = was_active;
+            wall.borrow_mut().locked = was_locked;
+            return Err(error);
+        }
+
+        Ok(())
+    }
+
+    //Joins the edges of the maze as given by wraparound.
+    //The maze is reconstructed, so it has to be regenerated afterwards.
+    pub fn set_wraparound(&mut self, wraparound: Wraparound) {
+        self.wraparound = wraparound;
+        self.reconstruct();
+    }
+
+    //Getters
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn room_layout(&self) -> Option<&RoomLayout> { self.room_layout.as_ref() }
+
+    //Rooms the maze was last generated with, for placing things in them
+    pub fn rooms(&self) -> &Vec<Room> { &self.rooms }
+
+    //Index into rooms of the room that covers point
+    pub fn room_at(&self, point: Point) -> Option<usize> { self.rooms.iter().position(|room| room.contains(point)) }
+    pub fn region_layout(&self) -> Option<&RegionLayout> { self.region_layout.as_ref() }
+
+    //Index of the region that covers point, None if the maze's own algorithm generates it
+    pub fn region_at(&self, point: Point) -> Option<usize> {
+        self.region_layout.as_ref()?.regions.iter().position(|region| region.contains(point))
+    }
+    pub fn cells(&self) -> &Vec<Vec<MazeCell>> { &self.cells }
+    pub fn mask(&self) -> Option<&MazeMask> { self.mask.as_ref() }
+    pub fn wraparound(&self) -> Wraparound { self.wraparound }
+    pub fn terrain(&self, point: Point) -> Terrain { self.terrain[point.x][point.y] }
+
+    //Setters
+    pub fn set_terrain(&mut self, point: Point, terrain: Terrain) { self.terrain[point.x][point.y] = terrain; }
+
+    //Changes the seed the maze reports without generating it again, e.g. to put back the seed of walls being restored
+    pub fn set_seed(&mut self, seed: u64) { self.seed = seed; }
+
+    //The cell at point, e.g. for swapping out its walls. Walls swapped in aren't reset or regenerated along with the maze's own.
+    pub fn cell_mut(&mut self, point: Point) -> &mut MazeCell { &mut self.cells[point.x][point.y] }
+
+    //Sets the terrain of every cell to what pattern returns for it
+    pub fn set_terrain_pattern(&mut self, pattern: &dyn Fn(Point) -> Terrain) {
+        for x in 0..self.width {
+            for y in 0..self.height {
+                self.terrain[x][y] = pattern(Point { x, y });
+            }
+        }
+    }
+
+    //Gives each cell a random terrain other than floor with a chance of fraction, and floor otherwise.
+    //The only randomness comes from rng, so the same rng gives the same terrain.
+    pub fn randomize_terrain(&mut self, fraction: f64, rng: &mut dyn RngCore) {
+        let rough = &Terrain::ALL[1..];
+
+        for column in self.terrain.iter_mut() {
+            for terrain in column.iter_mut() {
+                *terrain = if rng.gen::<f64>() < fraction { rough[rng.gen_range(0..rough.len())] } else { Terrain::Floor };
+            }
+        }
+    }
+
+    //Lower bound on the cost of getting from one cell to another, for the A* solver.
+    //Every step costs at least as much as floor, and no path is shorter than the straight line distance in cells.
+    pub fn estimated_cost(&self, from: usize, to: usize) -> u32 {
+        let from = self.point(from);
+        let to = self.point(to);
+
+        let mut dx = from.x.abs_diff(to.x);
+        let mut dy = from.y.abs_diff(to.y);
+
+        //Wrapped edges can make the way around the back shorter
+        if self.wraparound.wraps_horizontally() { dx = dx.min(self.width - dx); }
+        if self.wraparound.wraps_vertically() { dy = dy.min(self.height - dy); }
+
+        (dx + dy) as u32 * Terrain::Floor.cost()
+    }
+
+    //Cells are numbered column by column for the maze algorithms
+    pub fn cell_id(&self, point: Point) -> usize { point.x * self.height + point.y }
+    pub fn point(&self, cell: usize) -> Point { Point { x: cell / self.height, y: cell % self.height } }
+
+    //Returns false for disabled cells and for points outside of the maze
+    pub fn is_enabled_at(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height && self.cells[point.x][point.y].is_enabled()
+    }
+
+    //The point next to point in direction, coming back in on the opposite side of a joined edge.
+    //None if it's outside the maze.
+    pub fn step(&self, point: Point, direction: Direction) -> Option<Point> {
+        let next = match direction {
+            Direction::North => Point { x: point.x, y: point.y.wrapping_sub(1) },
+            Direction::South => Point { x: point.x, y: point.y + 1 },
+            Direction::East => Point { x: point.x + 1, y: point.y },
+            Direction::West => Point { x: point.x.wrapping_sub(1), y: point.y },
+        };
+
+        let next = self.wrap_point(next);
+        if next.x < self.width && next.y < self.height { Some(next) } else { None }
+    }
+
+    //Cells that can be seen from point, as limited by options. Sight doesn't carry across joined edges,
+    //and cells left out by the mask are never seen.
+    pub fn visible_from(&self, point: Point, options: &SightOptions) -> Vec<Point> {
+        if !self.is_enabled_at(point) { return Vec::new(); }
+
+        let blocked = |from: Point, direction: Direction| {
+            let cell = &self.cells[from.x][from.y];
+            let wall = match direction {
+                Direction::North => cell.top_wall(),
+                Direction::South => cell.bottom_wall(),
+                Direction::East => cell.right_wall(),
+                Direction::West => cell.left_wall(),
+            };
+
+            wall.active || self.step(from, direction).is_none_or(|next| !self.is_enabled_at(next))
+        };
+
+        maze_visibility::visible_cells(self.width, self.height, point, options, &blocked)
+    }
+
+    //Moves a point that stepped off a joined edge back in on the opposite side.
+    //Points to the left of or above the maze are passed in as wrapped around usize values.
+    fn wrap_point(&self, point: Point) -> Point {
+        let mut point = point;
+
+        if self.wraparound.wraps_horizontally() && self.width > 1 {
+            if point.x == usize::MAX { point.x = self.width - 1; }
+            else if point.x == self.width { point.x = 0; }
+        }
+        if self.wraparound.wraps_vertically() && self.height > 1 {
+            if point.y == usize::MAX { point.y = self.height - 1; }
+            else if point.y == self.height { point.y = 0; }
+        }
+
+        point
+    }
+
+    //Walls are only drawn where they border at least one enabled cell, which gives masked mazes their outline.
+    //Points to the left of or above the maze are passed in as wrapped around usize values, which is_enabled rejects.
+    fn wall_visible(&self, one: Point, two: Point) -> bool {
+        self.is_enabled_at(one) || self.is_enabled_at(two)
+    }
+
+    //A corner is drawn if any of the four cells around it is enabled. (x, y) is the cell below and to the right of it.
+    pub fn corner_visible(&self, x: usize, y: usize) -> bool {
+        let left = x.wrapping_sub(1);
+        let up = y.wrapping_sub(1);
+
+        //A corner inside a room has no walls running into it at all, so it's left out
+        let cells = [Point { x: left, y: up }, Point { x, y: up }, Point { x: left, y }, Point { x, y }];
+        if cells.iter().all(|point| self.is_enabled_at(*point)) &&
+            !self.cells[left][y].top_wall().active && !self.cells[x][y].top_wall().active &&
+            !self.cells[x][up].left_wall().active && !self.cells[x][y].left_wall().active {
+            return false;
+        }
+
+        cells.iter().any(|point| self.is_enabled_at(*point))
+    }
+
+    //Text drawing of the maze, the same one Display prints. contents can replace what is drawn inside a cell,
+    //which is normally two spaces, with something else that takes up two characters.
+    pub fn string_with(&self, contents: &dyn Fn(Point) -> Option<String>) -> String {
         //Exception case
-        if self.cells.len() == 0 { return write!(f, "Maze is empty."); }
+        if self.cell_count() == 0 { return "Maze is empty.".to_string(); }
 
+        let block = |visible: bool| if visible { "██" } else { "  " };
         let mut maze_str = String::new();
 
         for y in 0..self.height {
             //Top Row
             for x in 0..self.width {
-                maze_str += "██";
-
-                if self.cells[x][y].top_wall().active { 
-                    maze_str += "██"; 
-                }
-                else { 
-                    maze_str += "  "; 
-                }
+                maze_str += block(self.corner_visible(x, y));
+                maze_str += block(self.cells[x][y].top_wall().active && 
+                    self.wall_visible(Point { x, y }, Point { x, y: y.wrapping_sub(1) }));
             }
 
             //Rightest wall of top row
-            maze_str += "██\n";
+            maze_str += block(self.corner_visible(self.width, y));
+            maze_str += "\n";
             
             //Middle Row
             for x in 0..self.width {
-                if self.cells[x][y].left_wall().active { 
-                    maze_str += "██"; 
-                }
-                else { 
-                    maze_str += "  "; 
-                }
+                maze_str += block(self.cells[x][y].left_wall().active && 
+                    self.wall_visible(Point { x, y }, Point { x: x.wrapping_sub(1), y }));
 
-                maze_str += "  ";
+                match contents(Point { x, y }) {
+                    Some(value) => maze_str += &value,
+                    None => maze_str += "  ",
+                }
             }
 
             //Rightest wall of middle row
-            if self.cells[self.width - 1][y].right_wall().active { 
-                maze_str += "██\n"; 
+            maze_str += block(self.cells[self.width - 1][y].right_wall().active && 
+                self.is_enabled_at(Point { x: self.width - 1, y }));
+            maze_str += "\n";
+        }
+
+        //Bottom Row
+        for x in 0..self.width {
+            maze_str += block(self.corner_visible(x, self.height));
+            maze_str += block(self.cells[x][self.height - 1].bottom_wall().active && 
+                self.is_enabled_at(Point { x, y: self.height - 1 }));
+        }
+
+        //Bottom right corner
+        maze_str += block(self.corner_visible(self.width, self.height));
+        maze_str += "\n";
+
+        maze_str
+    }
+}
+
+impl Regenerate for Maze {
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>] { &self.walls }
+
+    //Same as regenerate_with_seed, but reports every step of the algorithm to trace.
+    //With a room layout, the rooms are placed first, the algorithm carves the rest of the maze around them,
+    //and then the rooms are opened up and given their doors.
+    //With a region layout, every region is carved by its own algorithm, the cells outside them by the maze's algorithm,
+    //and the regions are then stitched together.
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep)) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        self.seed = seed;
+        self.reset();
+
+        if self.room_layout.is_none() && self.region_layout.is_none() {
+            self.rooms = Vec::new();
+            self.maze_algorithm.generate_traced(&*self, &mut rng, trace);
+            return;
+        }
+
+        self.rooms = match &self.room_layout {
+            //Rooms stay clear of locked walls, so they never break up a hand made section
+            Some(layout) => {
+                let fits = |point: Point| {
+                    self.is_enabled_at(point) && self.neighbors(self.cell_id(point)).iter().all(|(_, wall)| !wall.borrow().locked)
+                };
+                maze_room::place_rooms(layout, self.width, self.height, &fits, &mut rng)
             }
-            else { 
-                maze_str += "  \n"; 
+            None => Vec::new(),
+        };
+
+        let room_of: Vec<Option<usize>> = (0..self.cell_count()).map(|cell| self.room_at(self.point(cell))).collect();
+        let corridors = FilteredGrid::new(&*self, &|cell| room_of[cell].is_none());
+
+        match &self.region_layout {
+            Some(layout) => {
+                let part_of = self.part_of();
+
+                for (index, region) in layout.regions.iter().enumerate() {
+                    let area = FilteredGrid::new(&corridors, &|cell| part_of[cell] == index);
+                    region.algorithm.generate_traced(&area, &mut rng, trace);
+                }
+
+                let rest = FilteredGrid::new(&corridors, &|cell| part_of[cell] == layout.regions.len());
+                self.maze_algorithm.generate_traced(&rest, &mut rng, trace);
+
+                let parts: Vec<usize> = (0..layout.regions.len()).collect();
+                maze_region::stitch(&corridors, &part_of, &parts, layout.extra_connections, &mut rng, trace);
             }
+
+            None => self.maze_algorithm.generate_traced(&corridors, &mut rng, trace),
         }
 
-        //Bottom Row
+        if let Some(layout) = &self.room_layout {
+            maze_room::open_rooms(&*self, &room_of, layout.doors, &mut rng, trace);
+        }
+    }
+}
+
+impl MazeGrid for Maze {
+    fn cell_count(&self) -> usize { self.width * self.height }
+
+    fn is_enabled(&self, cell: usize) -> bool { self.is_enabled_at(self.point(cell)) }
+
+    //Neighbors in the same row are side by side, including the ones joined across a wrapped edge
+    fn axis(&self, cell: usize, neighbor: usize) -> Option<Axis> {
+        if self.point(cell).y == self.point(neighbor).y { Some(Axis::Horizontal) } else { Some(Axis::Vertical) }
+    }
+
+    fn cost(&self, cell: usize) -> u32 {
+        let point = self.point(cell);
+        self.terrain[point.x][point.y].cost()
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        let point = self.point(cell);
+        let maze_cell = &self.cells[point.x][point.y];
+        let mut neighbors = Vec::new();
+
+        let left = self.wrap_point(Point { x: point.x.wrapping_sub(1), y: point.y });
+        let right = self.wrap_point(Point { x: point.x + 1, y: point.y });
+        let up = self.wrap_point(Point { x: point.x, y: point.y.wrapping_sub(1) });
+        let down = self.wrap_point(Point { x: point.x, y: point.y + 1 });
+
+        if self.is_enabled_at(left) { neighbors.push((self.cell_id(left), Rc::clone(maze_cell.left_wall_mut()))); }
+        if self.is_enabled_at(right) { neighbors.push((self.cell_id(right), Rc::clone(maze_cell.right_wall_mut()))); }
+        if self.is_enabled_at(up) { neighbors.push((self.cell_id(up), Rc::clone(maze_cell.top_wall_mut()))); }
+        if self.is_enabled_at(down) { neighbors.push((self.cell_id(down), Rc::clone(maze_cell.bottom_wall_mut()))); }
+
+        neighbors
+    }
+
+    //The entrance and exit are the first and last enabled cells going column by column.
+    //The entrance always has a border (or disabled cell) to its left, and the exit always has one to its right.
+    fn exits(&self) -> Option<(usize, usize)> {
+        let entrance = (0..self.cell_count()).find(|cell| self.is_enabled(*cell))?;
+        let exit = (0..self.cell_count()).rev().find(|cell| self.is_enabled(*cell))?;
+
+        Some((entrance, exit))
+    }
+
+    //Opens the left wall of the entrance and the right wall of the exit.
+    //On a cylinder the left and right edges are joined, so the top of the entrance and the bottom of the exit are opened instead.
+    //A torus has no border at all, so nothing is opened.
+    fn open_exits(&self) {
+        if let Some((entrance, exit)) = self.exits() {
+            let entrance = &self.cells[self.point(entrance).x][self.point(entrance).y];
+            let exit = &self.cells[self.point(exit).x][self.point(exit).y];
+
+            match self.wraparound {
+                Wraparound::None => {
+                    entrance.left_wall_mut().borrow_mut().active = false;
+                    exit.right_wall_mut().borrow_mut().active = false;
+                }
+
+                Wraparound::Cylinder => {
+                    entrance.top_wall_mut().borrow_mut().active = false;
+                    exit.bottom_wall_mut().borrow_mut().active = false;
+                }
+
+                Wraparound::Torus => {}
+            }
+        }
+    }
+}
+
+impl MazeDrawing for Maze {
+    fn extent(&self) -> (f64, f64) { (self.width as f64, self.height as f64) }
+
+    //Cells are coloured by their terrain
+    fn fills(&self) -> Vec<Fill> {
+        let mut fills = Vec::new();
+
         for x in 0..self.width {
-            maze_str += "██";
+            for y in 0..self.height {
+                if !self.cells[x][y].is_enabled() { continue; }
 
-            if self.cells[x][self.height - 1].bottom_wall().active { 
-                maze_str += "██"; 
+                if let Some(colour) = self.terrain[x][y].colour() {
+                    fills.push(Fill::square(x as f64, y as f64, colour));
+                }
             }
-            else { 
-                maze_str += "  "; 
+        }
+
+        fills
+    }
+
+    fn wall_strokes(&self) -> Vec<Stroke> {
+        let mut strokes = Vec::new();
+        let line = |from: (usize, usize), to: (usize, usize)| Stroke::Line {
+            from: (from.0 as f64, from.1 as f64),
+            to: (to.0 as f64, to.1 as f64),
+        };
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let cell = &self.cells[x][y];
+
+                if cell.top_wall().active && self.wall_visible(Point { x, y }, Point { x, y: y.wrapping_sub(1) }) {
+                    strokes.push(line((x, y), (x + 1, y)));
+                }
+                if cell.left_wall().active && self.wall_visible(Point { x, y }, Point { x: x.wrapping_sub(1), y }) {
+                    strokes.push(line((x, y), (x, y + 1)));
+                }
+                if x == self.width - 1 && cell.right_wall().active && cell.is_enabled() {
+                    strokes.push(line((x + 1, y), (x + 1, y + 1)));
+                }
+                if y == self.height - 1 && cell.bottom_wall().active && cell.is_enabled() {
+                    strokes.push(line((x, y + 1), (x + 1, y + 1)));
+                }
             }
         }
 
-        //Bottom right corner
-        maze_str += "██\n";
+        strokes
+    }
+}
 
-        write!(f, "{}", maze_str)
+impl fmt::Display for Maze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.string_with(&|_| None))
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,448 @@
+extern crate rand;
+
+use std::fmt;
+use std::rc::Rc;
+use std::cell::{RefCell, Ref};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Serialize, Deserialize};
+
+use super::maze_wall::MazeWall;
+use super::maze_algorithm::{MazeAlgorithm, MazeStep};
+use super::maze_grid::{MazeGrid, Point, Axis, Direction};
+use super::maze_regenerate::Regenerate;
+use super::maze_render::{MazeDrawing, Stroke};
+use super::maze_error::{MazeError, check_dimensions};
+use super::maze_visibility::{self, CellView, SightOptions};
+
+//Position of a cell in a multi level maze. z is the floor, starting from the bottom.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Point3D {
+    pub x: usize,
+    pub y: usize,
+    pub z: usize,
+}
+
+//The six sides of a cell. Up and down are the floor and ceiling, which stairs lead through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction3D {
+    North,
+    South,
+    East,
+    West,
+    Up,
+    Down,
+}
+
+impl Direction3D {
+    pub const ALL: [Direction3D; 6] = [
+        Direction3D::North,
+        Direction3D::South,
+        Direction3D::East,
+        Direction3D::West,
+        Direction3D::Up,
+        Direction3D::Down,
+    ];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            Direction3D::North => Direction3D::South,
+            Direction3D::South => Direction3D::North,
+            Direction3D::East => Direction3D::West,
+            Direction3D::West => Direction3D::East,
+            Direction3D::Up => Direction3D::Down,
+            Direction3D::Down => Direction3D::Up,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Direction3D::North => 0,
+            Direction3D::South => 1,
+            Direction3D::East => 2,
+            Direction3D::West => 3,
+            Direction3D::Up => 4,
+            Direction3D::Down => 5,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Cell3D {
+    walls: [Option<Rc<RefCell<MazeWall>>>; 6],
+}
+
+impl Cell3D {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Getters
+    pub fn wall(&self, direction: Direction3D) -> Ref<'_, MazeWall> {
+        self.wall_mut(direction).borrow()
+    }
+
+    //An open floor or ceiling is a staircase to the next floor
+    pub fn has_stairs(&self, direction: Direction3D) -> bool {
+        !self.wall(direction).active
+    }
+
+    //Mutable Access
+    pub fn wall_mut(&self, direction: Direction3D) -> &Rc<RefCell<MazeWall>> {
+        match &self.walls[direction.index()] {
+            Some(value) => value,
+            None => panic!("Cell3D was not properly constructed."),
+        }
+    }
+
+    //Setters
+    pub fn set_wall(&mut self, direction: Direction3D, wall: Rc<RefCell<MazeWall>>) { self.walls[direction.index()] = Some(wall); }
+}
+
+//A maze of several floors stacked on top of each other, connected by stairs.
+//The entrance is on the west side of the bottom floor and the exit is on the east side of the top floor.
+pub struct Maze3D {
+    maze_algorithm: Box<dyn MazeAlgorithm>,
+    cells: Vec<Vec<Vec<Cell3D>>>,
+    walls: Vec<Rc<RefCell<MazeWall>>>,
+    seed: u64,
+    width: usize,
+    height: usize,
+    depth: usize,
+}
+
+impl Maze3D {
+    //Constructor
+    pub fn new(width: usize, height: usize, depth: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        let mut tmp = Self {
+            maze_algorithm,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            seed: 0,
+            width,
+            height,
+            depth,
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor that checks the dimensions first. Fails if any is zero or the maze would be too large.
+    pub fn try_new(width: usize, height: usize, depth: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[width, height, depth])?;
+        Ok(Self::new(width, height, depth, maze_algorithm))
+    }
+
+    //Reconstructs maze with the dimensions width, height and depth. No algorithm is applied on the maze.
+    pub fn reconstruct(&mut self) {
+        self.cells = vec![vec![vec![Cell3D::new(); self.height]; self.width]; self.depth];
+        self.walls = Vec::new();
+
+        //Every wall is built once, by the first of its two cells, and then shared with the neighboring cell
+        for z in 0..self.depth {
+            for x in 0..self.width {
+                for y in 0..self.height {
+                    for direction in Direction3D::ALL.iter() {
+                        if self.cells[z][x][y].walls[direction.index()].is_some() { continue; }
+
+                        let wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                        self.cells[z][x][y].set_wall(*direction, Rc::clone(&wall));
+
+                        if let Some(neighbor) = self.neighbor(Point3D { x, y, z }, *direction) {
+                            self.cells[neighbor.z][neighbor.x][neighbor.y].set_wall(direction.opposite(), Rc::clone(&wall));
+                        }
+
+                        self.walls.push(wall);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn set_algorithm(&mut self, new_algorithm: Box<dyn MazeAlgorithm>) {
+        self.maze_algorithm = new_algorithm;
+    }
+
+    //Getters
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn depth(&self) -> usize { self.depth }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn cells(&self) -> &Vec<Vec<Vec<Cell3D>>> { &self.cells }
+
+    pub fn cell(&self, point: Point3D) -> &Cell3D { &self.cells[point.z][point.x][point.y] }
+
+    //Cells are numbered floor by floor, and column by column within a floor
+    pub fn cell_id(&self, point: Point3D) -> usize { (point.z * self.width + point.x) * self.height + point.y }
+
+    pub fn point(&self, cell: usize) -> Point3D {
+        let floor_size = self.width * self.height;
+        let index = cell % floor_size;
+        Point3D { x: index / self.height, y: index % self.height, z: cell / floor_size }
+    }
+
+    //Returns the cell next to point in direction, None if that is outside of the maze
+    pub fn neighbor(&self, point: Point3D, direction: Direction3D) -> Option<Point3D> {
+        let Point3D { x, y, z } = point;
+
+        let neighbor = match direction {
+            Direction3D::North => Point3D { x, y: y.wrapping_sub(1), z },
+            Direction3D::South => Point3D { x, y: y + 1, z },
+            Direction3D::East => Point3D { x: x + 1, y, z },
+            Direction3D::West => Point3D { x: x.wrapping_sub(1), y, z },
+            Direction3D::Up => Point3D { x, y, z: z + 1 },
+            Direction3D::Down => Point3D { x, y, z: z.wrapping_sub(1) },
+        };
+
+        if neighbor.x < self.width && neighbor.y < self.height && neighbor.z < self.depth { Some(neighbor) } else { None }
+    }
+
+    //Text drawing of a single floor. Stairs are marked with /\ going up, \/ going down and <> going both ways, and doors with [].
+    //A floor the maze doesn't have is drawn as an empty string.
+    pub fn floor_string(&self, floor: usize) -> String {
+        self.floor_string_with(floor, &|_| None)
+    }
+
+    //Same as floor_string, but contents can replace what is drawn inside a cell with two other characters
+    pub fn floor_string_with(&self, floor: usize, contents: &dyn Fn(Point3D) -> Option<String>) -> String {
+        self.floor_rows(floor, &|_| CellView::Visible, contents).into_iter().map(|row| row + "\n").collect()
+    }
+
+    //Same as floor_string_with, but drawn as much as view lets the player know of it.
+    //Hidden cells are covered by ░░, and walls only seen before are drawn as ▓▓.
+    //A wall is drawn as well as the best known cell next to it.
+    pub fn floor_string_fogged(&self, floor: usize, view: &dyn Fn(Point3D) -> CellView, contents: &dyn Fn(Point3D) -> Option<String>) -> String {
+        self.floor_rows(floor, view, contents).into_iter().map(|row| row + "\n").collect()
+    }
+
+    //Cells on the same floor as point that can be seen from it, as limited by options
+    pub fn visible_from(&self, point: Point3D, options: &SightOptions) -> Vec<Point3D> {
+        let floor = point.z;
+        if floor >= self.depth { return Vec::new(); }
+
+        let blocked = |from: Point, direction: Direction| {
+            let direction = match direction {
+                Direction::North => Direction3D::North,
+                Direction::South => Direction3D::South,
+                Direction::East => Direction3D::East,
+                Direction::West => Direction3D::West,
+            };
+
+            self.cells[floor][from.x][from.y].wall(direction).active
+        };
+
+        maze_visibility::visible_cells(self.width, self.height, Point { x: point.x, y: point.y }, options, &blocked)
+            .into_iter()
+            .map(|visible| Point3D { x: visible.x, y: visible.y, z: floor })
+            .collect()
+    }
+
+    //Lines of the text drawing of floor, all of the same length. There are none if the floor is outside the maze.
+    fn floor_rows(&self, floor: usize, view: &dyn Fn(Point3D) -> CellView, contents: &dyn Fn(Point3D) -> Option<String>) -> Vec<String> {
+        if self.width == 0 || self.height == 0 || floor >= self.depth { return Vec::new(); }
+
+        //How well the piece of wall touching the cells at x and y, give or take one, is known.
+        //Cells outside the floor are passed in as wrapped around usize values and skipped.
+        let known = |xs: [usize; 2], ys: [usize; 2]| {
+            xs.iter().flat_map(|x| ys.iter().map(move |y| (*x, *y)))
+                .filter(|(x, y)| *x < self.width && *y < self.height)
+                .map(|(x, y)| view(Point3D { x, y, z: floor }))
+                .max()
+                .unwrap_or(CellView::Hidden)
+        };
+
+        let block = |active: bool, known: CellView| match (known, active) {
+            (CellView::Hidden, _) => "░░",
+            (CellView::Remembered, true) => "▓▓",
+            (CellView::Visible, true) => "██",
+            (_, false) => "  ",
+        };
+
+        //Doors standing in passages are drawn as []
+        let wall = |wall: &MazeWall, known: CellView| {
+            if known != CellView::Hidden && !wall.active && wall.door.is_some() { "[]" } else { block(wall.active, known) }
+        };
+
+        let cells = &self.cells[floor];
+        let mut rows = Vec::new();
+
+        for y in 0..self.height {
+            let above = y.wrapping_sub(1);
+
+            //Top Row
+            let mut top = String::new();
+            for (x, column) in cells.iter().enumerate() {
+                top += block(true, known([x.wrapping_sub(1), x], [above, y]));
+                top += wall(&column[y].wall(Direction3D::North), known([x, x], [above, y]));
+            }
+            top += block(true, known([self.width - 1, self.width - 1], [above, y]));
+            rows.push(top);
+
+            //Middle Row
+            let mut middle = String::new();
+            for (x, column) in cells.iter().enumerate() {
+                let cell = &column[y];
+                let point = Point3D { x, y, z: floor };
+                middle += wall(&cell.wall(Direction3D::West), known([x.wrapping_sub(1), x], [y, y]));
+
+                if view(point) == CellView::Hidden {
+                    middle += "░░";
+                    continue;
+                }
+
+                match contents(point) {
+                    Some(value) => middle += &value,
+                    None => middle += match (cell.has_stairs(Direction3D::Up), cell.has_stairs(Direction3D::Down)) {
+                        (true, true) => "<>",
+                        (true, false) => "/\\",
+                        (false, true) => "\\/",
+                        (false, false) => "  ",
+                    },
+                }
+            }
+            middle += wall(&cells[self.width - 1][y].wall(Direction3D::East), known([self.width - 1, self.width - 1], [y, y]));
+            rows.push(middle);
+        }
+
+        //Bottom Row
+        let last = self.height - 1;
+        let mut bottom = String::new();
+        for (x, column) in cells.iter().enumerate() {
+            bottom += block(true, known([x.wrapping_sub(1), x], [last, last]));
+            bottom += wall(&column[last].wall(Direction3D::South), known([x, x], [last, last]));
+        }
+        bottom += block(true, known([self.width - 1, self.width - 1], [last, last]));
+        rows.push(bottom);
+
+        rows
+    }
+}
+
+impl Regenerate for Maze3D {
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>] { &self.walls }
+
+    //Same as regenerate_with_seed, but reports every step of the algorithm to trace
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep)) {
+        self.seed = seed;
+        self.reset();
+        self.maze_algorithm.generate_traced(&*self, &mut StdRng::seed_from_u64(seed), trace);
+    }
+}
+
+impl MazeGrid for Maze3D {
+    fn cell_count(&self) -> usize { self.width * self.height * self.depth }
+
+    fn is_enabled(&self, _cell: usize) -> bool { true }
+
+    //Stairs between floors don't run along either axis
+    fn axis(&self, cell: usize, neighbor: usize) -> Option<Axis> {
+        let one = self.point(cell);
+        let two = self.point(neighbor);
+
+        if one.z != two.z { None }
+        else if one.y == two.y { Some(Axis::Horizontal) }
+        else { Some(Axis::Vertical) }
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        let point = self.point(cell);
+
+        Direction3D::ALL.iter()
+            .filter_map(|direction| {
+                self.neighbor(point, *direction)
+                    .map(|neighbor| (self.cell_id(neighbor), Rc::clone(self.cell(point).wall_mut(*direction))))
+            })
+            .collect()
+    }
+
+    //The entrance is the top left cell of the bottom floor and the exit is the bottom right cell of the top floor
+    fn exits(&self) -> Option<(usize, usize)> {
+        if self.cell_count() == 0 { return None; }
+        Some((0, self.cell_count() - 1))
+    }
+
+    //Opens the west wall of the entrance and the east wall of the exit
+    fn open_exits(&self) {
+        if let Some((entrance, exit)) = self.exits() {
+            self.cell(self.point(entrance)).wall_mut(Direction3D::West).borrow_mut().active = false;
+            self.cell(self.point(exit)).wall_mut(Direction3D::East).borrow_mut().active = false;
+        }
+    }
+}
+
+//All floors side by side, from the bottom floor on the left to the top floor on the right, one cell apart
+impl MazeDrawing for Maze3D {
+    fn extent(&self) -> (f64, f64) {
+        let width = (self.width * self.depth + self.depth.saturating_sub(1)) as f64;
+        (width, self.height as f64)
+    }
+
+    fn wall_strokes(&self) -> Vec<Stroke> {
+        let mut strokes = Vec::new();
+        let line = |from: (f64, f64), to: (f64, f64)| Stroke::Line { from, to };
+
+        for z in 0..self.depth {
+            let offset = (z * (self.width + 1)) as f64;
+
+            for x in 0..self.width {
+                for y in 0..self.height {
+                    let cell = &self.cells[z][x][y];
+                    let (left, top) = (offset + x as f64, y as f64);
+
+                    if cell.wall(Direction3D::North).active {
+                        strokes.push(line((left, top), (left + 1.0, top)));
+                    }
+                    if cell.wall(Direction3D::West).active {
+                        strokes.push(line((left, top), (left, top + 1.0)));
+                    }
+                    if x == self.width - 1 && cell.wall(Direction3D::East).active {
+                        strokes.push(line((left + 1.0, top), (left + 1.0, top + 1.0)));
+                    }
+                    if y == self.height - 1 && cell.wall(Direction3D::South).active {
+                        strokes.push(line((left, top + 1.0), (left + 1.0, top + 1.0)));
+                    }
+
+                    //Stairs are marked with a chevron pointing the way they go
+                    let centre = (left + 0.5, top + 0.5);
+                    let shift = if cell.has_stairs(Direction3D::Up) && cell.has_stairs(Direction3D::Down) { 0.15 } else { 0.0 };
+
+                    if cell.has_stairs(Direction3D::Up) {
+                        let tip = (centre.0, centre.1 - 0.15 - shift);
+                        strokes.push(line((centre.0 - 0.2, tip.1 + 0.2), tip));
+                        strokes.push(line(tip, (centre.0 + 0.2, tip.1 + 0.2)));
+                    }
+                    if cell.has_stairs(Direction3D::Down) {
+                        let tip = (centre.0, centre.1 + 0.15 + shift);
+                        strokes.push(line((centre.0 - 0.2, tip.1 - 0.2), tip));
+                        strokes.push(line(tip, (centre.0 + 0.2, tip.1 - 0.2)));
+                    }
+                }
+            }
+        }
+
+        strokes
+    }
+}
+
+//Every floor side by side, from the bottom floor on the left to the top floor on the right
+impl fmt::Display for Maze3D {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        //Exception case
+        if self.cell_count() == 0 { return write!(f, "Maze is empty."); }
+
+        let floors: Vec<Vec<String>> = (0..self.depth).map(|floor| self.floor_rows(floor, &|_| CellView::Visible, &|_| None)).collect();
+        let mut maze_str = String::new();
+
+        for row in 0..floors[0].len() {
+            let line: Vec<&str> = floors.iter().map(|floor| floor[row].as_str()).collect();
+            maze_str += &line.join("  ");
+            maze_str += "\n";
+        }
+
+        write!(f, "{}", maze_str)
+    }
+}
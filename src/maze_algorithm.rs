@@ -2,33 +2,193 @@ extern crate rand;
 
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 
-use super::maze_cell::MazeCell;
+use rand::{Rng, RngCore};
+
+use super::maze_grid::MazeGrid;
 use super::maze_wall::MazeWall;
+use super::maze_config::{DepthFirstSearchConfig, PrimsConfig, KruskalsConfig, FrontierPolicy};
+use super::maze_error::MazeError;
+
+//A single event emitted while a maze is being generated or solved, in terms of the grid's cell numbers.
+//Used to animate a run.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MazeStep {
+    Visit(usize),           //Cell was reached by the algorithm
+    Frontier(usize),        //Cell was queued up to be reached later
+    Carve(usize, usize),    //Wall between the two cells was removed
+    Path(usize),            //Cell is part of the solution path
+}
 
+//Every random choice an algorithm makes comes from rng, so a seeded rng always gives the same maze
 pub trait MazeAlgorithm {
-    fn generate(&self, cells: &mut Vec<Vec<MazeCell>>, walls: &mut Vec<Rc<RefCell<MazeWall>>>);
+    fn generate(&self, grid: &dyn MazeGrid, rng: &mut dyn RngCore) {
+        self.generate_traced(grid, rng, &mut |_| {});
+    }
+
+    //Same as generate, but reports every step of the algorithm to trace as it happens
+    fn generate_traced(&self, grid: &dyn MazeGrid, rng: &mut dyn RngCore, trace: &mut dyn FnMut(MazeStep));
 }
 
-pub struct DepthFirstSearch;
+//Picks a random cell that isn't disabled by the maze's mask
+fn random_enabled_cell(grid: &dyn MazeGrid, rng: &mut dyn RngCore) -> Option<usize> {
+    let enabled: Vec<usize> = (0..grid.cell_count()).filter(|cell| grid.is_enabled(*cell)).collect();
 
-impl MazeAlgorithm for DepthFirstSearch {
-    fn generate(&self, cells: &mut Vec<Vec<MazeCell>>, _walls: &mut Vec<Rc<RefCell<MazeWall>>>) {
-        #[derive(Clone, Copy, Debug)]
-        enum NextPosition {
-            Up,
-            Down,
-            Left,
-            Right,
-            Backtrack,
+    if enabled.is_empty() { return None; }
+    Some(enabled[rng.gen_range(0..enabled.len())])
+}
+
+//The configured start cell if it's an enabled cell of grid, a random enabled cell otherwise
+fn start_cell(grid: &dyn MazeGrid, start: Option<usize>, rng: &mut dyn RngCore) -> Option<usize> {
+    match start {
+        Some(cell) if cell < grid.cell_count() && grid.is_enabled(cell) => Some(cell),
+        _ => random_enabled_cell(grid, rng),
+    }
+}
+
+//Picks an index into weights at random, in proportion to its weight. When all the weights are the same this is a plain
+//uniform pick, so an algorithm with the default config makes exactly the same random choices as it always has.
+fn choose_weighted(weights: &[f64], rng: &mut dyn RngCore) -> usize {
+    let total: f64 = weights.iter().sum();
+
+    if total <= 0.0 || weights.windows(2).all(|pair| pair[0] == pair[1]) {
+        return rng.gen_range(0..weights.len());
+    }
+
+    let mut target = rng.gen::<f64>() * total;
+    for (index, weight) in weights.iter().enumerate() {
+        if target < *weight { return index; }
+        target -= weight;
+    }
+
+    //Rounding can leave a sliver past the last weight
+    weights.iter().rposition(|weight| *weight > 0.0).unwrap_or(0)
+}
+
+//Builds a visited list for the algorithms where cells disabled by the mask are already marked as visited,
+//so they are never carved into
+fn initial_visited(grid: &dyn MazeGrid) -> Vec<bool> {
+    (0..grid.cell_count()).map(|cell| !grid.is_enabled(cell)).collect()
+}
+
+//Finds a cell that hasn't been visited yet. A mask can split the maze into separate regions,
+//so the algorithms use this to carry on in a region they haven't reached.
+fn unvisited_cell(visited: &[bool]) -> Option<usize> {
+    visited.iter().position(|value| !value)
+}
+
+//True if the algorithms may carve the wall, i.e. it isn't locked
+fn carvable(wall: &Rc<RefCell<MazeWall>>) -> bool {
+    !wall.borrow().locked
+}
+
+//True if the wall is locked open, making a passage the maze has to have
+fn forced_open(wall: &Rc<RefCell<MazeWall>>) -> bool {
+    let wall = wall.borrow();
+    wall.locked && !wall.active
+}
+
+//Every cell joined to cell by forced passages, starting with cell itself. The algorithms take in the whole group
+//whenever they reach one of its cells, so the forced passages end up in the maze without closing a loop.
+fn forced_group(grid: &dyn MazeGrid, cell: usize) -> Vec<usize> {
+    let mut group = vec![cell];
+    let mut index = 0;
+
+    while index < group.len() {
+        for (neighbor, wall) in grid.neighbors(group[index]) {
+            if forced_open(&wall) && !group.contains(&neighbor) {
+                group.push(neighbor);
+            }
+        }
+        index += 1;
+    }
+
+    group
+}
+
+//Finds the set a cell is in, flattening the path to it on the way.
+//parent holds a parent for every cell, and a cell that is its own parent stands for its set.
+pub fn find_set(parent: &mut [usize], cell: usize) -> usize {
+    let mut root = cell;
+    while parent[root] != root {
+        root = parent[root];
+    }
+
+    let mut current = cell;
+    while parent[current] != root {
+        let next = parent[current];
+        parent[current] = root;
+        current = next;
+    }
+
+    root
+}
+
+//Checks that the locked walls of grid still leave room for a perfect maze. Fails if the forced passages make a loop,
+//or if the forced walls cut off cells that could otherwise be reached from each other.
+pub fn check_locked_walls(grid: &dyn MazeGrid) -> Result<(), MazeError> {
+    let mut forced: Vec<usize> = (0..grid.cell_count()).collect();
+    let mut open: Vec<usize> = (0..grid.cell_count()).collect();
+    let mut locked_walls = Vec::new();
+
+    for cell in 0..grid.cell_count() {
+        if !grid.is_enabled(cell) { continue; }
+
+        for (neighbor, wall) in grid.neighbors(cell) {
+            if cell >= neighbor { continue; }
+
+            if forced_open(&wall) {
+                let one = find_set(&mut forced, cell);
+                let two = find_set(&mut forced, neighbor);
+                if one == two {
+                    return Err(MazeError::ForcedLoop(cell, neighbor));
+                }
+                forced[one] = two;
+            }
+
+            if carvable(&wall) || !wall.borrow().active {
+                let one = find_set(&mut open, cell);
+                let two = find_set(&mut open, neighbor);
+                open[one] = two;
+            }
+            else {
+                locked_walls.push((cell, neighbor));
+            }
         }
+    }
 
-        #[derive(Clone, Copy, Debug)]
-        struct Point {
-            pub x: usize,
-            pub y: usize,
+    //Every forced wall has cells on both sides, so if they can't reach each other any more the wall split the maze
+    for (cell, neighbor) in locked_walls {
+        if find_set(&mut open, cell) != find_set(&mut open, neighbor) {
+            return Err(MazeError::WalledOff(cell, neighbor));
         }
-        
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct DepthFirstSearch {
+    config: DepthFirstSearchConfig,
+}
+
+impl DepthFirstSearch {
+    //Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: DepthFirstSearchConfig) -> Self {
+        Self { config }
+    }
+
+    //Getters
+    pub fn config(&self) -> &DepthFirstSearchConfig { &self.config }
+}
+
+impl MazeAlgorithm for DepthFirstSearch {
+    fn generate_traced(&self, grid: &dyn MazeGrid, rng: &mut dyn RngCore, trace: &mut dyn FnMut(MazeStep)) {
         //Depth first search algorithm
         //1. Randomly choose an initial cell, mark it as visited, add it to stack
         //2. Randomly choose the next cell from the cell's unvisited neighbors
@@ -38,107 +198,118 @@ impl MazeAlgorithm for DepthFirstSearch {
         //5. Backtrack on the path that is in the stack until you reach a cell with an unvisted neighbor.
         //6. Continue the process from there.
         //7. When the algorithm backtracks back to the inital cell, the maze is complete.
-    
-        //Picks a random cell on the map
-        let width = cells.len();
-        let height = cells[0].len();
-
-        let initial = Point { 
-            x: rand::random::<usize>() % width,
-            y: rand::random::<usize>() % height,
+
+        //Picks the start cell, or a random cell on the map
+        let mut current = match start_cell(grid, self.config.start, rng) {
+            Some(cell) => cell,
+            None => return,
         };
 
-        let mut current = initial;
+        let mut visited = initial_visited(grid);
 
-        let mut visited = vec![vec![false; height]; width];
-        let mut backtracked = vec![vec![false; height]; width];
+        let mut stack: Vec<usize> = Vec::new();
 
-        let mut stack: Vec<Point> = Vec::new();
+        //Marks cell as visited along with every cell a forced passage joins it to.
+        //Those cells go on the stack, so the search backtracks through them and carries on from each one.
+        let reach = |cell: usize, visited: &mut Vec<bool>, stack: &mut Vec<usize>, trace: &mut dyn FnMut(MazeStep)| {
+            for member in forced_group(grid, cell) {
+                visited[member] = true;
+                trace(MazeStep::Visit(member));
+                if member != cell { stack.push(member); }
+            }
+        };
 
-        visited[initial.x][initial.y] = true;
+        reach(current, &mut visited, &mut stack, trace);
+
+        let config = &self.config;
 
         //Determines where the next cell is in the depth-first search algorithm
-        let next_cell_position = |current: &Point, visited: &Vec<Vec<bool>>| -> NextPosition {
-            let mut random_cell_list: Vec<NextPosition> = Vec::new();
-    
-            if (current.x > 0) && (!visited[current.x - 1][current.y])                     { random_cell_list.push(NextPosition::Left); }  //If it can move to the Above Cell
-            if (current.x < cells.len() - 1) && (!visited[current.x + 1][current.y])       { random_cell_list.push(NextPosition::Right); } //If it can move to the Below Cell
-            if (current.y > 0) && (!visited[current.x][current.y - 1])                     { random_cell_list.push(NextPosition::Up); }    //If it can move to the Left Cell
-            if (current.y < cells[0].len() - 1) && (!visited[current.x][current.y + 1])    { random_cell_list.push(NextPosition::Down); }  //If it can move to the Right Cell
-    
-            //Randomly selects a direction to go with the algorithm
-            //If it cannot move anywhere, then it is a deadend and needs to backtrack
-            if random_cell_list.len() > 0 {
-                random_cell_list[rand::random::<usize>() % random_cell_list.len()]
-            } 
-            else {
-                NextPosition::Backtrack
+        //If it cannot move anywhere, then it is a deadend and needs to backtrack
+        let next_cell = |current: usize, previous: Option<usize>, visited: &Vec<bool>, rng: &mut dyn RngCore| -> Option<(usize, Rc<RefCell<MazeWall>>)> {
+            let mut random_cell_list: Vec<(usize, Rc<RefCell<MazeWall>>)> = grid.neighbors(current).into_iter()
+                .filter(|(neighbor, wall)| !visited[*neighbor] && carvable(wall))
+                .collect();
+
+            if random_cell_list.is_empty() { return None; }
+
+            //Going straight means carrying on along the axis the search came in on
+            let heading = previous.and_then(|previous| grid.axis(previous, current));
+            let straight: Vec<bool> = random_cell_list.iter()
+                .map(|(neighbor, _)| heading.is_some() && grid.axis(current, *neighbor) == heading)
+                .collect();
+            let can_choose = straight.contains(&true) && straight.contains(&false);
+
+            //The chances are only rolled when they're set, so the default config uses the rng the same way as before
+            if can_choose && config.straightness > 0.0 && rng.gen_bool(config.straightness) {
+                random_cell_list = random_cell_list.into_iter().zip(straight.iter()).filter(|(_, straight)| **straight).map(|(cell, _)| cell).collect();
             }
+            else if can_choose && config.turn_probability > 0.0 && rng.gen_bool(config.turn_probability) {
+                random_cell_list = random_cell_list.into_iter().zip(straight.iter()).filter(|(_, straight)| !**straight).map(|(cell, _)| cell).collect();
+            }
+
+            //Randomly selects a direction to go with the algorithm, weighted by the bias
+            let weights: Vec<f64> = random_cell_list.iter().map(|(neighbor, _)| config.bias.weight(grid.axis(current, *neighbor))).collect();
+            let index = choose_weighted(&weights, rng);
+            Some(random_cell_list.swap_remove(index))
         };
 
         loop {
-            match next_cell_position(&current, &visited) {
-                NextPosition::Up => {
-                    cells[current.x][current.y].top_wall_mut().borrow_mut().active = false;
-
-                    stack.push(current.clone());
-                    current.y -= 1;
-                    visited[current.x][current.y] = true;
-                }
-    
-                NextPosition::Down => {
-                    cells[current.x][current.y].bottom_wall_mut().borrow_mut().active = false;
+            match next_cell(current, stack.last().copied(), &visited, rng) {
+                Some((next, wall)) => {
+                    wall.borrow_mut().active = false;
 
                     stack.push(current);
-                    current.y += 1;
-                    visited[current.x][current.y] = true;
-                }
-    
-                NextPosition::Left => {
-                    cells[current.x][current.y].left_wall_mut().borrow_mut().active = false;
+                    trace(MazeStep::Carve(current, next));
 
-                    stack.push(current);
-                    current.x -= 1;
-                    visited[current.x][current.y] = true;
+                    current = next;
+                    reach(current, &mut visited, &mut stack, trace);
                 }
-    
-                NextPosition::Right => {
-                    cells[current.x][current.y].right_wall_mut().borrow_mut().active = false;
 
-                    stack.push(current);
-                    current.x += 1;
-                    visited[current.x][current.y] = true;
-                }
-    
-                NextPosition::Backtrack => {
-                    backtracked[current.x][current.y] = true;
-                    if let Some(value) = stack.pop() {
-                        current = value;
+                None => {
+                    match stack.pop() {
+                        Some(value) => current = value,
+
+                        //If the algorithm backtracked all the way to the initial cell, then it's done with this region.
+                        //Carry on from any cell that a mask kept out of reach, otherwise the maze is done.
+                        None => match unvisited_cell(&visited) {
+                            Some(cell) => {
+                                current = cell;
+                                reach(current, &mut visited, &mut stack, trace);
+                            }
+
+                            None => break,
+                        },
                     }
                 }
             }
-    
-            
-            //If the algorithm backtracked all the way to the initial cell, then it's done.
-            if backtracked[initial.x][initial.y] { break; }
         }
 
         //Set up maze exits
-        cells[0][0].left_wall_mut().borrow_mut().active = false;
-        cells[width - 1][height - 1].right_wall_mut().borrow_mut().active = false;
+        grid.open_exits();
     }
 }
 
-pub struct PrimsAlgorithm;
+#[derive(Default)]
+pub struct PrimsAlgorithm {
+    config: PrimsConfig,
+}
+
+impl PrimsAlgorithm {
+    //Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: PrimsConfig) -> Self {
+        Self { config }
+    }
+
+    //Getters
+    pub fn config(&self) -> &PrimsConfig { &self.config }
+}
 
 impl MazeAlgorithm for PrimsAlgorithm {
-    fn generate(&self, cells: &mut Vec<Vec<MazeCell>>, _walls: &mut Vec<Rc<RefCell<MazeWall>>>) {
-        #[derive(Clone, Copy, Debug)]
-        struct Point {
-            pub x: usize,
-            pub y: usize,
-        }
-        
+    fn generate_traced(&self, grid: &dyn MazeGrid, rng: &mut dyn RngCore, trace: &mut dyn FnMut(MazeStep)) {
         /*
         1. Start with a grid full of walls.
         2. Pick a cell, mark it as part of the maze. Add the walls of the cell to the wall list.
@@ -149,147 +320,194 @@ impl MazeAlgorithm for PrimsAlgorithm {
             2. Remove the wall from the list.
         */
 
-        let width = cells.len();
-        let height = cells[0].len();
-
-        let rand = Point {
-            x: rand::random::<usize>() % width,
-            y: rand::random::<usize>() % height,
-        };
-
-        let mut visited = vec![vec![false; height]; width];
-        visited[rand.x][rand.y] = true;
-
-        let mut wall_list: Vec<&Rc<RefCell<MazeWall>>> = Vec::new();
-
-        //Adds indexes that aren't border walls
-        if rand.x != 0 { 
-            wall_list.push(cells[rand.x][rand.y].left_wall_mut());
-        }
-        if rand.x != width - 1 { 
-            wall_list.push(cells[rand.x][rand.y].right_wall_mut());
-        }
-        if rand.y != 0 { 
-            wall_list.push(cells[rand.x][rand.y].top_wall_mut());
-        }
-        if rand.y != height - 1 { 
-            wall_list.push(cells[rand.x][rand.y].bottom_wall_mut());
+        //Every wall in the list remembers the visited cell it was added from and the cell on its other side
+        struct ListedWall {
+            from: usize,
+            to: usize,
+            wall: Rc<RefCell<MazeWall>>,
         }
 
-        while wall_list.len() > 0 {
-            let random_index = rand::random::<usize>() % wall_list.len();
+        let mut visited = initial_visited(grid);
+        let mut wall_list: VecDeque<ListedWall> = VecDeque::new();
+        let mut start = start_cell(grid, self.config.start, rng);
 
-            let mut cell_one = Point { x: usize::MAX, y: usize::MAX, };
-            let mut cell_two = Point { x: usize::MAX, y: usize::MAX, };
+        let frontier = self.config.frontier;
 
-            for i in 0..width {
-                for j in 0..height {
-                    if (i == cell_one.x) && (j == cell_one.y) { continue; }
-                    
-                    if Rc::ptr_eq(wall_list[random_index], cells[i][j].bottom_wall_mut()) {
-                        cell_one = Point { x: i, y: j, };
-                        cell_two = Point { x: i, y: j + 1, };
-                        break;
-                    }
-                    else if Rc::ptr_eq(wall_list[random_index], cells[i][j].right_wall_mut()) {
-                        cell_one = Point { x: i, y: j, };
-                        cell_two = Point { x: i + 1, y: j, };
-                        break;
-                    }
-                    else if Rc::ptr_eq(wall_list[random_index], cells[i][j].left_wall_mut()) {
-                        cell_one = Point { x: i, y: j, };
-                        cell_two = Point { x: i - 1, y: j, };
-                        break;
-                    }
-                    else if Rc::ptr_eq(wall_list[random_index], cells[i][j].top_wall_mut()) {
-                        cell_one = Point { x: i, y: j, };
-                        cell_two = Point { x: i, y: j - 1, };
-                        break;
-                    }
-                }
-
-                if cell_two.x != usize::MAX { break; }
+        //Marks cell as part of the maze and adds its walls to the wall list.
+        //from is the cell it was carved in from, if there is one.
+        let mut add_neighboring_walls = |cell: usize, from: Option<usize>, visited: &mut Vec<bool>, wall_list: &mut VecDeque<ListedWall>, rng: &mut dyn RngCore| {
+            if let Some(from) = from {
+                trace(MazeStep::Carve(from, cell));
             }
 
-            let mut add_neighboring_walls = |cell: Point, visited: &mut Vec<Vec<bool>>| {
-                wall_list[random_index].borrow_mut().active = false;
-                visited[cell.x][cell.y] = true;
-
-                let mut new_walls: Vec<&Rc<RefCell<MazeWall>>> = Vec::new();
+            //Cells joined to it by forced passages become part of the maze along with it
+            let group = forced_group(grid, cell);
+            for member in group.iter() {
+                visited[*member] = true;
+                trace(MazeStep::Visit(*member));
+            }
 
-                if cell.x != 0 && !Rc::ptr_eq(wall_list[random_index], cells[cell.x][cell.y].left_wall_mut()) {
-                    new_walls.push(cells[cell.x][cell.y].left_wall_mut());
-                }
+            let first_added = wall_list.len();
+            for member in group {
+                for (neighbor, wall) in grid.neighbors(member) {
+                    if visited[neighbor] || !carvable(&wall) { continue; }
 
-                if cell.x != width - 1 && !Rc::ptr_eq(wall_list[random_index], cells[cell.x][cell.y].right_wall_mut()) {
-                    new_walls.push(cells[cell.x][cell.y].right_wall_mut());
+                    trace(MazeStep::Frontier(neighbor));
+                    wall_list.push_back(ListedWall { from: member, to: neighbor, wall });
                 }
+            }
 
-                if cell.y != 0 && !Rc::ptr_eq(wall_list[random_index], cells[cell.x][cell.y].top_wall_mut()) {
-                    new_walls.push(cells[cell.x][cell.y].top_wall_mut());
+            //The newest and oldest policies always take the same end of the list,
+            //so the cell's walls are shuffled to keep its neighbors from always being taken in the same order
+            if frontier != FrontierPolicy::Random {
+                let added = wall_list.len() - first_added;
+                for i in (1..added).rev() {
+                    let random = rng.gen_range(0..=i);
+                    wall_list.swap(first_added + i, first_added + random);
                 }
+            }
+        };
 
-                if cell.y != height - 1 && !Rc::ptr_eq(wall_list[random_index], cells[cell.x][cell.y].bottom_wall_mut()) {
-                    new_walls.push(cells[cell.x][cell.y].bottom_wall_mut());
-                }
+        //A mask can split the maze into separate regions, each one is grown from its own starting cell
+        while let Some(rand) = start {
+            add_neighboring_walls(rand, None, &mut visited, &mut wall_list, rng);
 
-                wall_list.append(&mut new_walls);
-            };
+            while !wall_list.is_empty() {
+                let taken = match frontier {
+                    FrontierPolicy::Random => {
+                        let random_index = if self.config.bias.is_uniform() {
+                            rng.gen_range(0..wall_list.len())
+                        }
+                        else {
+                            let weights: Vec<f64> = wall_list.iter().map(|listed| self.config.bias.weight(grid.axis(listed.from, listed.to))).collect();
+                            choose_weighted(&weights, rng)
+                        };
 
-            if !visited[cell_one.x][cell_one.y] {
-                add_neighboring_walls(cell_one, &mut visited);
-            }
-            else if !visited[cell_two.x][cell_two.y] {
-                add_neighboring_walls(cell_two, &mut visited);
+                        wall_list.swap_remove_back(random_index)
+                    }
+                    FrontierPolicy::Newest => wall_list.pop_back(),
+                    FrontierPolicy::Oldest => wall_list.pop_front(),
+                };
+
+                let listed = match taken {
+                    Some(value) => value,
+                    None => break,
+                };
+
+                //The cell the wall was added from is always visited, so only the other one needs checking
+                if !visited[listed.to] {
+                    listed.wall.borrow_mut().active = false;
+                    add_neighboring_walls(listed.to, Some(listed.from), &mut visited, &mut wall_list, rng);
+                }
             }
 
-            wall_list.remove(random_index);
+            start = unvisited_cell(&visited);
         }
 
         //Set up maze exits
-        cells[0][0].left_wall_mut().borrow_mut().active = false;
-        cells[width - 1][height - 1].right_wall_mut().borrow_mut().active = false;
+        grid.open_exits();
     }
 }
 
-pub struct KruskalsAlgorithm;
+#[derive(Default)]
+pub struct KruskalsAlgorithm {
+    config: KruskalsConfig,
+}
+
+impl KruskalsAlgorithm {
+    //Constructor
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: KruskalsConfig) -> Self {
+        Self { config }
+    }
+
+    //Getters
+    pub fn config(&self) -> &KruskalsConfig { &self.config }
+}
 
 impl MazeAlgorithm for KruskalsAlgorithm {
-    fn generate(&self, cells: &mut Vec<Vec<MazeCell>>, walls: &mut Vec<Rc<RefCell<MazeWall>>>) {
+    fn generate_traced(&self, grid: &dyn MazeGrid, rng: &mut dyn RngCore, trace: &mut dyn FnMut(MazeStep)) {
         //Step 1: Setup
-        let width = cells.len();
-        let height = cells[0].len();
+        //Every wall between two enabled cells, along with the cells it divides
+        let mut walls: Vec<(usize, usize, Rc<RefCell<MazeWall>>)> = Vec::new();
+
+        for cell in 0..grid.cell_count() {
+            if !grid.is_enabled(cell) { continue; }
+
+            for (neighbor, wall) in grid.neighbors(cell) {
+                //Each wall is seen from both of its cells, only keep it once
+                if cell < neighbor {
+                    walls.push((cell, neighbor, wall));
+                }
+            }
+        }
 
         let n = walls.len();
-        let mut rand_nums: Vec<usize> = Vec::with_capacity(n);
         let mut random: usize;
 
-        //Step 1.1: create a vector of numbers in a random order        
+        //Step 1.1: put the walls in a random order
 
-        //Populate vector with numbers from 0 to n - 1
-        for i in 0..n {
-            rand_nums.push(i);
+        if self.config.bias.is_uniform() {
+            //Fisher-Yates shuffle for randomization
+            for i in 0..n.saturating_sub(1) {
+                //j is random int i <= random < n
+                random = rng.gen_range(i..n);
+                //exchange a[i] and a[j]
+                walls.swap(i, random);
+            }
         }
-
-        //Fisher-Yates shuffle for randomization
-        for i in 0..(n - 1) {
-            //j is random int i <= random < n
-            random = (rand::random::<usize>() % (n - i)) + i;
-            //exchange a[i] and a[j]
-            //cannot use mem::swap because cannot borrow same vector twice
-            let tmp = rand_nums[i];
-            rand_nums[i] = rand_nums[random];
-            rand_nums[random] = tmp;
+        else {
+            //Weighted shuffle: every wall gets a random key that tends to be larger the more weight it has,
+            //and the walls are sorted by key. Walls with no weight go last, so the maze still gets connected.
+            let mut keyed: Vec<(f64, _)> = walls.into_iter()
+                .map(|wall| {
+                    let weight = self.config.bias.weight(grid.axis(wall.0, wall.1));
+                    let key = if weight > 0.0 { rng.gen::<f64>().powf(1.0 / weight) } else { -1.0 };
+                    (key, wall)
+                })
+                .collect();
+
+            keyed.sort_by(|one, two| two.0.total_cmp(&one.0));
+            walls = keyed.into_iter().map(|(_, wall)| wall).collect();
         }
 
-        //Step 1.2: create a vector for each cell, containing only that one cell
-        let mut cell_sets: Vec<Vec<&MazeCell>> = Vec::with_capacity(width * height);
+        //Step 1.2: create a set for each cell, containing only that one cell.
+        //set_of keeps track of which set each cell is in.
+        //Cells disabled by the mask never join a set, so walls next to them are never broken.
+        let mut cell_sets: Vec<Vec<usize>> = Vec::with_capacity(grid.cell_count());
+        let mut set_of: Vec<usize> = Vec::with_capacity(grid.cell_count());
+        let mut set_count = 0;
+
+        for cell in 0..grid.cell_count() {
+            cell_sets.push(vec![cell]);
+            set_of.push(cell);
+            if grid.is_enabled(cell) { set_count += 1; }
+        }
 
-        for i in 0..width {
-            for j in 0..height {
-                cell_sets.push(Vec::new());
-                cell_sets[(i * height) + j].push(&cells[i][j]);
+        //Sets are combined by moving the smaller set into the larger one
+        let join = |set_one: usize, set_two: usize, cell_sets: &mut Vec<Vec<usize>>, set_of: &mut Vec<usize>| {
+            let (into, from) = if cell_sets[set_one].len() >= cell_sets[set_two].len() { (set_one, set_two) } else { (set_two, set_one) };
+            let mut tmp = std::mem::take(&mut cell_sets[from]);
+            for cell in tmp.iter() {
+                set_of[*cell] = into;
             }
+            cell_sets[into].append(&mut tmp);
+        };
+
+        //Step 1.3: walls that are already open before generation starts, like the crossings of a weave maze
+        //or forced passages, join their cells up front. Otherwise the algorithm could connect those cells a second way and make a loop.
+        for (cell_one, cell_two, wall) in walls.iter() {
+            if wall.borrow().active { continue; }
+
+            let set_one = set_of[*cell_one];
+            let set_two = set_of[*cell_two];
+            if set_one == set_two { continue; }
+
+            join(set_one, set_two, &mut cell_sets, &mut set_of);
+            set_count -= 1;
         }
 
         /*
@@ -302,58 +520,27 @@ impl MazeAlgorithm for KruskalsAlgorithm {
 
         //Step 2
         //for each wall that exists
-        let mut set_one_index: Option<usize>;
-        let mut set_two_index: Option<usize>;
-
-        for i in rand_nums.iter() {
-            if cell_sets.len() == 1 { break; }
+        for (cell_one, cell_two, wall) in walls.iter() {
+            if set_count <= 1 { break; }
 
-            set_one_index = None;
-            set_two_index = None;
+            let set_one = set_of[*cell_one];
+            let set_two = set_of[*cell_two];
 
-            //loops through every set to find the two sets that contain the two cells that the wall divides
-            for set_index in 0..cell_sets.len() {
-                for cell in &cell_sets[set_index] {
-                    
+            //if the two cells sharing the wall pertain to the same set
+            //then the wall will not be removed and the sets not joined.
+            //Locked walls are never removed either.
+            if set_one == set_two || !carvable(wall) { continue; }
 
-                    if  (Rc::ptr_eq(&walls[*i], cell.top_wall_mut())) ||
-                        (Rc::ptr_eq(&walls[*i], cell.bottom_wall_mut())) ||
-                        (Rc::ptr_eq(&walls[*i], cell.left_wall_mut())) ||
-                        (Rc::ptr_eq(&walls[*i], cell.right_wall_mut())) {
+            //shared wall is broken
+            wall.borrow_mut().active = false;
+            trace(MazeStep::Carve(*cell_one, *cell_two));
 
-                        if set_one_index == None { //tests to see if the index is an actual index
-                            set_one_index = Some(set_index);
-                        }
-                        else {
-                            set_two_index = Some(set_index);
-                        }
-                        break;
-                    }
-                }
-                if set_two_index != None { break; }
-            }
-
-            match set_two_index {
-                //if the two cells sharing the wall did not pertain to two different sets 
-                //then the wall will not be removed and the sets not joined
-                None => continue,
-
-                Some(sti) => {
-                    //shared wall is broken
-                    walls[*i].borrow_mut().active = false;
-
-                    //sets combined
-                    //cannot use vec::append because cannot borrow cell_sets as mut twice, have to do manually
-                    let mut tmp = cell_sets.remove(sti);
-                    if let Some(soi) = set_one_index {
-                        cell_sets[soi].append(&mut tmp);
-                    }   
-                }
-            }
+            //sets combined
+            join(set_one, set_two, &mut cell_sets, &mut set_of);
+            set_count -= 1;
         }
 
         //Set up maze exits
-        cells[0][0].left_wall_mut().borrow_mut().active = false;
-        cells[width - 1][height - 1].right_wall_mut().borrow_mut().active = false;
+        grid.open_exits();
     }
-}
\ No newline at end of file
+}
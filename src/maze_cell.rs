@@ -4,85 +4,55 @@ use std::cell::{RefCell, Ref};
 use super::maze_wall::MazeWall;
 
 pub struct MazeCell {
-    top_wall: Option<Rc<RefCell<MazeWall>>>,
-    bottom_wall: Option<Rc<RefCell<MazeWall>>>,
-    left_wall: Option<Rc<RefCell<MazeWall>>>,
-    right_wall: Option<Rc<RefCell<MazeWall>>>,
+    top_wall: Rc<RefCell<MazeWall>>,
+    bottom_wall: Rc<RefCell<MazeWall>>,
+    left_wall: Rc<RefCell<MazeWall>>,
+    right_wall: Rc<RefCell<MazeWall>>,
+    enabled: bool,
 }
 
 impl MazeCell {
+    //Constructor for a cell on its own, closed on every side by walls of its own.
+    //A maze swaps in the walls each cell shares with its neighbors when it's built.
     pub fn new() -> Self {
-        Self {
-            top_wall: None,
-            bottom_wall: None,
-            left_wall: None,
-            right_wall: None,
-        }
-    }
-
-    //Getters
-    pub fn top_wall(&self) -> Ref<'_, MazeWall> { 
-        match &self.top_wall {
-            Some(value) => value.borrow(),
-            None => panic!("MazeCell was not properly constructed."),
-        }
-    }
+        let wall = || Rc::new(RefCell::new(MazeWall::new(true)));
 
-    pub fn bottom_wall(&self) -> Ref<'_, MazeWall> { 
-        match &self.bottom_wall {
-            Some(value) => value.borrow(),
-            None => panic!("MazeCell was not properly constructed."),
+        Self {
+            top_wall: wall(),
+            bottom_wall: wall(),
+            left_wall: wall(),
+            right_wall: wall(),
+            enabled: true,
         }
     }
 
-    pub fn left_wall(&self) -> Ref<'_, MazeWall> { 
-        match &self.left_wall {
-            Some(value) => value.borrow(),
-            None => panic!("MazeCell was not properly constructed."),
-        }
-    }
+    //Disabled cells lie outside the shape of a masked maze and are never carved into
+    pub fn is_enabled(&self) -> bool { self.enabled }
+    pub fn set_enabled(&mut self, enabled: bool) { self.enabled = enabled; }
 
-    pub fn right_wall(&self) -> Ref<'_, MazeWall> { 
-        match &self.right_wall {
-            Some(value) => value.borrow(),
-            None => panic!("MazeCell was not properly constructed."),
-        }
-    }
+    //Getters
+    pub fn top_wall(&self) -> Ref<'_, MazeWall> { self.top_wall.borrow() }
+    pub fn bottom_wall(&self) -> Ref<'_, MazeWall> { self.bottom_wall.borrow() }
+    pub fn left_wall(&self) -> Ref<'_, MazeWall> { self.left_wall.borrow() }
+    pub fn right_wall(&self) -> Ref<'_, MazeWall> { self.right_wall.borrow() }
 
     //Mutable Access
-    pub fn top_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { 
-        match &self.top_wall {
-            Some(value) => value,
-            None => panic!("MazeCell was not properly constructed."),
-        }
-    }
-
-    pub fn bottom_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { 
-        match &self.bottom_wall {
-            Some(value) => value,
-            None => panic!("MazeCell was not properly constructed."),
-        }
-    }
+    pub fn top_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { &self.top_wall }
+    pub fn bottom_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { &self.bottom_wall }
+    pub fn left_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { &self.left_wall }
+    pub fn right_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { &self.right_wall }
 
-    pub fn left_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { 
-        match &self.left_wall {
-            Some(value) => value,
-            None => panic!("MazeCell was not properly constructed."),
-        }
-    }
+    //Setters
+    pub fn set_top_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.top_wall = wall; }
+    pub fn set_bottom_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.bottom_wall = wall; }
+    pub fn set_left_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.left_wall = wall; }
+    pub fn set_right_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.right_wall = wall; }
+}
 
-    pub fn right_wall_mut(&self) -> &Rc<RefCell<MazeWall>> { 
-        match &self.right_wall {
-            Some(value) => value,
-            None => panic!("MazeCell was not properly constructed."),
-        }
+impl Default for MazeCell {
+    fn default() -> Self {
+        Self::new()
     }
-
-    //Setters
-    pub fn set_top_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.top_wall = Some(wall); }
-    pub fn set_bottom_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.bottom_wall = Some(wall); }
-    pub fn set_left_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.left_wall = Some(wall); }
-    pub fn set_right_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.right_wall = Some(wall); }
 }
 
 impl Clone for MazeCell {
@@ -92,6 +62,7 @@ impl Clone for MazeCell {
             bottom_wall: self.bottom_wall.clone(),
             left_wall: self.left_wall.clone(),
             right_wall: self.right_wall.clone(),
+            enabled: self.enabled,
         }
     }
 }
\ No newline at end of file
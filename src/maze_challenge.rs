@@ -0,0 +1,224 @@
+extern crate rand;
+
+use std::io::{self, BufRead, Write};
+use std::time::Duration;
+
+use rand::{RngCore, SeedableRng};
+use rand::rngs::StdRng;
+use serde::{Serialize, Deserialize};
+
+use super::maze_3d::Maze3D;
+use super::maze_algorithm::DepthFirstSearch;
+use super::maze_distance::DistanceMap;
+use super::maze_enemy::Behaviour;
+use super::maze_error::{MazeError, check_dimensions};
+use super::maze_grid::MazeGrid;
+use super::maze_items::ItemOptions;
+use super::maze_leaderboard::LeaderboardEntry;
+use super::maze_play::{self, MazeGame};
+use super::maze_regenerate::Regenerate;
+
+//How a challenge grows from one level to the next. Every level is square, growth cells wider and taller than the one before,
+//and a floor is added every floor_every levels. Levels are given base_seconds, and seconds_per_level more for each level before them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ChallengeOptions {
+    pub levels: usize,
+    pub first_size: usize,
+    pub growth: usize,
+    pub floor_every: usize,     //0 keeps every level to a single floor
+    pub base_seconds: u64,
+    pub seconds_per_level: u64,
+    pub items: bool,
+    pub monsters: bool,
+}
+
+impl Default for ChallengeOptions {
+    fn default() -> Self {
+        Self {
+            levels: 5,
+            first_size: 6,
+            growth: 2,
+            floor_every: 3,
+            base_seconds: 30,
+            seconds_per_level: 15,
+            items: true,
+            monsters: false,
+        }
+    }
+}
+
+//How a level of a challenge went. optimal is the length of the shortest way from the entrance to the exit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelResult {
+    pub seed: u64,
+    pub escaped: bool,
+    pub moves: usize,
+    pub optimal: usize,
+    pub coins: usize,
+    pub seconds: u64,
+    pub time_limit: u64,
+}
+
+impl LevelResult {
+    //Up to 1000 points for taking the shortest way, 10 for every second left on the clock and 100 for every coin.
+    //A level that isn't escaped only scores its coins.
+    pub fn score(&self) -> u64 {
+        let coins = 100 * self.coins as u64;
+        if !self.escaped { return coins; }
+
+        let efficiency = (1000 * self.optimal.max(1) / self.moves.max(1)).min(1000) as u64;
+        efficiency + 10 * self.time_limit.saturating_sub(self.seconds) + coins
+    }
+}
+
+//A run through a sequence of levels against the clock. Every level is built from seed, so a challenge
+//with the same options and seed always has the same levels, and can be played again or compared.
+pub struct Challenge {
+    options: ChallengeOptions,
+    seed: u64,
+    results: Vec<LevelResult>,
+}
+
+impl Challenge {
+    //Constructor. Fails if the largest level would be too large.
+    pub fn new(options: ChallengeOptions, seed: u64) -> Result<Self, MazeError> {
+        if options.levels > 0 {
+            let (size, floors) = Self::dimensions(&options, options.levels - 1);
+            check_dimensions(&[size, size, floors])?;
+        }
+
+        Ok(Self {
+            options,
+            seed,
+            results: Vec::new(),
+        })
+    }
+
+    //Getters
+    pub fn options(&self) -> &ChallengeOptions { &self.options }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn results(&self) -> &[LevelResult] { &self.results }
+
+    pub fn score(&self) -> u64 { self.results.iter().map(|result| result.score()).sum() }
+
+    //A challenge ends after its last level, or as soon as a level isn't escaped
+    pub fn is_over(&self) -> bool {
+        self.results.len() >= self.options.levels || self.results.last().is_some_and(|result| !result.escaped)
+    }
+
+    //Seed the level with this index is generated from
+    pub fn level_seed(&self, index: usize) -> u64 {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        (0..index).for_each(|_| { rng.next_u64(); });
+        rng.next_u64()
+    }
+
+    pub fn time_limit(&self, index: usize) -> Duration {
+        Duration::from_secs(self.options.base_seconds + self.options.seconds_per_level * index as u64)
+    }
+
+    //A new game of the level with this index, counting from 0
+    pub fn level(&self, index: usize) -> MazeGame {
+        let (size, floors) = Self::dimensions(&self.options, index);
+        let seed = self.level_seed(index);
+
+        let mut maze = Maze3D::new(size, size, floors, Box::new(DepthFirstSearch::new()));
+        maze.regenerate_with_seed(seed);
+        let mut game = MazeGame::new(maze);
+
+        //Small levels may not have room for every door, so they make do with fewer
+        if self.options.items {
+            let coins = 2 + index;
+            for doors in (0..=index.min(3)).rev() {
+                if game.place_items(&ItemOptions { coins, doors }, seed).is_ok() { break; }
+            }
+        }
+
+        if self.options.monsters {
+            let behaviours: Vec<Behaviour> = (0..index.div_ceil(2))
+                .map(|monster| if monster % 2 == 0 { Behaviour::RandomWalker } else { Behaviour::WallFollower })
+                .collect();
+            game.spawn_enemies(&behaviours);
+        }
+
+        game
+    }
+
+    //Records how the next level went, given the game it was played in and how long it took
+    pub fn record(&mut self, game: &MazeGame, elapsed: Duration) -> LevelResult {
+        let index = self.results.len();
+        let time_limit = self.time_limit(index);
+        let maze = game.maze();
+        let (entrance, exit) = maze.exits().unwrap_or((0, 0));
+
+        let result = LevelResult {
+            seed: self.level_seed(index),
+            escaped: game.is_won() && elapsed <= time_limit,
+            moves: game.moves(),
+            optimal: DistanceMap::new(maze, entrance).distance(exit).unwrap_or(0),
+            coins: game.coins(),
+            seconds: elapsed.as_secs(),
+            time_limit: time_limit.as_secs(),
+        };
+
+        self.results.push(result);
+        result
+    }
+
+    //The challenge as it went so far, to go on a leaderboard under name
+    pub fn entry(&self, name: &str) -> LeaderboardEntry {
+        LeaderboardEntry {
+            name: name.to_string(),
+            seed: self.seed,
+            options: self.options,
+            score: self.score(),
+            levels: self.results.clone(),
+        }
+    }
+
+    //Side and number of floors of the level with this index
+    fn dimensions(options: &ChallengeOptions, index: usize) -> (usize, usize) {
+        let size = options.first_size + options.growth * index;
+        let floors = 1 + index.checked_div(options.floor_every).unwrap_or(0);
+        (size, floors)
+    }
+}
+
+//Plays challenge level by level, reading lines of commands from input, until it's over or input runs out.
+//clock gives the time since any fixed moment. It's read whenever a line comes in, and a line that comes in
+//after the level's time is up isn't played.
+pub fn run<R: BufRead, W: Write>(challenge: &mut Challenge, input: R, mut output: W, clock: &mut dyn FnMut() -> Duration) -> io::Result<()> {
+    writeln!(output, "{}", maze_play::HELP)?;
+    let mut lines = input.lines();
+
+    while !challenge.is_over() {
+        let index = challenge.results().len();
+        let limit = challenge.time_limit(index);
+        let mut game = challenge.level(index);
+
+        writeln!(output, "Level {} of {}, {} seconds on the clock", index + 1, challenge.options().levels, limit.as_secs())?;
+        write!(output, "{}", game)?;
+
+        let started = clock();
+        let mut elapsed = Duration::ZERO;
+
+        for line in lines.by_ref() {
+            let line = line?;
+            elapsed = clock().saturating_sub(started);
+
+            if elapsed > limit {
+                writeln!(output, "Time's up!")?;
+                break;
+            }
+            if !maze_play::play_line(&mut game, &line, &mut output)? { break; }
+            writeln!(output, "{} seconds left", (limit - elapsed).as_secs())?;
+        }
+
+        let result = challenge.record(&game, elapsed);
+        writeln!(output, "Level score: {}, {} in total", result.score(), challenge.score())?;
+    }
+
+    writeln!(output, "Challenge over with a score of {}. Seed {} plays the same levels again.", challenge.score(), challenge.seed())?;
+    Ok(())
+}
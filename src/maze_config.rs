@@ -0,0 +1,225 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use super::maze_algorithm::{MazeAlgorithm, DepthFirstSearch, PrimsAlgorithm, KruskalsAlgorithm};
+use super::maze_grid::Axis;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Parse(String),
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Io(error) => write!(f, "could not read config: {}", error),
+            ConfigError::Parse(reason) => write!(f, "could not parse config: {}", reason),
+            ConfigError::Invalid(reason) => write!(f, "invalid config: {}", reason),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        ConfigError::Io(error)
+    }
+}
+
+//How likely the algorithms are to carve a passage along each axis. Passages that don't run along either axis,
+//like stairs or the walls of a hex maze, always have a weight of 1.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Bias {
+    pub horizontal: f64,
+    pub vertical: f64,
+}
+
+impl Default for Bias {
+    fn default() -> Self {
+        Self { horizontal: 1.0, vertical: 1.0 }
+    }
+}
+
+impl Bias {
+    pub fn weight(&self, axis: Option<Axis>) -> f64 {
+        match axis {
+            Some(Axis::Horizontal) => self.horizontal,
+            Some(Axis::Vertical) => self.vertical,
+            None => 1.0,
+        }
+    }
+
+    //True for the default weights, where the algorithms pick between passages without looking at their axis
+    pub fn is_uniform(&self) -> bool {
+        self.horizontal == 1.0 && self.vertical == 1.0
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let valid = |weight: f64| weight.is_finite() && weight >= 0.0;
+
+        if !valid(self.horizontal) || !valid(self.vertical) {
+            return Err(ConfigError::Invalid(format!("bias weights must be 0 or more, not {} and {}", self.horizontal, self.vertical)));
+        }
+        if self.horizontal == 0.0 && self.vertical == 0.0 {
+            return Err(ConfigError::Invalid("bias weights can't both be 0".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+fn validate_probability(name: &str, value: f64) -> Result<(), ConfigError> {
+    if (0.0..=1.0).contains(&value) { Ok(()) } else { Err(ConfigError::Invalid(format!("{} must be from 0 to 1, not {}", name, value))) }
+}
+
+//Settings for the depth first search. start is the grid's number of the first cell, a random cell if it's None
+//or isn't an enabled cell of the maze.
+//Whenever the search can both go straight on and turn, it goes straight with a chance of straightness,
+//and otherwise turns with a chance of turn_probability. If neither happens every way is equally likely, after bias.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DepthFirstSearchConfig {
+    pub start: Option<usize>,
+    pub bias: Bias,
+    pub straightness: f64,
+    pub turn_probability: f64,
+}
+
+impl DepthFirstSearchConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.bias.validate()?;
+        validate_probability("straightness", self.straightness)?;
+        validate_probability("turn_probability", self.turn_probability)
+    }
+}
+
+//Which wall Prim's algorithm takes off its list next. Taking the newest wall gives long corridors like a depth first search,
+//taking the oldest gives short corridors spreading out evenly from the start.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontierPolicy {
+    #[default]
+    Random,
+    Newest,
+    Oldest,
+}
+
+impl FrontierPolicy {
+    pub const ALL: [FrontierPolicy; 3] = [FrontierPolicy::Random, FrontierPolicy::Newest, FrontierPolicy::Oldest];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            FrontierPolicy::Random => "random",
+            FrontierPolicy::Newest => "newest",
+            FrontierPolicy::Oldest => "oldest",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|policy| policy.name() == name)
+    }
+}
+
+//Settings for Prim's algorithm. start works the same as for the depth first search.
+//bias only applies to the random frontier policy, the others always take the same wall.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrimsConfig {
+    pub start: Option<usize>,
+    pub bias: Bias,
+    pub frontier: FrontierPolicy,
+}
+
+impl PrimsConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.bias.validate()
+    }
+}
+
+//Settings for Kruskal's algorithm. It has no start cell, as it works on the whole maze at once.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KruskalsConfig {
+    pub bias: Bias,
+}
+
+impl KruskalsConfig {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        self.bias.validate()
+    }
+}
+
+//An algorithm along with its settings, as read from a config file. The algorithm is picked by its registry name, e.g.
+//    algorithm = "depth_first_search"
+//    straightness = 0.6
+//    bias = { horizontal = 2.0, vertical = 1.0 }
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "snake_case")]
+pub enum AlgorithmConfig {
+    DepthFirstSearch(DepthFirstSearchConfig),
+    Prims(PrimsConfig),
+    Kruskals(KruskalsConfig),
+}
+
+impl AlgorithmConfig {
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(text).map_err(|error| ConfigError::Parse(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(text).map_err(|error| ConfigError::Parse(error.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    //Reads a config file, as JSON if it ends in .json and as TOML otherwise
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path)?;
+
+        if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("json")) {
+            Self::from_json(&text)
+        }
+        else {
+            Self::from_toml(&text)
+        }
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        match self {
+            AlgorithmConfig::DepthFirstSearch(config) => config.validate(),
+            AlgorithmConfig::Prims(config) => config.validate(),
+            AlgorithmConfig::Kruskals(config) => config.validate(),
+        }
+    }
+
+    //Builds the configured algorithm
+    pub fn build(&self) -> Result<Box<dyn MazeAlgorithm>, ConfigError> {
+        self.validate()?;
+
+        Ok(match self {
+            AlgorithmConfig::DepthFirstSearch(config) => Box::new(DepthFirstSearch::with_config(*config)),
+            AlgorithmConfig::Prims(config) => Box::new(PrimsAlgorithm::with_config(*config)),
+            AlgorithmConfig::Kruskals(config) => Box::new(KruskalsAlgorithm::with_config(*config)),
+        })
+    }
+}
@@ -0,0 +1,271 @@
+extern crate rand;
+
+use std::rc::Rc;
+use std::cell::{RefCell, Ref};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use super::maze_wall::MazeWall;
+use super::maze_algorithm::{MazeAlgorithm, MazeStep};
+use super::maze_grid::{MazeGrid, Point};
+use super::maze_regenerate::Regenerate;
+use super::maze_render::{MazeDrawing, Stroke};
+use super::maze_error::{MazeError, check_dimensions};
+
+//A triangular cell. The base wall is the horizontal side of the triangle, which is at the bottom
+//of an upward pointing cell and at the top of a downward pointing one.
+#[derive(Clone, Default)]
+pub struct DeltaCell {
+    left_wall: Option<Rc<RefCell<MazeWall>>>,
+    right_wall: Option<Rc<RefCell<MazeWall>>>,
+    base_wall: Option<Rc<RefCell<MazeWall>>>,
+}
+
+impl DeltaCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Getters
+    pub fn left_wall(&self) -> Ref<'_, MazeWall> { self.left_wall_mut().borrow() }
+    pub fn right_wall(&self) -> Ref<'_, MazeWall> { self.right_wall_mut().borrow() }
+    pub fn base_wall(&self) -> Ref<'_, MazeWall> { self.base_wall_mut().borrow() }
+
+    //Mutable Access
+    pub fn left_wall_mut(&self) -> &Rc<RefCell<MazeWall>> {
+        match &self.left_wall {
+            Some(value) => value,
+            None => panic!("DeltaCell was not properly constructed."),
+        }
+    }
+
+    pub fn right_wall_mut(&self) -> &Rc<RefCell<MazeWall>> {
+        match &self.right_wall {
+            Some(value) => value,
+            None => panic!("DeltaCell was not properly constructed."),
+        }
+    }
+
+    pub fn base_wall_mut(&self) -> &Rc<RefCell<MazeWall>> {
+        match &self.base_wall {
+            Some(value) => value,
+            None => panic!("DeltaCell was not properly constructed."),
+        }
+    }
+
+    //Setters
+    pub fn set_left_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.left_wall = Some(wall); }
+    pub fn set_right_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.right_wall = Some(wall); }
+    pub fn set_base_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.base_wall = Some(wall); }
+}
+
+//A maze of triangles that alternate between pointing up and pointing down. The top left cell points up.
+//Every cell has a neighbor to its left and right, and one more below it if it points up or above it if it points down.
+//A maze one cell wide falls apart into separate pairs of cells, so it needs to be at least two cells wide.
+pub struct DeltaMaze {
+    maze_algorithm: Box<dyn MazeAlgorithm>,
+    cells: Vec<Vec<DeltaCell>>,
+    walls: Vec<Rc<RefCell<MazeWall>>>,
+    seed: u64,
+    width: usize,
+    height: usize,
+}
+
+impl DeltaMaze {
+    //Constructor
+    pub fn new(width: usize, height: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        let mut tmp = Self {
+            maze_algorithm,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            seed: 0,
+            width,
+            height,
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor that checks the dimensions first. Fails if either is zero, the maze would be too large,
+    //or it's one triangle wide and more than two rows tall, which can't be connected.
+    pub fn try_new(width: usize, height: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[width, height])?;
+        if width == 1 && height > 2 {
+            return Err(MazeError::TooNarrow(height));
+        }
+
+        Ok(Self::new(width, height, maze_algorithm))
+    }
+
+    //Reconstructs maze with the dimensions width and height. No algorithm is applied on the maze.
+    pub fn reconstruct(&mut self) {
+        let width = self.width;
+        let height = self.height;
+        self.cells = vec![vec![DeltaCell::new(); height]; width];
+        self.walls = Vec::new();
+
+        for x in 0..width {
+            for y in 0..height {
+                let left_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+
+                //Set current cell's left wall and the left neighbor's right wall
+                self.cells[x][y].set_left_wall(Rc::clone(&left_wall));
+                if x != 0 {
+                    self.cells[x - 1][y].set_right_wall(Rc::clone(&left_wall));
+                }
+                self.walls.push(left_wall);
+
+                //Downward pointing cells share their base with the upward pointing cell above them.
+                //Cells without a neighbor on their base get a border wall of their own.
+                let base_neighbor = self.base_neighbor(Point { x, y });
+                if self.points_up(Point { x, y }) || base_neighbor.is_none() {
+                    let base_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                    self.cells[x][y].set_base_wall(Rc::clone(&base_wall));
+                    self.walls.push(base_wall);
+                }
+                else if let Some(neighbor) = base_neighbor {
+                    let base_wall = Rc::clone(self.cells[neighbor.x][neighbor.y].base_wall_mut());
+                    self.cells[x][y].set_base_wall(base_wall);
+                }
+            }
+        }
+
+        //Add walls to the right column
+        if let Some(column) = self.cells.last_mut() {
+            for cell in column.iter_mut() {
+                let right_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                cell.set_right_wall(Rc::clone(&right_wall));
+                self.walls.push(right_wall);
+            }
+        }
+    }
+
+    pub fn set_algorithm(&mut self, new_algorithm: Box<dyn MazeAlgorithm>) {
+        self.maze_algorithm = new_algorithm;
+    }
+
+    //Getters
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn cells(&self) -> &Vec<Vec<DeltaCell>> { &self.cells }
+
+    //Cells are numbered column by column for the maze algorithms
+    pub fn cell_id(&self, point: Point) -> usize { point.x * self.height + point.y }
+    pub fn point(&self, cell: usize) -> Point { Point { x: cell / self.height, y: cell % self.height } }
+
+    pub fn points_up(&self, point: Point) -> bool { (point.x + point.y).is_multiple_of(2) }
+
+    //Returns the cell on the other side of point's base, None if that is outside of the maze
+    pub fn base_neighbor(&self, point: Point) -> Option<Point> {
+        if self.points_up(point) {
+            if point.y + 1 < self.height { Some(Point { x: point.x, y: point.y + 1 }) } else { None }
+        }
+        else if point.y > 0 {
+            Some(Point { x: point.x, y: point.y - 1 })
+        }
+        else {
+            None
+        }
+    }
+
+    //Corners of a cell in a drawing where every side of a triangle is one unit long.
+    //Returns the left and right ends of the base, then the tip.
+    fn corners(&self, point: Point) -> [(f64, f64); 3] {
+        let row_height = 3f64.sqrt() / 2.0;
+        let left = 0.5 * point.x as f64;
+        let top = row_height * point.y as f64;
+
+        if self.points_up(point) {
+            [(left, top + row_height), (left + 1.0, top + row_height), (left + 0.5, top)]
+        }
+        else {
+            [(left, top), (left + 1.0, top), (left + 0.5, top + row_height)]
+        }
+    }
+}
+
+impl Regenerate for DeltaMaze {
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>] { &self.walls }
+
+    //Same as regenerate_with_seed, but reports every step of the algorithm to trace
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep)) {
+        self.seed = seed;
+        self.reset();
+        self.maze_algorithm.generate_traced(&*self, &mut StdRng::seed_from_u64(seed), trace);
+    }
+}
+
+impl MazeGrid for DeltaMaze {
+    fn cell_count(&self) -> usize { self.width * self.height }
+
+    fn is_enabled(&self, _cell: usize) -> bool { true }
+
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        let point = self.point(cell);
+        let delta_cell = &self.cells[point.x][point.y];
+        let mut neighbors = Vec::new();
+
+        if point.x > 0 {
+            neighbors.push((self.cell_id(Point { x: point.x - 1, y: point.y }), Rc::clone(delta_cell.left_wall_mut())));
+        }
+        if point.x + 1 < self.width {
+            neighbors.push((self.cell_id(Point { x: point.x + 1, y: point.y }), Rc::clone(delta_cell.right_wall_mut())));
+        }
+        if let Some(neighbor) = self.base_neighbor(point) {
+            neighbors.push((self.cell_id(neighbor), Rc::clone(delta_cell.base_wall_mut())));
+        }
+
+        neighbors
+    }
+
+    //The entrance is the top left cell and the exit is the bottom right cell
+    fn exits(&self) -> Option<(usize, usize)> {
+        if self.cell_count() == 0 { return None; }
+        Some((0, self.cell_count() - 1))
+    }
+
+    //Opens the left wall of the entrance and the right wall of the exit, which are always on the border
+    fn open_exits(&self) {
+        if self.cell_count() == 0 { return; }
+
+        self.cells[0][0].left_wall_mut().borrow_mut().active = false;
+        self.cells[self.width - 1][self.height - 1].right_wall_mut().borrow_mut().active = false;
+    }
+}
+
+impl MazeDrawing for DeltaMaze {
+    fn extent(&self) -> (f64, f64) {
+        (0.5 * self.width as f64 + 0.5, 3f64.sqrt() / 2.0 * self.height as f64)
+    }
+
+    fn wall_strokes(&self) -> Vec<Stroke> {
+        let mut strokes = Vec::new();
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let point = Point { x, y };
+                let cell = &self.cells[x][y];
+                let [base_left, base_right, tip] = self.corners(point);
+
+                //Shared walls are drawn once. Right walls are only drawn on the border,
+                //and shared bases are drawn by the upward pointing cell.
+                if cell.left_wall().active {
+                    strokes.push(Stroke::Line { from: base_left, to: tip });
+                }
+                if x == self.width - 1 && cell.right_wall().active {
+                    strokes.push(Stroke::Line { from: tip, to: base_right });
+                }
+                if (self.points_up(point) || self.base_neighbor(point).is_none()) && cell.base_wall().active {
+                    strokes.push(Stroke::Line { from: base_left, to: base_right });
+                }
+            }
+        }
+
+        strokes
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+
+use super::maze::Maze;
+use super::maze_grid::{MazeGrid, Point};
+use super::maze_render::{Colour, MazeDrawing, Stroke, Fill};
+
+//Number of passages between a source cell and every other cell of a maze.
+//Cells that can't be reached from the source have no distance.
+pub struct DistanceMap {
+    source: usize,
+    distances: Vec<Option<usize>>,
+}
+
+impl DistanceMap {
+    //Constructor. Floods the maze from source with a breadth first search.
+    pub fn new(grid: &dyn MazeGrid, source: usize) -> Self {
+        let mut distances: Vec<Option<usize>> = vec![None; grid.cell_count()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+
+        if source < distances.len() {
+            distances[source] = Some(0);
+            queue.push_back(source);
+        }
+
+        while let Some(current) = queue.pop_front() {
+            let next = distances[current].unwrap_or(0) + 1;
+
+            for neighbor in grid.passages(current) {
+                if distances[neighbor].is_some() { continue; }
+
+                distances[neighbor] = Some(next);
+                queue.push_back(neighbor);
+            }
+        }
+
+        Self { source, distances }
+    }
+
+    //Getters
+    pub fn source(&self) -> usize { self.source }
+    pub fn distances(&self) -> &Vec<Option<usize>> { &self.distances }
+    pub fn distance(&self, cell: usize) -> Option<usize> { self.distances.get(cell).copied().flatten() }
+
+    //Distance to the cell furthest from the source
+    pub fn max_distance(&self) -> Option<usize> { self.distances.iter().flatten().max().copied() }
+
+    //The reachable cell furthest from the source. Ties go to the lowest numbered cell.
+    pub fn farthest(&self) -> Option<usize> {
+        let max = self.max_distance()?;
+        self.distances.iter().position(|distance| *distance == Some(max))
+    }
+
+    //How far cell is on a scale from 0 at the source to 1 at the furthest cell
+    pub fn fraction(&self, cell: usize) -> Option<f64> {
+        let distance = self.distance(cell)?;
+        let max = self.max_distance()?;
+
+        if max == 0 { Some(0.0) } else { Some(distance as f64 / max as f64) }
+    }
+}
+
+//Colour part of the way from near to far, where fraction goes from 0 to 1
+pub fn gradient(near: Colour, far: Colour, fraction: f64) -> Colour {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let mix = |one: u8, two: u8| (one as f64 + (two as f64 - one as f64) * fraction).round() as u8;
+
+    [mix(near[0], far[0]), mix(near[1], far[1]), mix(near[2], far[2])]
+}
+
+//A maze drawn with every cell coloured by its distance from the source. Cells that can't be reached are left blank.
+pub struct Heatmap<'a> {
+    maze: &'a Maze,
+    distances: &'a DistanceMap,
+    pub near: Colour,
+    pub far: Colour,
+}
+
+impl<'a> Heatmap<'a> {
+    //Constructor
+    pub fn new(maze: &'a Maze, distances: &'a DistanceMap) -> Self {
+        Self {
+            maze,
+            distances,
+            near: [255, 240, 150],
+            far: [170, 20, 60],
+        }
+    }
+
+    //Heat colour of the cell at point, None if it can't be reached
+    pub fn colour(&self, point: Point) -> Option<Colour> {
+        let fraction = self.distances.fraction(self.maze.cell_id(point))?;
+        Some(gradient(self.near, self.far, fraction))
+    }
+
+    //Text drawing of the maze with every cell coloured in using ANSI true colour escape codes
+    pub fn ansi_string(&self) -> String {
+        self.maze.string_with(&|point| {
+            self.colour(point).map(|colour| format!("\x1b[48;2;{};{};{}m  \x1b[0m", colour[0], colour[1], colour[2]))
+        })
+    }
+}
+
+impl<'a> MazeDrawing for Heatmap<'a> {
+    fn extent(&self) -> (f64, f64) { self.maze.extent() }
+
+    fn wall_strokes(&self) -> Vec<Stroke> { self.maze.wall_strokes() }
+
+    fn fills(&self) -> Vec<Fill> {
+        let mut fills = Vec::new();
+
+        for x in 0..self.maze.width() {
+            for y in 0..self.maze.height() {
+                if let Some(colour) = self.colour(Point { x, y }) {
+                    fills.push(Fill::square(x as f64, y as f64, colour));
+                }
+            }
+        }
+
+        fills
+    }
+}
@@ -0,0 +1,646 @@
+extern crate rand;
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use super::maze::Maze;
+use super::maze_algorithm::{MazeAlgorithm, DepthFirstSearch};
+use super::maze_distance::DistanceMap;
+use super::maze_error::{MazeError, check_area};
+use super::maze_file::{self, MazeFile, FileError};
+use super::maze_grid::{MazeGrid, Point, Direction};
+use super::maze_regenerate::Regenerate;
+use super::maze_registry::{AlgorithmRegistry, RegistryError};
+use super::maze_validator::{self, MazeViolation};
+use super::maze_wall::MazeWall;
+
+//A single wall an edit changed. The wall is the one at index side of cell's neighbors, which tells apart
+//the two walls a pair of cells can share on a narrow wrapped maze.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct WallChange {
+    cell: usize,
+    side: usize,
+    active: bool,       //State the edit left the wall in, undoing it gives the opposite
+}
+
+//A single edit in the history. seeds is the maze's seed before and after the edit, if the edit generated the whole maze again.
+struct Edit {
+    changes: Vec<WallChange>,
+    seeds: Option<(u64, u64)>,
+}
+
+//Edits a maze for a level designer. Every edit is kept in a history, so it can be undone and redone.
+//The editor owns the maze, so nothing can change it without going through the history.
+pub struct MazeEditor {
+    maze: Maze,
+    undo_history: Vec<Edit>,
+    redo_history: Vec<Edit>,
+}
+
+impl MazeEditor {
+    //Constructor
+    pub fn new(maze: Maze) -> Self {
+        Self {
+            maze,
+            undo_history: Vec::new(),
+            redo_history: Vec::new(),
+        }
+    }
+
+    //Getters
+    pub fn maze(&self) -> &Maze { &self.maze }
+    pub fn into_maze(self) -> Maze { self.maze }
+    pub fn can_undo(&self) -> bool { !self.undo_history.is_empty() }
+    pub fn can_redo(&self) -> bool { !self.redo_history.is_empty() }
+
+    //True if there's a wall between two neighboring cells, false if there's a passage
+    pub fn wall_active(&self, one: Point, two: Point) -> Result<bool, MazeError> {
+        let (cell, side) = self.side(one, two)?;
+        Ok(self.wall(cell, side).borrow().active)
+    }
+
+    //Knocks down the wall between two neighboring cells if it's up, and puts it up if it's down
+    pub fn toggle_wall(&mut self, one: Point, two: Point) -> Result<(), MazeError> {
+        let active = self.wall_active(one, two)?;
+        self.set_wall(one, two, !active)
+    }
+
+    //Puts up the wall between two neighboring cells, or knocks it down if active is false
+    pub fn set_wall(&mut self, one: Point, two: Point, active: bool) -> Result<(), MazeError> {
+        let side = self.side(one, two)?;
+        self.edit(vec![side], active)
+    }
+
+    //Knocks down the walls between each cell of path and the next, so it can be walked from one end to the other.
+    //Nothing changes if two cells in a row aren't neighbors, or a locked wall is in the way.
+    pub fn carve_path(&mut self, path: &[Point]) -> Result<(), MazeError> {
+        let sides = path.windows(2).map(|pair| self.side(pair[0], pair[1])).collect::<Result<Vec<_>, _>>()?;
+        self.edit(sides, false)
+    }
+
+    //Puts up every wall between two cells of the rectangle, or knocks them all down if active is false.
+    //x and y are the top left cell. Locked walls are left as they are.
+    pub fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, active: bool) -> Result<(), MazeError> {
+        check_area(x, y, width, height, self.maze.width(), self.maze.height())?;
+
+        let inside = |point: Point| point.x >= x && point.x < x + width && point.y >= y && point.y < y + height;
+        let mut sides = Vec::new();
+
+        for cell in 0..self.maze.cell_count() {
+            if !inside(self.maze.point(cell)) || !self.maze.is_enabled(cell) { continue; }
+
+            for (side, (neighbor, wall)) in self.maze.neighbors(cell).into_iter().enumerate() {
+                if cell < neighbor && inside(self.maze.point(neighbor)) && !wall.borrow().locked {
+                    sides.push((cell, side));
+                }
+            }
+        }
+
+        self.edit(sides, active)
+    }
+
+    //Generates a new maze from seed. Undoing it brings back the maze as it was before, along with its seed.
+    pub fn regenerate_with_seed(&mut self, seed: u64) {
+        let before = self.wall_states();
+        let seed_before = self.maze.seed();
+        self.maze.regenerate_with_seed(seed);
+        self.record_since(before, Some((seed_before, seed)));
+    }
+
+    //Carves the rectangle again with algorithm and stitches it back into the rest of the maze, as a single edit.
+    //x and y are the top left cell. Fails if the rectangle reaches outside the maze.
+    pub fn regenerate_area(&mut self, x: usize, y: usize, width: usize, height: usize, algorithm: &dyn MazeAlgorithm, seed: u64) -> Result<(), MazeError> {
+        let before = self.wall_states();
+        self.maze.regenerate_area(x, y, width, height, algorithm, seed)?;
+        self.record_since(before, None);
+        Ok(())
+    }
+
+    //Takes back the last edit. Returns false if there's nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo_history.pop() {
+            Some(edit) => {
+                for change in edit.changes.iter().rev() {
+                    self.wall(change.cell, change.side).borrow_mut().active = !change.active;
+                }
+                if let Some((before, _)) = edit.seeds {
+                    self.maze.set_seed(before);
+                }
+
+                self.redo_history.push(edit);
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    //Makes the last undone edit again. Returns false if there's nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        match self.redo_history.pop() {
+            Some(edit) => {
+                for change in edit.changes.iter() {
+                    self.wall(change.cell, change.side).borrow_mut().active = change.active;
+                }
+                if let Some((_, after)) = edit.seeds {
+                    self.maze.set_seed(after);
+                }
+
+                self.undo_history.push(edit);
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    //Forgets every edit, so none of them can be undone or redone
+    pub fn clear_history(&mut self) {
+        self.undo_history.clear();
+        self.redo_history.clear();
+    }
+
+    //Finds which of one's neighbors two is
+    fn side(&self, one: Point, two: Point) -> Result<(usize, usize), MazeError> {
+        if self.maze.is_enabled_at(one) && self.maze.is_enabled_at(two) {
+            let cell = self.maze.cell_id(one);
+            let neighbor = self.maze.cell_id(two);
+
+            if let Some(side) = self.maze.neighbors(cell).iter().position(|(other, _)| *other == neighbor) {
+                return Ok((cell, side));
+            }
+        }
+
+        Err(MazeError::NotNeighbors(one, two))
+    }
+
+    fn wall(&self, cell: usize, side: usize) -> Rc<RefCell<MazeWall>> {
+        Rc::clone(&self.maze.neighbors(cell)[side].1)
+    }
+
+    //Sets every listed wall to active as a single edit. Locked walls are checked first, so an edit either
+    //changes every wall or none of them.
+    fn edit(&mut self, sides: Vec<(usize, usize)>, active: bool) -> Result<(), MazeError> {
+        for (cell, side) in sides.iter() {
+            let (neighbor, wall) = &self.maze.neighbors(*cell)[*side];
+            let wall = wall.borrow();
+
+            if wall.locked && wall.active != active {
+                return Err(MazeError::LockedWall(self.maze.point(*cell), self.maze.point(*neighbor)));
+            }
+        }
+
+        let mut changes = Vec::new();
+        for (cell, side) in sides {
+            let wall = self.wall(cell, side);
+            if wall.borrow().active == active { continue; }
+
+            wall.borrow_mut().active = active;
+            changes.push(WallChange { cell, side, active });
+        }
+
+        self.record(Edit { changes, seeds: None });
+        Ok(())
+    }
+
+    //Adds an edit to the history. A new edit can't be followed by the ones undone before it, so they're forgotten.
+    //Edits that change nothing, not even the seed, are left out.
+    fn record(&mut self, edit: Edit) {
+        let new_seed = edit.seeds.is_some_and(|(before, after)| before != after);
+        if edit.changes.is_empty() && !new_seed { return; }
+
+        self.undo_history.push(edit);
+        self.redo_history.clear();
+    }
+
+    //Records every wall that changed since before was taken by wall_states as a single edit, along with the seeds if it has any
+    fn record_since(&mut self, before: Vec<(usize, usize, bool)>, seeds: Option<(u64, u64)>) {
+        let changes = self.wall_states().into_iter().zip(before)
+            .filter(|(after, before)| after.2 != before.2)
+            .map(|(after, _)| WallChange { cell: after.0, side: after.1, active: after.2 })
+            .collect();
+
+        self.record(Edit { changes, seeds });
+    }
+
+    //State of every wall between two enabled cells, as (cell, side, active)
+    fn wall_states(&self) -> Vec<(usize, usize, bool)> {
+        (0..self.maze.cell_count())
+            .filter(|cell| self.maze.is_enabled(*cell))
+            .flat_map(|cell| {
+                self.maze.neighbors(cell).into_iter().enumerate()
+                    .filter(move |(_, (neighbor, _))| cell < *neighbor)
+                    .map(move |(side, (_, wall))| (cell, side, wall.borrow().active))
+            })
+            .collect()
+    }
+}
+
+//A single input to the terminal editor
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EditorCommand {
+    Move(Direction),
+    ToggleWall(Direction),
+    PlaceStart,
+    PlaceGoal,
+    Mark,
+    ClearSelection,
+    Regenerate,
+    Undo,
+    Redo,
+    Save(Option<String>),
+    Load(Option<String>),
+    Algorithm(String),
+    Quit,
+}
+
+impl EditorCommand {
+    //w, a, s and d move the cursor, and in capitals toggle the wall on that side of it. b and g put the start and goal
+    //on the cursor, m marks a corner of the selection and c clears it, n regenerates the selection, u and r undo and redo,
+    //q quits
+    pub fn parse(key: char) -> Option<Self> {
+        match key {
+            'w' => Some(EditorCommand::Move(Direction::North)),
+            's' => Some(EditorCommand::Move(Direction::South)),
+            'd' => Some(EditorCommand::Move(Direction::East)),
+            'a' => Some(EditorCommand::Move(Direction::West)),
+            'W' => Some(EditorCommand::ToggleWall(Direction::North)),
+            'S' => Some(EditorCommand::ToggleWall(Direction::South)),
+            'D' => Some(EditorCommand::ToggleWall(Direction::East)),
+            'A' => Some(EditorCommand::ToggleWall(Direction::West)),
+            'b' => Some(EditorCommand::PlaceStart),
+            'g' => Some(EditorCommand::PlaceGoal),
+            'm' => Some(EditorCommand::Mark),
+            'c' => Some(EditorCommand::ClearSelection),
+            'n' => Some(EditorCommand::Regenerate),
+            'u' => Some(EditorCommand::Undo),
+            'r' => Some(EditorCommand::Redo),
+            'q' => Some(EditorCommand::Quit),
+            _ => None,
+        }
+    }
+
+    //Commands that take a word after them are typed out on a line of their own:
+    //save [file], load [file] and algorithm <name>. Returns None for any other line.
+    pub fn parse_line(line: &str) -> Option<Self> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["save"] => Some(EditorCommand::Save(None)),
+            ["save", file] => Some(EditorCommand::Save(Some(file.to_string()))),
+            ["load"] => Some(EditorCommand::Load(None)),
+            ["load", file] => Some(EditorCommand::Load(Some(file.to_string()))),
+            ["algorithm", name] => Some(EditorCommand::Algorithm(name.to_string())),
+            _ => None,
+        }
+    }
+}
+
+//A maze being edited in the terminal, along with the cursor, the start and goal placed in it,
+//and the selection that gets regenerated
+pub struct EditorSession {
+    editor: MazeEditor,
+    registry: AlgorithmRegistry,
+    algorithm_name: String,
+    algorithm: Box<dyn MazeAlgorithm>,
+    start: Point,
+    goal: Point,
+    cursor: Point,
+    anchor: Option<Point>,  //Corner of the selection across from the cursor
+}
+
+impl EditorSession {
+    //Constructor. Regenerating uses the depth first search until another algorithm is picked.
+    pub fn new(file: MazeFile) -> Self {
+        Self {
+            editor: MazeEditor::new(file.maze),
+            registry: AlgorithmRegistry::with_builtins(),
+            algorithm_name: "depth_first_search".to_string(),
+            algorithm: Box::new(DepthFirstSearch::new()),
+            cursor: file.start,
+            start: file.start,
+            goal: file.goal,
+            anchor: None,
+        }
+    }
+
+    //Getters
+    pub fn editor(&self) -> &MazeEditor { &self.editor }
+    pub fn algorithm_name(&self) -> &str { &self.algorithm_name }
+    pub fn start(&self) -> Point { self.start }
+    pub fn goal(&self) -> Point { self.goal }
+    pub fn cursor(&self) -> Point { self.cursor }
+
+    //Top left and bottom right cells of the selection, None if nothing is selected
+    pub fn selection(&self) -> Option<(Point, Point)> {
+        let anchor = self.anchor?;
+        let cursor = self.cursor;
+
+        Some((
+            Point { x: anchor.x.min(cursor.x), y: anchor.y.min(cursor.y) },
+            Point { x: anchor.x.max(cursor.x), y: anchor.y.max(cursor.y) },
+        ))
+    }
+
+    //Moves the cursor one cell. Returns false if that would take it out of the maze.
+    pub fn move_cursor(&mut self, direction: Direction) -> bool {
+        match self.editor.maze().step(self.cursor, direction) {
+            Some(next) => {
+                self.cursor = next;
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    //Toggles the wall on the side of the cursor's cell in direction. Fails with OutsideMaze for the border.
+    pub fn toggle_wall(&mut self, direction: Direction) -> Result<(), MazeError> {
+        let next = self.editor.maze().step(self.cursor, direction).ok_or(MazeError::OutsideMaze(self.cursor))?;
+        self.editor.toggle_wall(self.cursor, next)
+    }
+
+    //Puts the start on the cursor. Returns false if the cursor is on a cell the mask leaves out.
+    pub fn place_start(&mut self) -> bool {
+        if !self.editor.maze().is_enabled_at(self.cursor) { return false; }
+
+        self.start = self.cursor;
+        true
+    }
+
+    //Puts the goal on the cursor. Returns false if the cursor is on a cell the mask leaves out.
+    pub fn place_goal(&mut self) -> bool {
+        if !self.editor.maze().is_enabled_at(self.cursor) { return false; }
+
+        self.goal = self.cursor;
+        true
+    }
+
+    //Starts a selection with one corner where the cursor is. The cursor is the other corner.
+    pub fn mark(&mut self) { self.anchor = Some(self.cursor); }
+    pub fn clear_selection(&mut self) { self.anchor = None; }
+
+    //Picks the algorithm regeneration uses by its registry name
+    pub fn set_algorithm(&mut self, name: &str) -> Result<(), RegistryError> {
+        self.algorithm = self.registry.create_default(name)?;
+        self.algorithm_name = name.to_string();
+        Ok(())
+    }
+
+    //Carves the selection again with the picked algorithm and stitches it back in, or the whole maze if nothing is selected.
+    //Fails on a maze with no cells.
+    pub fn regenerate(&mut self, seed: u64) -> Result<(), MazeError> {
+        let maze = self.editor.maze();
+        if maze.cell_count() == 0 {
+            return Err(MazeError::ZeroSize);
+        }
+
+        let (corner, far) = self.selection().unwrap_or((Point { x: 0, y: 0 }, Point { x: maze.width() - 1, y: maze.height() - 1 }));
+
+        self.editor.regenerate_area(corner.x, corner.y, far.x - corner.x + 1, far.y - corner.y + 1, self.algorithm.as_ref(), seed)
+    }
+
+    pub fn undo(&mut self) -> bool { self.editor.undo() }
+    pub fn redo(&mut self) -> bool { self.editor.redo() }
+
+    //Number of steps from the start to the goal, None if the goal can't be reached
+    pub fn solution_length(&self) -> Option<usize> {
+        let maze = self.editor.maze();
+        DistanceMap::new(maze, maze.cell_id(self.start)).distance(maze.cell_id(self.goal))
+    }
+
+    //The maze as the text of a maze file
+    pub fn to_text(&self) -> String {
+        maze_file::write_text(self.editor.maze(), self.start, self.goal)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        std::fs::write(path, self.to_text())
+    }
+
+    //Replaces the maze with the one in a maze file. The history starts over and the selection is cleared.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<(), FileError> {
+        //The session regenerates with its own algorithm, so the one the maze keeps doesn't matter
+        let file = MazeFile::load(path, Box::new(DepthFirstSearch::new()))?;
+
+        self.editor = MazeEditor::new(file.maze);
+        self.cursor = file.start;
+        self.start = file.start;
+        self.goal = file.goal;
+        self.anchor = None;
+
+        Ok(())
+    }
+}
+
+//The maze with the cursor drawn as [], the start as SS, the goal as GG and the selection as ::,
+//followed by whether the goal can be reached and whether the maze is still a perfect maze
+impl fmt::Display for EditorSession {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let maze = self.editor.maze();
+        let selection = self.selection();
+
+        writeln!(f, "Cursor at ({}, {}), regenerating with {}", self.cursor.x, self.cursor.y, self.algorithm_name)?;
+        write!(f, "{}", maze.string_with(&|point| {
+            let selected = selection.is_some_and(|(corner, far)| point.x >= corner.x && point.x <= far.x && point.y >= corner.y && point.y <= far.y);
+
+            if point == self.cursor { Some("[]".to_string()) }
+            else if point == self.start { Some("SS".to_string()) }
+            else if point == self.goal { Some("GG".to_string()) }
+            else if selected { Some("::".to_string()) }
+            else { None }
+        }))?;
+
+        match self.solution_length() {
+            Some(steps) => writeln!(f, "The goal is {} steps from the start.", steps)?,
+            None => writeln!(f, "The goal can't be reached from the start!")?,
+        }
+
+        match maze_validator::validate(maze) {
+            Ok(()) => writeln!(f, "It's a perfect maze."),
+            Err(violations) => {
+                let loops = violations.iter().filter(|violation| matches!(violation, MazeViolation::Cycle(_, _))).count();
+                let unreachable = violations.iter().filter(|violation| matches!(violation, MazeViolation::Unreachable(_))).count();
+                let other = violations.len() - loops - unreachable;
+
+                writeln!(f, "It's not a perfect maze: {} loops, {} cells cut off, {} openings in the border.", loops, unreachable, other)
+            }
+        }
+    }
+}
+
+//Runs the editor on session, reading lines of commands from input until it quits or input runs out.
+//A line can hold several key commands, which are carried out one after the other. Saving and loading
+//without a file name uses path.
+pub fn run<R: BufRead, W: Write>(session: &mut EditorSession, path: &str, input: R, mut output: W) -> io::Result<()> {
+    writeln!(output, "w/a/s/d move, W/A/S/D toggle a wall, b/g place the start and goal, m marks the selection and c clears it,")?;
+    writeln!(output, "n regenerates the selection, u/r undo and redo, q quits. save [file], load [file] and algorithm <name> go on their own line.")?;
+    write!(output, "{}", session)?;
+
+    for line in input.lines() {
+        let line = line?;
+
+        match EditorCommand::parse_line(&line) {
+            Some(command) => {
+                if let Some(message) = carry_out(session, command, path) {
+                    writeln!(output, "{}", message)?;
+                }
+            }
+
+            None => {
+                for key in line.chars() {
+                    let command = match EditorCommand::parse(key) {
+                        Some(EditorCommand::Quit) => return Ok(()),
+                        Some(value) => value,
+                        None => continue,
+                    };
+
+                    if let Some(message) = carry_out(session, command, path) {
+                        writeln!(output, "{}", message)?;
+                    }
+                }
+            }
+        }
+
+        write!(output, "{}", session)?;
+        output.flush()?;
+    }
+
+    Ok(())
+}
+
+//Clears a terminal and moves its cursor back to the top left
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+//Runs the editor on session one key at a time, for a terminal that hands over keys as they're pressed.
+//The screen is drawn again after every key. The arrow keys move the cursor as well, and save, load and
+//algorithm are typed on a prompt opened with ':'. q, Ctrl-C or Ctrl-D quit, as does input running out.
+pub fn run_live<R: BufRead, W: Write>(session: &mut EditorSession, path: &str, input: R, mut output: W) -> io::Result<()> {
+    let mut bytes = input.bytes();
+    let mut message = None;
+
+    loop {
+        write!(output, "{}", CLEAR_SCREEN)?;
+        writeln!(output, "Arrows or w/a/s/d move, W/A/S/D toggle a wall, b/g place the start and goal, m marks the selection and c clears it,")?;
+        writeln!(output, "n regenerates the selection, u/r undo and redo, q quits. Type :save [file], :load [file] or :algorithm <name>.")?;
+        write!(output, "{}", session)?;
+        if let Some(text) = message.take() {
+            writeln!(output, "{}", text)?;
+        }
+        output.flush()?;
+
+        let key = match bytes.next() {
+            Some(key) => key?,
+            None => return Ok(()),
+        };
+
+        let command = match key {
+            3 | 4 => return Ok(()),
+
+            //Arrow keys come as ESC [ and a letter
+            0x1b => match (bytes.next().transpose()?, bytes.next().transpose()?) {
+                (Some(b'['), Some(b'A')) => Some(EditorCommand::Move(Direction::North)),
+                (Some(b'['), Some(b'B')) => Some(EditorCommand::Move(Direction::South)),
+                (Some(b'['), Some(b'C')) => Some(EditorCommand::Move(Direction::East)),
+                (Some(b'['), Some(b'D')) => Some(EditorCommand::Move(Direction::West)),
+                _ => None,
+            },
+
+            b':' => {
+                let line = read_prompt(&mut bytes, &mut output)?;
+                let command = EditorCommand::parse_line(&line);
+                if command.is_none() && !line.trim().is_empty() {
+                    message = Some(format!("Unknown command \"{}\".", line.trim()));
+                }
+                command
+            }
+
+            other => EditorCommand::parse(other as char),
+        };
+
+        match command {
+            Some(EditorCommand::Quit) => return Ok(()),
+            Some(command) => message = carry_out(session, command, path).or(message),
+            None => {}
+        }
+    }
+}
+
+//Reads a line typed on the prompt, echoing it back since the terminal doesn't. Backspace takes back a character.
+fn read_prompt<R: BufRead, W: Write>(bytes: &mut io::Bytes<R>, output: &mut W) -> io::Result<String> {
+    let mut line = String::new();
+    write!(output, ":")?;
+    output.flush()?;
+
+    for byte in bytes {
+        match byte? {
+            b'\r' | b'\n' => break,
+            0x7f | 8 if line.pop().is_some() => write!(output, "\x08 \x08")?,
+            byte if byte.is_ascii_graphic() || byte == b' ' => {
+                line.push(byte as char);
+                write!(output, "{}", byte as char)?;
+            }
+            _ => {}
+        }
+        output.flush()?;
+    }
+
+    Ok(line)
+}
+
+//Carries out a single command, returning a message for the user if there's something to say
+fn carry_out(session: &mut EditorSession, command: EditorCommand, path: &str) -> Option<String> {
+    match command {
+        EditorCommand::Move(direction) => {
+            if session.move_cursor(direction) { None } else { Some("The cursor can't go that way.".to_string()) }
+        }
+        EditorCommand::ToggleWall(direction) => match session.toggle_wall(direction) {
+            Ok(()) => None,
+            Err(MazeError::OutsideMaze(_)) => Some("The border of the maze can't be toggled.".to_string()),
+            Err(error) => Some(error.to_string()),
+        },
+        EditorCommand::PlaceStart => {
+            if session.place_start() { None } else { Some("The start has to go on a cell of the maze.".to_string()) }
+        }
+        EditorCommand::PlaceGoal => {
+            if session.place_goal() { None } else { Some("The goal has to go on a cell of the maze.".to_string()) }
+        }
+        EditorCommand::Mark => {
+            session.mark();
+            None
+        }
+        EditorCommand::ClearSelection => {
+            session.clear_selection();
+            None
+        }
+        EditorCommand::Regenerate => session.regenerate(rand::random()).err().map(|error| error.to_string()),
+        EditorCommand::Undo => {
+            if session.undo() { None } else { Some("Nothing to undo.".to_string()) }
+        }
+        EditorCommand::Redo => {
+            if session.redo() { None } else { Some("Nothing to redo.".to_string()) }
+        }
+        EditorCommand::Save(file) => {
+            let file = file.unwrap_or_else(|| path.to_string());
+            match session.save(&file) {
+                Ok(()) => Some(format!("Saved to {}.", file)),
+                Err(error) => Some(format!("Could not save {}: {}", file, error)),
+            }
+        }
+        EditorCommand::Load(file) => {
+            let file = file.unwrap_or_else(|| path.to_string());
+            match session.load(&file) {
+                Ok(()) => Some(format!("Loaded {}.", file)),
+                Err(error) => Some(format!("Could not load {}: {}", file, error)),
+            }
+        }
+        EditorCommand::Algorithm(name) => match session.set_algorithm(&name) {
+            Ok(()) => None,
+            Err(error) => Some(error.to_string()),
+        },
+        EditorCommand::Quit => None,
+    }
+}
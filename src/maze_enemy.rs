@@ -0,0 +1,139 @@
+extern crate rand;
+
+use std::collections::VecDeque;
+
+use rand::{Rng, RngCore};
+use serde::{Serialize, Deserialize};
+
+use super::maze_3d::{Maze3D, Point3D, Direction3D};
+use super::maze_distance::DistanceMap;
+use super::maze_grid::MazeGrid;
+
+//How a monster picks its next move
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Behaviour {
+    RandomWalker,           //Steps through a random open side every turn
+    WallFollower,           //Keeps its right hand on the wall, and never takes stairs
+    Pursuer,                //Heads for the player along the shortest way
+    Patrol(Vec<Point3D>),   //Walks the shortest way to each point in turn, then starts again from the first
+}
+
+//A monster in a game. Monsters are stopped by walls and locked doors the same way the player is, but never pick up keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Enemy {
+    behaviour: Behaviour,
+    position: Point3D,
+    facing: Direction3D,
+    waypoint: usize,
+}
+
+impl Enemy {
+    //Constructor
+    pub fn new(behaviour: Behaviour, position: Point3D) -> Self {
+        Self {
+            behaviour,
+            position,
+            facing: Direction3D::East,
+            waypoint: 0,
+        }
+    }
+
+    //Getters
+    pub fn behaviour(&self) -> &Behaviour { &self.behaviour }
+    pub fn position(&self) -> Point3D { self.position }
+
+    //Moves the monster one cell, or leaves it where it is if it has nowhere to go.
+    //The only randomness comes from rng, so the same rng gives the same moves.
+    pub fn take_turn(&mut self, maze: &Maze3D, player: Point3D, rng: &mut dyn RngCore) {
+        let direction = match &self.behaviour {
+            Behaviour::RandomWalker => {
+                let open: Vec<Direction3D> = Direction3D::ALL.iter().copied().filter(|direction| can_move(maze, self.position, *direction)).collect();
+                if open.is_empty() { None } else { Some(open[rng.gen_range(0..open.len())]) }
+            }
+
+            Behaviour::WallFollower => {
+                let right = turn_right(self.facing);
+                [right, self.facing, right.opposite(), self.facing.opposite()].iter().copied()
+                    .find(|direction| can_move(maze, self.position, *direction))
+            }
+
+            Behaviour::Pursuer => first_step(maze, self.position, player),
+
+            Behaviour::Patrol(route) => {
+                if route.is_empty() { return; }
+
+                if self.position == route[self.waypoint % route.len()] {
+                    self.waypoint = (self.waypoint + 1) % route.len();
+                }
+                first_step(maze, self.position, route[self.waypoint % route.len()])
+            }
+        };
+
+        if let Some(next) = direction.and_then(|direction| maze.neighbor(self.position, direction)) {
+            self.facing = direction.unwrap_or(self.facing);
+            self.position = next;
+        }
+    }
+}
+
+//Whether a monster at point can step through its side in direction
+pub fn can_move(maze: &Maze3D, point: Point3D, direction: Direction3D) -> bool {
+    maze.neighbor(point, direction).is_some() && maze.cell(point).wall(direction).is_passable(&[])
+}
+
+//Cells for count monsters to start on, picked with rng from the farther half of the maze as seen from start
+pub fn spawn_points(maze: &Maze3D, start: Point3D, count: usize, rng: &mut dyn RngCore) -> Vec<Point3D> {
+    let distances = DistanceMap::new(maze, maze.cell_id(start));
+    let far = distances.max_distance().unwrap_or(0).div_ceil(2).max(1);
+
+    let mut candidates: Vec<usize> = (0..maze.cell_count()).filter(|cell| distances.distance(*cell).is_some_and(|distance| distance >= far)).collect();
+    let count = count.min(candidates.len());
+    for i in 0..count {
+        let random = rng.gen_range(i..candidates.len());
+        candidates.swap(i, random);
+    }
+
+    candidates[..count].iter().map(|cell| maze.point(*cell)).collect()
+}
+
+//Clockwise quarter turn, seen from above. Stairs are left as they are.
+fn turn_right(direction: Direction3D) -> Direction3D {
+    match direction {
+        Direction3D::North => Direction3D::East,
+        Direction3D::East => Direction3D::South,
+        Direction3D::South => Direction3D::West,
+        Direction3D::West => Direction3D::North,
+        other => other,
+    }
+}
+
+//Direction of the first step of the shortest way from one point to another, None if there's no way or they're the same point.
+//Ties go to the first direction in Direction3D::ALL.
+fn first_step(maze: &Maze3D, from: Point3D, to: Point3D) -> Option<Direction3D> {
+    let mut first: Vec<Option<Direction3D>> = vec![None; maze.cell_count()];
+    let mut queue = VecDeque::new();
+    let start = maze.cell_id(from);
+    let target = maze.cell_id(to);
+
+    queue.push_back(start);
+    while let Some(current) = queue.pop_front() {
+        if current == target { return first[current]; }
+
+        let point = maze.point(current);
+        for direction in Direction3D::ALL.iter() {
+            if !can_move(maze, point, *direction) { continue; }
+
+            let next = match maze.neighbor(point, *direction) {
+                Some(value) => maze.cell_id(value),
+                None => continue,
+            };
+            if next == start || first[next].is_some() { continue; }
+
+            first[next] = first[current].or(Some(*direction));
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fmt;
+
+use super::maze_grid::Point;
+use super::maze_mask::MaskError;
+use super::maze_room::Room;
+
+//Most cells a maze can have. Every cell owns its walls through reference counted pointers,
+//so much larger mazes run out of memory long before they're useful.
+pub const MAX_CELLS: usize = 1 << 20;
+
+//Reasons a maze can't be built with the dimensions or configuration it was given
+#[derive(Debug)]
+pub enum MazeError {
+    ZeroSize,                   //One of the dimensions is zero, so the maze would have no cells
+    TooLarge(usize),            //The maze would have more than MAX_CELLS cells. Holds the number of cells asked for, or usize::MAX if that overflows.
+    TooNarrow(usize),           //Triangles in a delta maze one column wide only touch in pairs, so taller mazes can't be connected. Holds the height.
+    InvalidDensity(f64),        //Weave crossing density isn't a number from 0 to 1
+    InvalidRoom(Room),          //Room is outside the maze, covers a disabled cell or overlaps another room
+    InvalidRegion(usize),       //Region with this index is empty, outside the maze or overlaps another region
+    NoSuchRegion(usize),        //The maze has no region with this index
+    NotNeighbors(Point, Point), //There's no wall to lock or edit, as the cells aren't enabled cells next to each other
+    LockedWall(Point, Point),   //The wall between the two cells is locked, so it can't be edited
+    OutsideMaze(Point),         //The point isn't inside the maze
+    ForcedLoop(usize, usize),   //The forced passage between the two cells makes a loop with the other forced passages
+    WalledOff(usize, usize),    //The forced wall between the two cells splits the maze, the forced walls around it leave no way round
+    NoRoomForItems,             //The way to the goal is too short for the doors asked for, or the maze has too few cells for the items
+    Mask(MaskError),
+}
+
+impl fmt::Display for MazeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeError::ZeroSize => write!(f, "maze dimensions must be at least 1"),
+            MazeError::TooLarge(cells) => write!(f, "maze would have {} cells, the most allowed is {}", cells, MAX_CELLS),
+            MazeError::TooNarrow(height) => write!(f, "a delta maze one triangle wide can be at most 2 rows tall, not {}", height),
+            MazeError::InvalidDensity(density) => write!(f, "crossing density must be from 0 to 1, not {}", density),
+            MazeError::InvalidRoom(room) => {
+                write!(f, "room at ({}, {}) of {}x{} cells doesn't fit in the maze", room.x, room.y, room.width, room.height)
+            }
+            MazeError::InvalidRegion(index) => write!(f, "region {} is empty, outside the maze or overlaps another region", index),
+            MazeError::NoSuchRegion(index) => write!(f, "maze has no region {}", index),
+            MazeError::NotNeighbors(one, two) => {
+                write!(f, "cells ({}, {}) and ({}, {}) aren't enabled neighbors, so there's no wall between them", one.x, one.y, two.x, two.y)
+            }
+            MazeError::LockedWall(one, two) => write!(f, "wall between cells ({}, {}) and ({}, {}) is locked", one.x, one.y, two.x, two.y),
+            MazeError::OutsideMaze(point) => write!(f, "point ({}, {}) is outside the maze", point.x, point.y),
+            MazeError::ForcedLoop(one, two) => write!(f, "forced passage between cells {} and {} makes a loop", one, two),
+            MazeError::WalledOff(one, two) => write!(f, "forced wall between cells {} and {} cuts the maze in two", one, two),
+            MazeError::NoRoomForItems => write!(f, "maze doesn't have room for the items and doors asked for"),
+            MazeError::Mask(error) => write!(f, "invalid maze mask: {}", error),
+        }
+    }
+}
+
+impl Error for MazeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MazeError::Mask(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<MaskError> for MazeError {
+    fn from(error: MaskError) -> Self {
+        MazeError::Mask(error)
+    }
+}
+
+//Checks that a maze with the given dimensions has at least one cell and no more than MAX_CELLS.
+//Returns the number of cells.
+pub fn check_dimensions(dimensions: &[usize]) -> Result<usize, MazeError> {
+    if dimensions.contains(&0) {
+        return Err(MazeError::ZeroSize);
+    }
+
+    let cells = dimensions.iter().try_fold(1usize, |product, dimension| product.checked_mul(*dimension)).unwrap_or(usize::MAX);
+    if cells > MAX_CELLS {
+        return Err(MazeError::TooLarge(cells));
+    }
+
+    Ok(cells)
+}
+
+//Checks that the rectangle of width x height cells with its top left cell at (x, y) fits in a maze of maze_width x maze_height cells.
+//Fails with the bottom right cell of the rectangle if it doesn't.
+pub fn check_area(x: usize, y: usize, width: usize, height: usize, maze_width: usize, maze_height: usize) -> Result<(), MazeError> {
+    let fits = |start: usize, length: usize, limit: usize| start.checked_add(length).is_some_and(|end| end <= limit);
+    if fits(x, width, maze_width) && fits(y, height, maze_height) {
+        return Ok(());
+    }
+
+    Err(MazeError::OutsideMaze(Point { x: x.saturating_add(width.max(1) - 1), y: y.saturating_add(height.max(1) - 1) }))
+}
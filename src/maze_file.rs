@@ -0,0 +1,246 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::maze::{Maze, Wraparound};
+use super::maze_algorithm::{MazeAlgorithm, check_locked_walls};
+use super::maze_error::{MazeError, check_dimensions};
+use super::maze_grid::{MazeGrid, Point};
+use super::maze_mask::{MazeMask, MaskError};
+use super::maze_regenerate::Regenerate;
+use super::maze_wall::MazeWall;
+
+#[derive(Debug)]
+pub enum FileError {
+    Io(io::Error),
+    Parse(usize, String),   //Line number, counting from 1, and what's wrong with it
+    Maze(MazeError),        //The file reads fine, but the maze in it can't be built
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileError::Io(error) => write!(f, "could not read maze file: {}", error),
+            FileError::Parse(line, reason) => write!(f, "line {}: {}", line, reason),
+            FileError::Maze(error) => write!(f, "invalid maze: {}", error),
+        }
+    }
+}
+
+impl Error for FileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FileError::Io(error) => Some(error),
+            FileError::Maze(error) => Some(error),
+            FileError::Parse(_, _) => None,
+        }
+    }
+}
+
+impl From<io::Error> for FileError {
+    fn from(error: io::Error) -> Self {
+        FileError::Io(error)
+    }
+}
+
+impl From<MazeError> for FileError {
+    fn from(error: MazeError) -> Self {
+        FileError::Maze(error)
+    }
+}
+
+//A maze saved as text, along with the start and goal a designer placed in it. The first line is
+//    maze <width> <height> <none|cylinder|torus>
+//and the rest is a drawing of the maze, two characters for every cell plus one for the edges:
+//    +#+#+#+
+//     S  . #
+//    +#+%+ +
+//    #x#  G
+//    +#+#+#+
+//Corners are +. A wall is # when it's up and a space when it's down, % when it's locked up and . when it's locked down.
+//A cell is a space, x if the mask leaves it out, S for the start, G for the goal, and * if it's both.
+//The border around the maze is drawn, but is always rebuilt from the maze's exits when it's read back.
+pub struct MazeFile {
+    pub maze: Maze,
+    pub start: Point,
+    pub goal: Point,
+}
+
+impl MazeFile {
+    //Constructor. The start and goal are the maze's entrance and exit, or the top left corner if the maze has no cells.
+    pub fn new(maze: Maze) -> Self {
+        let (start, goal) = match maze.exits() {
+            Some((entrance, exit)) => (maze.point(entrance), maze.point(exit)),
+            None => (Point { x: 0, y: 0 }, Point { x: 0, y: 0 }),
+        };
+
+        Self { maze, start, goal }
+    }
+
+    //Reads a maze from its text. The maze is given algorithm to be regenerated with later.
+    pub fn from_text(text: &str, algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, FileError> {
+        let mut lines = text.lines();
+        let header: Vec<&str> = lines.next().unwrap_or("").split_whitespace().collect();
+
+        let (width, height, wraparound) = match header.as_slice() {
+            ["maze", width, height, wraparound] => {
+                let size = |text: &str| text.parse::<usize>().map_err(|_| FileError::Parse(1, format!("\"{}\" isn't a size", text)));
+                let wraparound = match *wraparound {
+                    "none" => Wraparound::None,
+                    "cylinder" => Wraparound::Cylinder,
+                    "torus" => Wraparound::Torus,
+                    other => return Err(FileError::Parse(1, format!("unknown wraparound \"{}\", expected none, cylinder or torus", other))),
+                };
+
+                (size(width)?, size(height)?, wraparound)
+            }
+            _ => return Err(FileError::Parse(1, "expected \"maze <width> <height> <none|cylinder|torus>\"".to_string())),
+        };
+
+        //The size is checked before it's used to work out how long the drawing is
+        check_dimensions(&[width, height])?;
+
+        //Editors like to strip trailing spaces, so short lines are read as if they carried on with spaces
+        let rows: Vec<Vec<char>> = lines.map(|line| line.chars().collect()).collect();
+        if rows.len() < 2 * height + 1 {
+            return Err(FileError::Parse(rows.len() + 2, format!("drawing of a maze {} cells high needs {} lines", height, 2 * height + 1)));
+        }
+        let at = |x: usize, y: usize| rows[y].get(x).copied().unwrap_or(' ');
+
+        let mut maze = Maze::try_with_wraparound(width, height, wraparound, algorithm)?;
+        let mut mask = MazeMask::new(width, height);
+        let mut start = None;
+        let mut goal = None;
+
+        for x in 0..width {
+            for y in 0..height {
+                match at(2 * x + 1, 2 * y + 1) {
+                    ' ' => {}
+                    'x' => mask.set_enabled(x, y, false),
+                    'S' => start = Some(Point { x, y }),
+                    'G' => goal = Some(Point { x, y }),
+                    '*' => {
+                        start = Some(Point { x, y });
+                        goal = Some(Point { x, y });
+                    }
+                    other => return Err(FileError::Parse(2 * y + 3, format!("unknown cell \"{}\"", other))),
+                }
+            }
+        }
+
+        if mask.is_empty() {
+            return Err(MazeError::from(MaskError::Empty).into());
+        }
+        if (0..width).any(|x| (0..height).any(|y| !mask.is_enabled(x, y))) {
+            maze.set_mask(Some(mask));
+        }
+        maze.reset();
+
+        //Every wall between two enabled cells, found at the right and bottom of each cell
+        for x in 0..width {
+            for y in 0..height {
+                let one = Point { x, y };
+                let right = if x + 1 < width || maze.wraparound().wraps_horizontally() { Some(Point { x: (x + 1) % width, y }) } else { None };
+                let down = if y + 1 < height || maze.wraparound().wraps_vertically() { Some(Point { x, y: (y + 1) % height }) } else { None };
+
+                let walls = [(right, 2 * x + 2, 2 * y + 1), (down, 2 * x + 1, 2 * y + 2)];
+                for (index, (two, column, row)) in walls.iter().enumerate() {
+                    let between_cells = two.is_some_and(|two| two != one && maze.is_enabled_at(one) && maze.is_enabled_at(two));
+                    if !between_cells { continue; }
+
+                    //Found by where it is rather than by the cells it divides, as on a maze two cells wide
+                    //that wraps around, the cells share two walls
+                    let cell = &maze.cells()[x][y];
+                    let mut wall = if index == 0 { cell.right_wall_mut() } else { cell.bottom_wall_mut() }.borrow_mut();
+
+                    match at(*column, *row) {
+                        '#' => {}
+                        '%' => wall.locked = true,
+                        '.' => {
+                            wall.active = false;
+                            wall.locked = true;
+                        }
+                        ' ' => wall.active = false,
+                        other => return Err(FileError::Parse(row + 2, format!("unknown wall \"{}\"", other))),
+                    }
+                }
+            }
+        }
+
+        check_locked_walls(&maze)?;
+        maze.open_exits();
+
+        //Without a start or goal marked, the maze's entrance and exit are used
+        let mut file = Self::new(maze);
+        if let Some(value) = start { file.start = value; }
+        if let Some(value) = goal { file.goal = value; }
+
+        Ok(file)
+    }
+
+    //Writes the maze as text that from_text reads back
+    pub fn to_text(&self) -> String {
+        write_text(&self.maze, self.start, self.goal)
+    }
+
+    //Reads a maze file. The maze is given algorithm to be regenerated with later.
+    pub fn load<P: AsRef<Path>>(path: P, algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, FileError> {
+        Self::from_text(&fs::read_to_string(path)?, algorithm)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+}
+
+//Writes maze as the text of a maze file, with start and goal marked in it
+pub fn write_text(maze: &Maze, start: Point, goal: Point) -> String {
+    let width = maze.width();
+    let height = maze.height();
+    let cells = maze.cells();
+
+    let wraparound = match maze.wraparound() {
+        Wraparound::None => "none",
+        Wraparound::Cylinder => "cylinder",
+        Wraparound::Torus => "torus",
+    };
+
+    let wall = |wall: &MazeWall| match (wall.locked, wall.active) {
+        (true, true) => '%',
+        (true, false) => '.',
+        (false, true) => '#',
+        (false, false) => ' ',
+    };
+
+    let mut rows = vec![vec!['+'; 2 * width + 1]; 2 * height + 1];
+
+    for x in 0..width {
+        for y in 0..height {
+            let point = Point { x, y };
+            let cell = &cells[x][y];
+
+            rows[2 * y + 1][2 * x + 1] = match (point == start, point == goal) {
+                _ if !maze.is_enabled_at(point) => 'x',
+                (true, true) => '*',
+                (true, false) => 'S',
+                (false, true) => 'G',
+                (false, false) => ' ',
+            };
+
+            rows[2 * y][2 * x + 1] = wall(&cell.top_wall());
+            rows[2 * y + 1][2 * x] = wall(&cell.left_wall());
+            rows[2 * y + 2][2 * x + 1] = wall(&cell.bottom_wall());
+            rows[2 * y + 1][2 * x + 2] = wall(&cell.right_wall());
+        }
+    }
+
+    let mut text = format!("maze {} {} {}\n", width, height, wraparound);
+    for row in rows {
+        text.extend(row);
+        text.push('\n');
+    }
+
+    text
+}
@@ -0,0 +1,327 @@
+extern crate gif;
+
+use std::io::{self, Write};
+
+use gif::{Encoder, EncodingError, Frame, Repeat};
+
+use super::maze::Maze;
+use super::maze_algorithm::MazeStep;
+use super::maze_grid::Point;
+use super::maze_regenerate::Regenerate;
+use super::maze_solver;
+
+pub use super::maze_render::Colour;
+
+//Settings for an animated GIF export. Delays are in hundredths of a second.
+#[derive(Clone, Debug)]
+pub struct GifOptions {
+    pub scale: u16,             //Size in pixels of a single cell or wall block
+    pub steps_per_frame: usize,
+    pub frame_delay: u16,
+    pub final_delay: u16,       //How long the finished maze is shown before the animation loops
+    pub background: Colour,     //Cells the algorithm hasn't reached yet
+    pub wall: Colour,
+    pub visited: Colour,
+    pub frontier: Colour,
+    pub carved: Colour,
+    pub path: Colour,
+    pub outside: Colour,        //Cells disabled by the maze's mask
+}
+
+impl Default for GifOptions {
+    fn default() -> Self {
+        Self {
+            scale: 8,
+            steps_per_frame: 1,
+            frame_delay: 4,
+            final_delay: 200,
+            background: [255, 255, 255],
+            wall: [0, 0, 0],
+            visited: [120, 160, 230],
+            frontier: [240, 200, 60],
+            carved: [230, 230, 230],
+            path: [220, 50, 50],
+            outside: [255, 255, 255],
+        }
+    }
+}
+
+//Palette indexes of each colour in GifOptions
+const BACKGROUND: u8 = 0;
+const WALL: u8 = 1;
+const VISITED: u8 = 2;
+const FRONTIER: u8 = 3;
+const CARVED: u8 = 4;
+const PATH: u8 = 5;
+const OUTSIDE: u8 = 6;
+
+//Exports the generation of the maze with its current algorithm as an animated GIF.
+//The maze is regenerated in the process.
+pub fn export_generation<W: Write>(maze: &mut Maze, options: &GifOptions, writer: W) -> Result<(), EncodingError> {
+    let mut steps = Vec::new();
+    maze.regenerate_traced(&mut |step| steps.push(step));
+
+    //Generation starts from a maze full of walls
+    let animation = Animation::new(maze, false);
+    animation.export(&steps, options, writer)
+}
+
+//Exports a breadth first search solve of the maze as an animated GIF
+pub fn export_solve<W: Write>(maze: &Maze, options: &GifOptions, writer: W) -> Result<(), EncodingError> {
+    let mut steps = Vec::new();
+    maze_solver::solve_traced(maze, &mut |step| steps.push(step));
+
+    let animation = Animation::new(maze, true);
+    animation.export(&steps, options, writer)
+}
+
+//Replays the steps of a run on a copy of the maze's state, rendering frames as it goes
+struct Animation<'a> {
+    maze: &'a Maze,
+    open_right: Vec<Vec<bool>>,
+    open_down: Vec<Vec<bool>>,
+    open_wrap_x: Vec<bool>,     //Passages across the joined left and right edges, by row
+    open_wrap_y: Vec<bool>,     //Passages across the joined top and bottom edges, by column
+    visited: Vec<Vec<bool>>,
+    frontier: Vec<Vec<bool>>,
+    carved: Vec<Vec<bool>>,
+    path: Vec<Vec<bool>>,
+}
+
+impl<'a> Animation<'a> {
+    //If with_passages is false, every wall between two cells starts out active
+    fn new(maze: &'a Maze, with_passages: bool) -> Self {
+        let width = maze.width();
+        let height = maze.height();
+
+        let mut open_right = vec![vec![false; height]; width];
+        let mut open_down = vec![vec![false; height]; width];
+
+        for x in 0..width {
+            for y in 0..height {
+                let cell = &maze.cells()[x][y];
+                let right = Point { x: x + 1, y };
+                let down = Point { x, y: y + 1 };
+
+                //Walls next to disabled cells are only ever opened as exits, so those are always copied
+                let copy_right = with_passages || !cell.is_enabled() || !maze.is_enabled_at(right);
+                let copy_down = with_passages || !cell.is_enabled() || !maze.is_enabled_at(down);
+
+                open_right[x][y] = copy_right && x < width - 1 && !cell.right_wall().active;
+                open_down[x][y] = copy_down && y < height - 1 && !cell.bottom_wall().active;
+            }
+        }
+
+        //Walls on joined edges are tracked like any other wall between two cells
+        let wraparound = maze.wraparound();
+        let open_wrap_x = (0..height)
+            .map(|y| with_passages && wraparound.wraps_horizontally() && width > 1 && !maze.cells()[0][y].left_wall().active)
+            .collect();
+        let open_wrap_y = (0..width)
+            .map(|x| with_passages && wraparound.wraps_vertically() && height > 1 && !maze.cells()[x][0].top_wall().active)
+            .collect();
+
+        Self {
+            maze,
+            open_right,
+            open_down,
+            open_wrap_x,
+            open_wrap_y,
+            visited: vec![vec![false; height]; width],
+            frontier: vec![vec![false; height]; width],
+            carved: vec![vec![false; height]; width],
+            path: vec![vec![false; height]; width],
+        }
+    }
+
+    fn apply(&mut self, step: MazeStep) {
+        let point = |cell: usize| self.maze.point(cell);
+
+        match step {
+            MazeStep::Visit(cell) => {
+                let point = point(cell);
+                self.visited[point.x][point.y] = true;
+                self.frontier[point.x][point.y] = false;
+            }
+
+            MazeStep::Frontier(cell) => {
+                let point = point(cell);
+                self.frontier[point.x][point.y] = true;
+            }
+
+            MazeStep::Carve(one, two) => {
+                let one = point(one);
+                let two = point(two);
+                self.carved[one.x][one.y] = true;
+                self.carved[two.x][two.y] = true;
+
+                //Order the cells so the wall is always the right or bottom wall of the first cell
+                //Cells more than one apart are joined across the edges of a wrapped maze
+                let (first, second) = if (one.x, one.y) < (two.x, two.y) { (one, two) } else { (two, one) };
+                if first.x != second.x {
+                    if second.x - first.x > 1 { self.open_wrap_x[first.y] = true; }
+                    else { self.open_right[first.x][first.y] = true; }
+                }
+                else if second.y - first.y > 1 {
+                    self.open_wrap_y[first.x] = true;
+                }
+                else {
+                    self.open_down[first.x][first.y] = true;
+                }
+            }
+
+            MazeStep::Path(cell) => {
+                let point = point(cell);
+                self.path[point.x][point.y] = true;
+            }
+        }
+    }
+
+    fn cell_colour(&self, point: Point) -> u8 {
+        let (x, y) = (point.x, point.y);
+
+        if !self.maze.is_enabled_at(point) { OUTSIDE }
+        else if self.path[x][y] { PATH }
+        else if self.frontier[x][y] { FRONTIER }
+        else if self.carved[x][y] { CARVED }
+        else if self.visited[x][y] { VISITED }
+        else { BACKGROUND }
+    }
+
+    //A passage between two cells takes the colour of the least advanced of the two
+    fn passage_colour(&self, one: Point, two: Point) -> u8 {
+        let rank = |colour: u8| match colour {
+            BACKGROUND => 0,
+            VISITED => 1,
+            CARVED => 2,
+            FRONTIER => 3,
+            _ => 4,
+        };
+
+        let colour_one = self.cell_colour(one);
+        let colour_two = self.cell_colour(two);
+        if rank(colour_one) <= rank(colour_two) { colour_one } else { colour_two }
+    }
+
+    //Renders the current state in the same block layout as the text renderer:
+    //every cell, wall and corner is one scale x scale block
+    fn render(&self, scale: usize) -> Vec<u8> {
+        let width = self.maze.width();
+        let height = self.maze.height();
+        let cells = self.maze.cells();
+        let wraps_x = self.maze.wraparound().wraps_horizontally() && width > 1;
+        let wraps_y = self.maze.wraparound().wraps_vertically() && height > 1;
+
+        let blocks_x = 2 * width + 1;
+        let blocks_y = 2 * height + 1;
+        let row_length = blocks_x * scale;
+        let mut pixels = vec![WALL; row_length * blocks_y * scale];
+
+        for block_y in 0..blocks_y {
+            for block_x in 0..blocks_x {
+                let x = block_x / 2;
+                let y = block_y / 2;
+
+                let colour = match (block_x % 2 == 1, block_y % 2 == 1) {
+                    //Cell
+                    (true, true) => self.cell_colour(Point { x, y }),
+
+                    //Walls that don't border an enabled cell are outside of the maze's outline
+                    (false, true) if !self.maze.is_enabled_at(Point { x: x.wrapping_sub(1), y }) && !self.maze.is_enabled_at(Point { x, y }) => OUTSIDE,
+                    (true, false) if !self.maze.is_enabled_at(Point { x, y: y.wrapping_sub(1) }) && !self.maze.is_enabled_at(Point { x, y }) => OUTSIDE,
+
+                    //Vertical wall
+                    (false, true) => {
+                        if (block_x == 0 || block_x == blocks_x - 1) && wraps_x {
+                            let x = if block_x == 0 { 0 } else { width - 1 };
+                            if self.open_wrap_x[y] { self.cell_colour(Point { x, y }) } else { WALL }
+                        }
+                        else if block_x == 0 {
+                            if cells[0][y].left_wall().active { WALL } else { self.cell_colour(Point { x: 0, y }) }
+                        }
+                        else if block_x == blocks_x - 1 {
+                            if cells[width - 1][y].right_wall().active { WALL } else { self.cell_colour(Point { x: width - 1, y }) }
+                        }
+                        else if self.open_right[x - 1][y] {
+                            self.passage_colour(Point { x: x - 1, y }, Point { x, y })
+                        }
+                        else { WALL }
+                    }
+
+                    //Horizontal wall
+                    (true, false) => {
+                        if (block_y == 0 || block_y == blocks_y - 1) && wraps_y {
+                            let y = if block_y == 0 { 0 } else { height - 1 };
+                            if self.open_wrap_y[x] { self.cell_colour(Point { x, y }) } else { WALL }
+                        }
+                        else if block_y == 0 {
+                            if cells[x][0].top_wall().active { WALL } else { self.cell_colour(Point { x, y: 0 }) }
+                        }
+                        else if block_y == blocks_y - 1 {
+                            if cells[x][height - 1].bottom_wall().active { WALL } else { self.cell_colour(Point { x, y: height - 1 }) }
+                        }
+                        else if self.open_down[x][y - 1] {
+                            self.passage_colour(Point { x, y: y - 1 }, Point { x, y })
+                        }
+                        else { WALL }
+                    }
+
+                    //Corner
+                    (false, false) => if self.maze.corner_visible(x, y) { WALL } else { OUTSIDE },
+                };
+
+                if colour == WALL { continue; }
+
+                for pixel_y in (block_y * scale)..((block_y + 1) * scale) {
+                    let start = pixel_y * row_length + block_x * scale;
+                    for pixel in pixels[start..(start + scale)].iter_mut() {
+                        *pixel = colour;
+                    }
+                }
+            }
+        }
+
+        pixels
+    }
+
+    fn export<W: Write>(mut self, steps: &[MazeStep], options: &GifOptions, writer: W) -> Result<(), EncodingError> {
+        let scale = usize::from(options.scale.max(1));
+        let image_width = (2 * self.maze.width() + 1) * scale;
+        let image_height = (2 * self.maze.height() + 1) * scale;
+
+        if self.maze.width() == 0 || self.maze.height() == 0 || image_width > usize::from(u16::MAX) || image_height > usize::from(u16::MAX) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "maze is too small or too large to export as a GIF").into());
+        }
+
+        let image_width = image_width as u16;
+        let image_height = image_height as u16;
+
+        let mut palette = Vec::new();
+        for colour in [options.background, options.wall, options.visited, options.frontier, options.carved, options.path, options.outside].iter() {
+            palette.extend_from_slice(colour);
+        }
+
+        let mut encoder = Encoder::new(writer, image_width, image_height, &palette)?;
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let mut write_frame = |animation: &Animation, delay: u16| -> Result<(), EncodingError> {
+            let mut frame = Frame::from_indexed_pixels(image_width, image_height, &animation.render(scale), None);
+            frame.delay = delay;
+            encoder.write_frame(&frame)
+        };
+
+        write_frame(&self, options.frame_delay)?;
+
+        let steps_per_frame = options.steps_per_frame.max(1);
+        for chunk in steps.chunks(steps_per_frame) {
+            for step in chunk {
+                self.apply(*step);
+            }
+
+            write_frame(&self, options.frame_delay)?;
+        }
+
+        //Hold the finished maze on screen before looping
+        write_frame(&self, options.final_delay)
+    }
+}
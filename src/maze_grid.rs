@@ -0,0 +1,94 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use super::maze_wall::MazeWall;
+
+//Position of a cell on a two dimensional grid
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+//Direction a passage runs in on a grid laid out in rows and columns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Horizontal,
+    Vertical,
+}
+
+//Way from a cell to one of its four neighbors on a grid laid out in rows and columns. North is up.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+//A maze seen as a graph, independent of the shape of its cells. Cells are numbered from 0 to cell_count - 1,
+//and every pair of neighboring cells shares a wall. The maze algorithms only ever see a maze through this trait,
+//so they work the same on every topology.
+pub trait MazeGrid {
+    fn cell_count(&self) -> usize;
+
+    //Cells disabled by a mask aren't part of the maze
+    fn is_enabled(&self, cell: usize) -> bool;
+
+    //Every enabled cell next to cell, along with the wall between them
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)>;
+
+    //Returns the entrance and exit cells, None if the maze has no enabled cells
+    fn exits(&self) -> Option<(usize, usize)>;
+
+    //Opens the border walls that the entrance and exit lead out through
+    fn open_exits(&self);
+
+    //Direction of the passage between cell and its neighbor, used to bias the maze algorithms.
+    //None if the maze has no rows and columns, or the passage goes between floors.
+    fn axis(&self, _cell: usize, _neighbor: usize) -> Option<Axis> { None }
+
+    //Cost of stepping into cell, used by the cost aware solvers. Every cell costs the same unless the maze says otherwise.
+    fn cost(&self, _cell: usize) -> u32 { 1 }
+
+    //Every cell that can be reached from cell in one move, i.e. without crossing an active wall
+    fn passages(&self, cell: usize) -> Vec<usize> {
+        self.neighbors(cell).into_iter()
+            .filter(|(_, wall)| !wall.borrow().active)
+            .map(|(neighbor, _)| neighbor)
+            .collect()
+    }
+}
+
+//A grid with some of its enabled cells left out, so an algorithm can be run on just part of a maze.
+//Walls between a cell that's kept and one that's left out are never touched.
+pub struct FilteredGrid<'a> {
+    grid: &'a dyn MazeGrid,
+    kept: Vec<bool>,
+}
+
+impl<'a> FilteredGrid<'a> {
+    //Constructor, keep decides which of grid's cells are part of the view
+    pub fn new(grid: &'a dyn MazeGrid, keep: &dyn Fn(usize) -> bool) -> Self {
+        let kept = (0..grid.cell_count()).map(|cell| grid.is_enabled(cell) && keep(cell)).collect();
+        Self { grid, kept }
+    }
+}
+
+impl<'a> MazeGrid for FilteredGrid<'a> {
+    fn cell_count(&self) -> usize { self.grid.cell_count() }
+
+    fn is_enabled(&self, cell: usize) -> bool { self.kept[cell] }
+
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        self.grid.neighbors(cell).into_iter().filter(|(neighbor, _)| self.kept[*neighbor]).collect()
+    }
+
+    fn exits(&self) -> Option<(usize, usize)> { self.grid.exits() }
+
+    fn open_exits(&self) { self.grid.open_exits(); }
+
+    fn axis(&self, cell: usize, neighbor: usize) -> Option<Axis> { self.grid.axis(cell, neighbor) }
+
+    fn cost(&self, cell: usize) -> u32 { self.grid.cost(cell) }
+}
@@ -0,0 +1,322 @@
+extern crate rand;
+
+use std::fmt;
+use std::rc::Rc;
+use std::cell::{RefCell, Ref};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use super::maze_wall::MazeWall;
+use super::maze_algorithm::{MazeAlgorithm, MazeStep};
+use super::maze_grid::{MazeGrid, Point};
+use super::maze_regenerate::Regenerate;
+use super::maze_render::{MazeDrawing, Stroke};
+use super::maze_error::{MazeError, check_dimensions};
+
+//The six sides of a flat topped hexagon
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::North,
+        HexDirection::NorthEast,
+        HexDirection::SouthEast,
+        HexDirection::South,
+        HexDirection::SouthWest,
+        HexDirection::NorthWest,
+    ];
+
+    pub fn opposite(self) -> Self {
+        match self {
+            HexDirection::North => HexDirection::South,
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+            HexDirection::South => HexDirection::North,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            HexDirection::North => 0,
+            HexDirection::NorthEast => 1,
+            HexDirection::SouthEast => 2,
+            HexDirection::South => 3,
+            HexDirection::SouthWest => 4,
+            HexDirection::NorthWest => 5,
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct HexCell {
+    walls: [Option<Rc<RefCell<MazeWall>>>; 6],
+}
+
+impl HexCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Getters
+    pub fn wall(&self, direction: HexDirection) -> Ref<'_, MazeWall> {
+        self.wall_mut(direction).borrow()
+    }
+
+    //Mutable Access
+    pub fn wall_mut(&self, direction: HexDirection) -> &Rc<RefCell<MazeWall>> {
+        match &self.walls[direction.index()] {
+            Some(value) => value,
+            None => panic!("HexCell was not properly constructed."),
+        }
+    }
+
+    //Setters
+    pub fn set_wall(&mut self, direction: HexDirection, wall: Rc<RefCell<MazeWall>>) { self.walls[direction.index()] = Some(wall); }
+}
+
+//A maze of flat topped hexagons, laid out in columns. Every odd column is shifted down by half a cell.
+pub struct HexMaze {
+    maze_algorithm: Box<dyn MazeAlgorithm>,
+    cells: Vec<Vec<HexCell>>,
+    walls: Vec<Rc<RefCell<MazeWall>>>,
+    seed: u64,
+    width: usize,
+    height: usize,
+}
+
+impl HexMaze {
+    //Constructor
+    pub fn new(width: usize, height: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        let mut tmp = Self {
+            maze_algorithm,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            seed: 0,
+            width,
+            height,
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor that checks the dimensions first. Fails if either is zero or the maze would be too large.
+    pub fn try_new(width: usize, height: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[width, height])?;
+        Ok(Self::new(width, height, maze_algorithm))
+    }
+
+    //Reconstructs maze with the dimensions width and height. No algorithm is applied on the maze.
+    pub fn reconstruct(&mut self) {
+        self.cells = vec![vec![HexCell::new(); self.height]; self.width];
+        self.walls = Vec::new();
+
+        //Every wall is built once, by the first of its two cells, and then shared with the neighboring cell
+        for x in 0..self.width {
+            for y in 0..self.height {
+                for direction in HexDirection::ALL.iter() {
+                    if self.cells[x][y].walls[direction.index()].is_some() { continue; }
+
+                    let wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                    self.cells[x][y].set_wall(*direction, Rc::clone(&wall));
+
+                    if let Some(neighbor) = self.neighbor(Point { x, y }, *direction) {
+                        self.cells[neighbor.x][neighbor.y].set_wall(direction.opposite(), Rc::clone(&wall));
+                    }
+
+                    self.walls.push(wall);
+                }
+            }
+        }
+    }
+
+    pub fn set_algorithm(&mut self, new_algorithm: Box<dyn MazeAlgorithm>) {
+        self.maze_algorithm = new_algorithm;
+    }
+
+    //Getters
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn cells(&self) -> &Vec<Vec<HexCell>> { &self.cells }
+
+    //Cells are numbered column by column for the maze algorithms
+    pub fn cell_id(&self, point: Point) -> usize { point.x * self.height + point.y }
+    pub fn point(&self, cell: usize) -> Point { Point { x: cell / self.height, y: cell % self.height } }
+
+    //Returns the cell next to point in direction, None if that is outside of the maze
+    pub fn neighbor(&self, point: Point, direction: HexDirection) -> Option<Point> {
+        let odd = point.x % 2 == 1;
+        let (x, y) = (point.x, point.y);
+
+        //Odd columns sit half a cell lower, so their diagonal neighbors are one row further down
+        let neighbor = match direction {
+            HexDirection::North => Point { x, y: y.wrapping_sub(1) },
+            HexDirection::South => Point { x, y: y + 1 },
+            HexDirection::NorthEast => Point { x: x + 1, y: if odd { y } else { y.wrapping_sub(1) } },
+            HexDirection::SouthEast => Point { x: x + 1, y: if odd { y + 1 } else { y } },
+            HexDirection::NorthWest => Point { x: x.wrapping_sub(1), y: if odd { y } else { y.wrapping_sub(1) } },
+            HexDirection::SouthWest => Point { x: x.wrapping_sub(1), y: if odd { y + 1 } else { y } },
+        };
+
+        if neighbor.x < self.width && neighbor.y < self.height { Some(neighbor) } else { None }
+    }
+
+    //Centre of a cell in a drawing where every side of a hexagon is one unit long
+    fn centre(&self, point: Point) -> (f64, f64) {
+        let row_height = 3f64.sqrt();
+        let x = 1.0 + 1.5 * point.x as f64;
+        let y = row_height * (point.y as f64 + 0.5 + if point.x % 2 == 1 { 0.5 } else { 0.0 });
+        (x, y)
+    }
+}
+
+impl Regenerate for HexMaze {
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>] { &self.walls }
+
+    //Same as regenerate_with_seed, but reports every step of the algorithm to trace
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep)) {
+        self.seed = seed;
+        self.reset();
+        self.maze_algorithm.generate_traced(&*self, &mut StdRng::seed_from_u64(seed), trace);
+    }
+}
+
+impl MazeGrid for HexMaze {
+    fn cell_count(&self) -> usize { self.width * self.height }
+
+    fn is_enabled(&self, _cell: usize) -> bool { true }
+
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        let point = self.point(cell);
+
+        HexDirection::ALL.iter()
+            .filter_map(|direction| {
+                self.neighbor(point, *direction)
+                    .map(|neighbor| (self.cell_id(neighbor), Rc::clone(self.cells[point.x][point.y].wall_mut(*direction))))
+            })
+            .collect()
+    }
+
+    //The entrance is the top left cell and the exit is the bottom right cell
+    fn exits(&self) -> Option<(usize, usize)> {
+        if self.cell_count() == 0 { return None; }
+        Some((0, self.cell_count() - 1))
+    }
+
+    //Opens the north west wall of the entrance and the south east wall of the exit, which are always on the border
+    fn open_exits(&self) {
+        if self.cell_count() == 0 { return; }
+
+        self.cells[0][0].wall_mut(HexDirection::NorthWest).borrow_mut().active = false;
+        self.cells[self.width - 1][self.height - 1].wall_mut(HexDirection::SouthEast).borrow_mut().active = false;
+    }
+}
+
+impl MazeDrawing for HexMaze {
+    fn extent(&self) -> (f64, f64) {
+        let width = 1.5 * self.width as f64 + 0.5;
+        let shift = if self.width > 1 { 0.5 } else { 0.0 };
+        let height = 3f64.sqrt() * (self.height as f64 + shift);
+        (width, height)
+    }
+
+    fn wall_strokes(&self) -> Vec<Stroke> {
+        let mut strokes = Vec::new();
+
+        //Corners of a hexagon, going clockwise from the rightmost one.
+        //Side i runs from corner i to corner i + 1.
+        let sides = [
+            HexDirection::SouthEast,
+            HexDirection::South,
+            HexDirection::SouthWest,
+            HexDirection::NorthWest,
+            HexDirection::North,
+            HexDirection::NorthEast,
+        ];
+
+        let corner = |centre: (f64, f64), index: usize| {
+            let angle = (60.0 * index as f64).to_radians();
+            (centre.0 + angle.cos(), centre.1 + angle.sin())
+        };
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let point = Point { x, y };
+                let centre = self.centre(point);
+
+                for (index, direction) in sides.iter().enumerate() {
+                    //Shared walls are drawn once, by the cell that comes first
+                    if let Some(neighbor) = self.neighbor(point, *direction) {
+                        if self.cell_id(neighbor) < self.cell_id(point) { continue; }
+                    }
+
+                    if self.cells[x][y].wall(*direction).active {
+                        strokes.push(Stroke::Line { from: corner(centre, index), to: corner(centre, index + 1) });
+                    }
+                }
+            }
+        }
+
+        strokes
+    }
+}
+
+//ASCII approximation of the maze. Every cell is drawn as
+// __
+///  \
+//\__/
+//with neighboring cells sharing their sides.
+impl fmt::Display for HexMaze {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        //Exception case
+        if self.cells.is_empty() || self.height == 0 { return write!(f, "Maze is empty."); }
+
+        let rows = 2 * self.height + if self.width > 1 { 2 } else { 1 };
+        let columns = 3 * self.width + 1;
+        let mut canvas = vec![vec![' '; columns]; rows];
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let cell = &self.cells[x][y];
+                let top = 2 * y + x % 2;
+                let left = 3 * x;
+
+                if cell.wall(HexDirection::North).active {
+                    canvas[top][left + 1] = '_';
+                    canvas[top][left + 2] = '_';
+                }
+                if cell.wall(HexDirection::NorthWest).active { canvas[top + 1][left] = '/'; }
+                if cell.wall(HexDirection::NorthEast).active { canvas[top + 1][left + 3] = '\\'; }
+                if cell.wall(HexDirection::SouthWest).active { canvas[top + 2][left] = '\\'; }
+                if cell.wall(HexDirection::SouthEast).active { canvas[top + 2][left + 3] = '/'; }
+                if cell.wall(HexDirection::South).active {
+                    canvas[top + 2][left + 1] = '_';
+                    canvas[top + 2][left + 2] = '_';
+                }
+            }
+        }
+
+        let mut maze_str = String::new();
+        for row in canvas {
+            let line: String = row.into_iter().collect();
+            maze_str += line.trim_end();
+            maze_str += "\n";
+        }
+
+        write!(f, "{}", maze_str)
+    }
+}
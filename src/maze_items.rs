@@ -0,0 +1,146 @@
+extern crate rand;
+
+use std::collections::VecDeque;
+
+use rand::{Rng, RngCore};
+use serde::{Serialize, Deserialize};
+
+use super::maze_distance::DistanceMap;
+use super::maze_error::MazeError;
+use super::maze_grid::MazeGrid;
+
+//Something lying in a cell for the player to pick up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Item {
+    Coin,
+    Key(usize),     //Opens the door with the same number
+}
+
+//How many items to put in a maze. Every door comes with a key of its own.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ItemOptions {
+    pub coins: usize,
+    pub doors: usize,
+}
+
+impl Default for ItemOptions {
+    fn default() -> Self {
+        Self {
+            coins: 5,
+            doors: 2,
+        }
+    }
+}
+
+//Where place_items put everything. A door is the two cells of the passage it stands in, and the number of its key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ItemLayout {
+    pub items: Vec<(usize, Item)>,
+    pub doors: Vec<(usize, usize, usize)>,
+}
+
+//Puts doors in passages along the way from start to goal, numbered from the one nearest start, and the key of each door
+//somewhere that can be reached from start with only the keys of the doors before it, so the goal can always be reached.
+//Coins go anywhere that can be reached without going past the goal, since reaching it ends the game.
+//Nothing is put on start or goal, and any doors already in the maze are taken out.
+pub fn place_items(grid: &dyn MazeGrid, start: usize, goal: usize, options: &ItemOptions, rng: &mut dyn RngCore) -> Result<ItemLayout, MazeError> {
+    for cell in 0..grid.cell_count() {
+        for (_, wall) in grid.neighbors(cell) {
+            wall.borrow_mut().door = None;
+        }
+    }
+
+    let distances = DistanceMap::new(grid, start);
+    let goal_distance = distances.distance(goal).ok_or(MazeError::NoRoomForItems)?;
+
+    //The passages of the shortest way from start to goal, in order
+    let mut path = Vec::new();
+    let mut current = goal;
+    for distance in (0..goal_distance).rev() {
+        let (previous, wall) = grid.neighbors(current).into_iter()
+            .find(|(neighbor, wall)| !wall.borrow().active && distances.distance(*neighbor) == Some(distance))
+            .ok_or(MazeError::NoRoomForItems)?;
+
+        path.push((previous, current, wall));
+        current = previous;
+    }
+    path.reverse();
+
+    //The first passage is left without a door, so the key of the first door has somewhere to go other than start.
+    //Keys and coins all go in cells that can be reached before the goal.
+    let before_goal = walk(grid, start, &[], Some(goal));
+    let room = before_goal.iter().filter(|reached| **reached).count();
+    let taken = if start == goal { 1 } else { 2 };
+    if options.doors > path.len().saturating_sub(1) || options.coins + options.doors + taken > room {
+        return Err(MazeError::NoRoomForItems);
+    }
+
+    let mut candidates: Vec<usize> = (1..path.len()).collect();
+    for i in 0..options.doors {
+        let random = rng.gen_range(i..candidates.len());
+        candidates.swap(i, random);
+    }
+    let mut chosen = candidates[..options.doors].to_vec();
+    chosen.sort_unstable();
+
+    let mut layout = ItemLayout::default();
+    for (key, index) in chosen.iter().enumerate() {
+        let (one, two, wall) = &path[*index];
+        wall.borrow_mut().door = Some(key);
+        layout.doors.push((*one, *two, key));
+    }
+
+    let mut occupied = vec![false; grid.cell_count()];
+    occupied[start] = true;
+    occupied[goal] = true;
+
+    let mut put = |within: &[bool], item: Item, occupied: &mut Vec<bool>, layout: &mut ItemLayout| {
+        let free: Vec<usize> = (0..within.len()).filter(|cell| within[*cell] && !occupied[*cell]).collect();
+        let cell = free[rng.gen_range(0..free.len())];
+
+        occupied[cell] = true;
+        layout.items.push((cell, item));
+    };
+
+    //Every door before the key's own is open by the time the key is needed
+    for key in 0..options.doors {
+        let keys: Vec<usize> = (0..key).collect();
+        put(&reachable(grid, start, &keys), Item::Key(key), &mut occupied, &mut layout);
+    }
+
+    for _ in 0..options.coins {
+        put(&before_goal, Item::Coin, &mut occupied, &mut layout);
+    }
+
+    Ok(layout)
+}
+
+//Every cell that can be walked to from start by someone holding keys, indexed by cell
+pub fn reachable(grid: &dyn MazeGrid, start: usize, keys: &[usize]) -> Vec<bool> {
+    walk(grid, start, keys, None)
+}
+
+//Like reachable, but nothing is walked to through stop. stop itself is still reached.
+fn walk(grid: &dyn MazeGrid, start: usize, keys: &[usize], stop: Option<usize>) -> Vec<bool> {
+    let mut reached = vec![false; grid.cell_count()];
+    let mut queue = VecDeque::new();
+
+    if start < reached.len() {
+        reached[start] = true;
+        queue.push_back(start);
+    }
+
+    while let Some(current) = queue.pop_front() {
+        if Some(current) == stop && current != start { continue; }
+
+        for (neighbor, wall) in grid.neighbors(current) {
+            if reached[neighbor] || !wall.borrow().is_passable(keys) { continue; }
+
+            reached[neighbor] = true;
+            queue.push_back(neighbor);
+        }
+    }
+
+    reached
+}
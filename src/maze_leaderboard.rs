@@ -0,0 +1,115 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Serialize, Deserialize};
+
+use super::maze_challenge::{ChallengeOptions, LevelResult};
+
+//Most entries a leaderboard keeps. Lower scores fall off the end.
+pub const MAX_ENTRIES: usize = 20;
+
+#[derive(Debug)]
+pub enum LeaderboardError {
+    Io(io::Error),
+    Parse(String),
+}
+
+impl fmt::Display for LeaderboardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LeaderboardError::Io(error) => write!(f, "could not read leaderboard: {}", error),
+            LeaderboardError::Parse(reason) => write!(f, "could not parse leaderboard: {}", reason),
+        }
+    }
+}
+
+impl Error for LeaderboardError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LeaderboardError::Io(error) => Some(error),
+            LeaderboardError::Parse(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for LeaderboardError {
+    fn from(error: io::Error) -> Self {
+        LeaderboardError::Io(error)
+    }
+}
+
+//A finished challenge. The seed and options are kept so the same levels can be played again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub name: String,
+    pub seed: u64,
+    pub options: ChallengeOptions,
+    pub score: u64,
+    pub levels: Vec<LevelResult>,
+}
+
+//Best challenges played, highest score first, kept in a JSON file
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    pub fn from_json(text: &str) -> Result<Self, LeaderboardError> {
+        let mut leaderboard: Self = serde_json::from_str(text).map_err(|error| LeaderboardError::Parse(error.to_string()))?;
+        leaderboard.entries.sort_by_key(|entry| std::cmp::Reverse(entry.score));
+        leaderboard.entries.truncate(MAX_ENTRIES);
+        Ok(leaderboard)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    //Reads a leaderboard file. A file that doesn't exist yet is an empty leaderboard.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LeaderboardError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Self::from_json(&text),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+
+    //Adds entry below every entry with at least its score. Returns its place, counting from 0,
+    //or None if it didn't make it onto the leaderboard.
+    pub fn add(&mut self, entry: LeaderboardEntry) -> Option<usize> {
+        let place = self.entries.iter().take_while(|other| other.score >= entry.score).count();
+        if place >= MAX_ENTRIES { return None; }
+
+        self.entries.insert(place, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(place)
+    }
+
+    //Entries that played the same levels, so they can be compared with each other
+    pub fn same_levels(&self, seed: u64, options: &ChallengeOptions) -> Vec<&LeaderboardEntry> {
+        self.entries.iter().filter(|entry| entry.seed == seed && entry.options == *options).collect()
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.entries.is_empty() {
+            return writeln!(f, "No challenges played yet.");
+        }
+
+        for (place, entry) in self.entries.iter().enumerate() {
+            let escaped = entry.levels.iter().filter(|level| level.escaped).count();
+            writeln!(f, "{:>2}. {:<16} {:>7}  {} of {} levels, seed {}", place + 1, entry.name, entry.score, escaped, entry.options.levels, entry.seed)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::maze_error::check_dimensions;
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(io::Error),
+    InvalidImage(String),
+    Empty,
+    Disconnected(usize, usize),     //The enabled cell at x, y can't be reached from the others, so no one maze can cover them all
+}
+
+impl fmt::Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MaskError::Io(error) => write!(f, "could not read mask: {}", error),
+            MaskError::InvalidImage(reason) => write!(f, "invalid mask image: {}", reason),
+            MaskError::Empty => write!(f, "mask has no enabled cells"),
+            MaskError::Disconnected(x, y) => write!(f, "cell ({}, {}) isn't connected to the rest of the mask", x, y),
+        }
+    }
+}
+
+impl Error for MaskError {}
+
+impl From<io::Error> for MaskError {
+    fn from(error: io::Error) -> Self {
+        MaskError::Io(error)
+    }
+}
+
+//Decides which cells of a width x height grid are part of a maze. Used to build mazes that aren't rectangles.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MazeMask {
+    enabled: Vec<Vec<bool>>,
+    width: usize,
+    height: usize,
+}
+
+impl MazeMask {
+    //Constructor, every cell starts out enabled
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            enabled: vec![vec![true; height]; width],
+            width,
+            height,
+        }
+    }
+
+    //Builds a mask from ASCII art, one character per cell. '.' and ' ' are disabled cells, anything else is enabled.
+    //Short lines are padded with disabled cells.
+    pub fn from_ascii(text: &str) -> Result<Self, MaskError> {
+        let lines: Vec<&str> = text.lines().map(|line| line.trim_end_matches('\r')).collect();
+        let height = lines.iter().rposition(|line| !line.trim().is_empty()).map_or(0, |last| last + 1);
+        let width = lines[..height].iter().map(|line| line.chars().count()).max().unwrap_or(0);
+
+        let mut mask = Self {
+            enabled: vec![vec![false; height]; width],
+            width,
+            height,
+        };
+
+        for (y, line) in lines[..height].iter().enumerate() {
+            for (x, character) in line.chars().enumerate() {
+                mask.enabled[x][y] = character != '.' && character != ' ';
+            }
+        }
+
+        mask.check_not_empty()
+    }
+
+    //Builds a mask from a black and white PBM image (plain P1 or raw P4). Black pixels are enabled cells.
+    //Images with more pixels than a maze can have cells are turned down.
+    pub fn from_pbm(bytes: &[u8]) -> Result<Self, MaskError> {
+        let mut position = 0;
+
+        let magic = next_token(bytes, &mut position)?;
+        let width = parse_dimension(next_token(bytes, &mut position)?)?;
+        let height = parse_dimension(next_token(bytes, &mut position)?)?;
+
+        //The header is checked against the size limit and the length of the data before anything is allocated for it
+        check_dimensions(&[width, height]).map_err(|error| MaskError::InvalidImage(error.to_string()))?;
+
+        let row_length = width.div_ceil(8);
+        let (data, needed): (Vec<u8>, usize) = match magic {
+            //Every pixel is a '0' or '1', whitespace between them is optional
+            b"P1" => (bytes[position..].iter().copied().filter(|byte| !byte.is_ascii_whitespace()).take(width * height).collect(), width * height),
+
+            //A single whitespace byte separates the header from the packed pixels. Rows are padded to a whole byte.
+            b"P4" => (bytes[(position + 1).min(bytes.len())..].iter().copied().take(row_length * height).collect(), row_length * height),
+
+            _ => return Err(MaskError::InvalidImage(String::from("only P1 and P4 PBM images are supported"))),
+        };
+
+        if data.len() < needed {
+            return Err(MaskError::InvalidImage(String::from("image data is too short")));
+        }
+
+        let mut mask = Self::new(width, height);
+        for y in 0..height {
+            for x in 0..width {
+                mask.enabled[x][y] = match magic {
+                    b"P1" => match data[y * width + x] {
+                        b'1' => true,
+                        b'0' => false,
+                        _ => return Err(MaskError::InvalidImage(String::from("pixels must be 0 or 1"))),
+                    },
+                    _ => data[y * row_length + x / 8] & (0x80 >> (x % 8)) != 0,
+                };
+            }
+        }
+
+        mask.check_not_empty()
+    }
+
+    //Loads a mask from a file. PBM images are detected by their header, anything else is read as ASCII art.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, MaskError> {
+        let bytes = fs::read(path)?;
+
+        if bytes.starts_with(b"P1") || bytes.starts_with(b"P4") {
+            Self::from_pbm(&bytes)
+        }
+        else {
+            match String::from_utf8(bytes) {
+                Ok(text) => Self::from_ascii(&text),
+                Err(_) => Err(MaskError::InvalidImage(String::from("file is neither a PBM image nor text"))),
+            }
+        }
+    }
+
+    //Getters
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+
+    pub fn is_enabled(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.enabled[x][y]
+    }
+
+    //True if the mask has no enabled cells, so a maze in its shape would have nothing in it
+    pub fn is_empty(&self) -> bool {
+        !self.enabled.iter().any(|column| column.iter().any(|enabled| *enabled))
+    }
+
+    //An enabled cell that can't be reached from the first enabled cell by stepping between enabled cells
+    //that share a side, None if the enabled cells are all in one piece
+    pub fn unreachable_cell(&self) -> Option<(usize, usize)> {
+        let first = (0..self.width).flat_map(|x| (0..self.height).map(move |y| (x, y))).find(|(x, y)| self.enabled[*x][*y])?;
+
+        let mut reached = vec![vec![false; self.height]; self.width];
+        let mut stack = vec![first];
+        reached[first.0][first.1] = true;
+
+        while let Some((x, y)) = stack.pop() {
+            let sides = [(x.wrapping_sub(1), y), (x + 1, y), (x, y.wrapping_sub(1)), (x, y + 1)];
+            for (next_x, next_y) in sides.iter().copied() {
+                if self.is_enabled(next_x, next_y) && !reached[next_x][next_y] {
+                    reached[next_x][next_y] = true;
+                    stack.push((next_x, next_y));
+                }
+            }
+        }
+
+        (0..self.width).flat_map(|x| (0..self.height).map(move |y| (x, y))).find(|(x, y)| self.enabled[*x][*y] && !reached[*x][*y])
+    }
+
+    //Setters
+    pub fn set_enabled(&mut self, x: usize, y: usize, enabled: bool) { self.enabled[x][y] = enabled; }
+
+    fn check_not_empty(self) -> Result<Self, MaskError> {
+        if self.is_empty() {
+            Err(MaskError::Empty)
+        }
+        else {
+            Ok(self)
+        }
+    }
+}
+
+//Reads the next whitespace separated token of a PBM header, skipping comments
+fn next_token<'a>(bytes: &'a [u8], position: &mut usize) -> Result<&'a [u8], MaskError> {
+    loop {
+        while *position < bytes.len() && bytes[*position].is_ascii_whitespace() {
+            *position += 1;
+        }
+
+        if *position < bytes.len() && bytes[*position] == b'#' {
+            while *position < bytes.len() && bytes[*position] != b'\n' {
+                *position += 1;
+            }
+        }
+        else {
+            break;
+        }
+    }
+
+    let start = *position;
+    while *position < bytes.len() && !bytes[*position].is_ascii_whitespace() {
+        *position += 1;
+    }
+
+    if start == *position {
+        Err(MaskError::InvalidImage(String::from("header is incomplete")))
+    }
+    else {
+        Ok(&bytes[start..*position])
+    }
+}
+
+fn parse_dimension(token: &[u8]) -> Result<usize, MaskError> {
+    std::str::from_utf8(token).ok()
+        .and_then(|text| text.parse::<usize>().ok())
+        .filter(|value| *value > 0)
+        .ok_or_else(|| MaskError::InvalidImage(String::from("width and height must be positive numbers")))
+}
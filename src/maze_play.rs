@@ -0,0 +1,336 @@
+extern crate rand;
+
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use super::maze_3d::{Maze3D, Point3D, Direction3D};
+use super::maze_enemy::{self, Enemy, Behaviour};
+use super::maze_error::MazeError;
+use super::maze_grid::MazeGrid;
+use super::maze_items::{self, Item, ItemOptions};
+use super::maze_visibility::{CellView, SightOptions};
+
+//A single player input
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    Move(Direction3D),
+    Wait,
+    Quit,
+}
+
+impl Command {
+    //w, a, s and d move around a floor, < climbs stairs up and > goes down them, . waits a turn, q quits
+    pub fn parse(key: char) -> Option<Self> {
+        match key.to_ascii_lowercase() {
+            'w' => Some(Command::Move(Direction3D::North)),
+            's' => Some(Command::Move(Direction3D::South)),
+            'd' => Some(Command::Move(Direction3D::East)),
+            'a' => Some(Command::Move(Direction3D::West)),
+            '<' => Some(Command::Move(Direction3D::Up)),
+            '>' => Some(Command::Move(Direction3D::Down)),
+            '.' => Some(Command::Wait),
+            'q' => Some(Command::Quit),
+            _ => None,
+        }
+    }
+}
+
+//A turn based game where the player walks from the entrance of a maze to its exit.
+//A plain two dimensional maze is played as a maze with a single floor.
+//With fog of war, the player only sees what's in sight and remembers the cells seen before.
+//Levels can have coins to collect and doors that only open once the player has picked up their key.
+//Monsters take a turn after every turn of the player, and the game is lost if one catches the player.
+//Everything random in a game comes from the maze's seed, so the same seed and moves always play out the same.
+pub struct MazeGame {
+    maze: Maze3D,
+    player: Point3D,
+    goal: Point3D,
+    moves: usize,
+    sight: Option<SightOptions>,
+    seen: Vec<bool>,        //Indexed by cell id, only kept up with fog of war
+    visible: Vec<bool>,
+    items: Vec<Option<Item>>,   //Indexed by cell id
+    keys: Vec<usize>,
+    coins: usize,
+    total_coins: usize,
+    enemies: Vec<Enemy>,
+    caught: bool,
+    rng: StdRng,
+}
+
+impl MazeGame {
+    //Constructor. The player starts at the maze's entrance.
+    pub fn new(maze: Maze3D) -> Self {
+        let (entrance, exit) = maze.exits().unwrap_or((0, 0));
+        let cell_count = maze.cell_count();
+        let seed = maze.seed();
+
+        Self {
+            player: maze.point(entrance),
+            goal: maze.point(exit),
+            maze,
+            moves: 0,
+            sight: None,
+            seen: Vec::new(),
+            visible: Vec::new(),
+            items: vec![None; cell_count],
+            keys: Vec::new(),
+            coins: 0,
+            total_coins: 0,
+            enemies: Vec::new(),
+            caught: false,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    //Constructor for a game played with fog of war, where sight limits what the player can see
+    pub fn with_fog(maze: Maze3D, sight: SightOptions) -> Self {
+        let cell_count = maze.cell_count();
+        let mut game = Self::new(maze);
+
+        game.sight = Some(sight);
+        game.seen = vec![false; cell_count];
+        game.visible = vec![false; cell_count];
+        game.look();
+
+        game
+    }
+
+    //Getters
+    pub fn maze(&self) -> &Maze3D { &self.maze }
+    pub fn player(&self) -> Point3D { self.player }
+    pub fn goal(&self) -> Point3D { self.goal }
+    pub fn moves(&self) -> usize { self.moves }
+    pub fn sight(&self) -> Option<SightOptions> { self.sight }
+    pub fn item(&self, point: Point3D) -> Option<Item> { self.items[self.maze.cell_id(point)] }
+    pub fn keys(&self) -> &[usize] { &self.keys }
+    pub fn coins(&self) -> usize { self.coins }
+    pub fn total_coins(&self) -> usize { self.total_coins }
+
+    //Number of the door that stands in the way in direction, None if there's no door there or it's open
+    pub fn door(&self, direction: Direction3D) -> Option<usize> {
+        let wall = self.maze.cell(self.player).wall(direction);
+        if wall.active || wall.is_passable(&self.keys) { None } else { wall.door }
+    }
+
+    //Puts coins, doors and keys in the maze for a game that hasn't started yet, replacing any that are there.
+    //The goal can always be reached.
+    pub fn place_items(&mut self, options: &ItemOptions, seed: u64) -> Result<(), MazeError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let start = self.maze.cell_id(self.player);
+        let goal = self.maze.cell_id(self.goal);
+        let layout = maze_items::place_items(&self.maze, start, goal, options, &mut rng)?;
+
+        self.items = vec![None; self.maze.cell_count()];
+        for (cell, item) in layout.items {
+            self.items[cell] = Some(item);
+        }
+        self.keys.clear();
+        self.coins = 0;
+        self.total_coins = options.coins;
+
+        Ok(())
+    }
+
+    //What the player knows of the cell at point. Everything is visible without fog of war.
+    pub fn view(&self, point: Point3D) -> CellView {
+        if self.sight.is_none() { return CellView::Visible; }
+
+        let cell = self.maze.cell_id(point);
+        if self.visible[cell] { CellView::Visible }
+        else if self.seen[cell] { CellView::Remembered }
+        else { CellView::Hidden }
+    }
+
+    pub fn enemies(&self) -> &[Enemy] { &self.enemies }
+
+    pub fn is_won(&self) -> bool { self.player == self.goal }
+    pub fn is_lost(&self) -> bool { self.caught }
+
+    //Puts a monster in the maze. It catches the player straight away if it's put on them.
+    pub fn add_enemy(&mut self, enemy: Enemy) {
+        self.caught |= enemy.position() == self.player;
+        self.enemies.push(enemy);
+    }
+
+    //Puts a monster for each behaviour somewhere in the farther half of the maze from the player
+    pub fn spawn_enemies(&mut self, behaviours: &[Behaviour]) {
+        let points = maze_enemy::spawn_points(&self.maze, self.player, behaviours.len(), &mut self.rng);
+        for (behaviour, point) in behaviours.iter().zip(points) {
+            self.add_enemy(Enemy::new(behaviour.clone(), point));
+        }
+    }
+
+    //The player stays where they are, and the monsters take their turn
+    pub fn wait(&mut self) {
+        if self.is_won() || self.caught { return; }
+        self.enemies_turn();
+    }
+
+    //Moves the player one cell in direction, and picks up what's there. Returns false if a wall or a locked door is in the way.
+    pub fn try_move(&mut self, direction: Direction3D) -> bool {
+        if self.is_won() || self.caught { return false; }
+        if !self.maze.cell(self.player).wall(direction).is_passable(&self.keys) { return false; }
+
+        match self.maze.neighbor(self.player, direction) {
+            Some(next) => {
+                self.player = next;
+                self.moves += 1;
+                self.pick_up();
+                self.look();
+
+                //Walking into a monster is as bad as being caught by it
+                self.caught = self.enemies.iter().any(|enemy| enemy.position() == next);
+                if !self.is_won() && !self.caught { self.enemies_turn(); }
+                true
+            }
+
+            None => false,
+        }
+    }
+
+    fn enemies_turn(&mut self) {
+        for enemy in self.enemies.iter_mut() {
+            enemy.take_turn(&self.maze, self.player, &mut self.rng);
+            self.caught |= enemy.position() == self.player;
+        }
+    }
+
+    fn pick_up(&mut self) {
+        match self.items[self.maze.cell_id(self.player)].take() {
+            Some(Item::Coin) => self.coins += 1,
+            Some(Item::Key(key)) => {
+                self.keys.push(key);
+
+                //The key opens its door for good, so it's no longer drawn
+                for cell in 0..self.maze.cell_count() {
+                    for (_, wall) in self.maze.neighbors(cell) {
+                        let mut wall = wall.borrow_mut();
+                        if wall.door == Some(key) { wall.door = None; }
+                    }
+                }
+            }
+            None => {}
+        }
+    }
+
+    //Works out what the player can see from where they stand, and remembers it
+    fn look(&mut self) {
+        let sight = match &self.sight {
+            Some(value) => value,
+            None => return,
+        };
+
+        self.visible.iter_mut().for_each(|visible| *visible = false);
+        for point in self.maze.visible_from(self.player, sight) {
+            let cell = self.maze.cell_id(point);
+            self.visible[cell] = true;
+            self.seen[cell] = true;
+        }
+    }
+}
+
+//The floor the player is on, with the player drawn as @@, the exit as XX, coins as $$, keys as k and their number, and doors as [].
+//Monsters are drawn as MM, but only where the player can see them.
+//With fog of war, cells never seen are covered and walls out of sight are drawn dimly.
+impl fmt::Display for MazeGame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let player = self.player;
+        let goal = self.goal;
+        let contents = |point: Point3D| {
+            if point == player { Some("@@".to_string()) }
+            else if self.view(point) == CellView::Visible && self.enemies.iter().any(|enemy| enemy.position() == point) { Some("MM".to_string()) }
+            else if point == goal { Some("XX".to_string()) }
+            else {
+                match self.item(point) {
+                    Some(Item::Coin) => Some("$$".to_string()),
+                    Some(Item::Key(key)) if key < 10 => Some(format!("k{}", key)),
+                    Some(Item::Key(_)) => Some("kk".to_string()),
+                    None => None,
+                }
+            }
+        };
+
+        writeln!(f, "Floor {} of {}, {} moves", player.z + 1, self.maze.depth(), self.moves)?;
+        if self.total_coins > 0 || !self.keys.is_empty() {
+            let keys: Vec<String> = self.keys.iter().map(|key| key.to_string()).collect();
+            writeln!(f, "Coins: {} of {}, keys: {}", self.coins, self.total_coins, if keys.is_empty() { "none".to_string() } else { keys.join(" ") })?;
+        }
+        match self.sight {
+            Some(_) => write!(f, "{}", self.maze.floor_string_fogged(player.z, &|point| self.view(point), &contents))?,
+            None => write!(f, "{}", self.maze.floor_string_with(player.z, &contents))?,
+        }
+
+        //The player hides the stair marker of the cell they are standing on
+        let cell = self.maze.cell(player);
+        match (cell.has_stairs(Direction3D::Up), cell.has_stairs(Direction3D::Down)) {
+            (true, true) => writeln!(f, "There are stairs going up and down here."),
+            (true, false) => writeln!(f, "There are stairs going up here."),
+            (false, true) => writeln!(f, "There are stairs going down here."),
+            (false, false) => Ok(()),
+        }?;
+
+        //Doors on stairs can't be drawn on the floor
+        for (direction, way) in [(Direction3D::Up, "up"), (Direction3D::Down, "down")].iter() {
+            if let Some(door) = self.door(*direction) {
+                writeln!(f, "The stairs going {} are behind door {}.", way, door)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+//Keys the player can press, shown before a game starts
+pub const HELP: &str = "w/a/s/d to move, < and > to take stairs up and down, . to wait, q to quit";
+
+//Plays game by reading lines of commands from input until the player wins, quits or input runs out.
+//A line can hold several commands, which are played one after the other.
+pub fn run<R: BufRead, W: Write>(game: &mut MazeGame, input: R, mut output: W) -> io::Result<()> {
+    writeln!(output, "{}", HELP)?;
+    write!(output, "{}", game)?;
+
+    for line in input.lines() {
+        if !play_line(game, &line?, &mut output)? { return Ok(()); }
+    }
+
+    Ok(())
+}
+
+//Plays a line of commands, then draws the game. Returns false once the game is over, as the player won, lost or quit.
+pub fn play_line<W: Write>(game: &mut MazeGame, line: &str, output: &mut W) -> io::Result<bool> {
+    for key in line.chars() {
+        match Command::parse(key) {
+            Some(Command::Move(direction)) if !game.try_move(direction) => match game.door(direction) {
+                Some(door) => writeln!(output, "The door is locked, it needs key {}.", door)?,
+                None => writeln!(output, "You can't go that way.")?,
+            },
+
+            Some(Command::Wait) => game.wait(),
+            Some(Command::Quit) => return Ok(false),
+            _ => {}
+        }
+
+        if game.is_won() {
+            write!(output, "{}", game)?;
+            writeln!(output, "You escaped the maze in {} moves!", game.moves())?;
+            if game.total_coins() > 0 {
+                writeln!(output, "You found {} of {} coins.", game.coins(), game.total_coins())?;
+            }
+            return Ok(false);
+        }
+
+        if game.is_lost() {
+            write!(output, "{}", game)?;
+            writeln!(output, "A monster caught you after {} moves.", game.moves())?;
+            return Ok(false);
+        }
+    }
+
+    write!(output, "{}", game)?;
+    output.flush()?;
+    Ok(true)
+}
@@ -0,0 +1,288 @@
+extern crate rand;
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+use std::cell::{RefCell, Ref};
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use super::maze_wall::MazeWall;
+use super::maze_algorithm::{MazeAlgorithm, MazeStep};
+use super::maze_grid::{MazeGrid, Point};
+use super::maze_regenerate::Regenerate;
+use super::maze_render::{MazeDrawing, Stroke};
+use super::maze_error::{MazeError, MAX_CELLS, check_dimensions};
+
+//A cell of a circular maze. Its outward walls are shared with the cells of the next ring,
+//or are a single border wall if the cell is on the outermost ring.
+#[derive(Clone, Default)]
+pub struct PolarCell {
+    inward_wall: Option<Rc<RefCell<MazeWall>>>,
+    clockwise_wall: Option<Rc<RefCell<MazeWall>>>,
+    counter_clockwise_wall: Option<Rc<RefCell<MazeWall>>>,
+    outward_walls: Vec<Rc<RefCell<MazeWall>>>,
+}
+
+impl PolarCell {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    //Getters. The centre cell has no inward, clockwise or counter clockwise walls, so those return None.
+    pub fn inward_wall(&self) -> Option<Ref<'_, MazeWall>> { self.inward_wall.as_ref().map(|wall| wall.borrow()) }
+    pub fn clockwise_wall(&self) -> Option<Ref<'_, MazeWall>> { self.clockwise_wall.as_ref().map(|wall| wall.borrow()) }
+    pub fn counter_clockwise_wall(&self) -> Option<Ref<'_, MazeWall>> { self.counter_clockwise_wall.as_ref().map(|wall| wall.borrow()) }
+
+    //Mutable Access
+    pub fn inward_wall_mut(&self) -> Option<&Rc<RefCell<MazeWall>>> { self.inward_wall.as_ref() }
+    pub fn clockwise_wall_mut(&self) -> Option<&Rc<RefCell<MazeWall>>> { self.clockwise_wall.as_ref() }
+    pub fn counter_clockwise_wall_mut(&self) -> Option<&Rc<RefCell<MazeWall>>> { self.counter_clockwise_wall.as_ref() }
+    pub fn outward_walls_mut(&self) -> &Vec<Rc<RefCell<MazeWall>>> { &self.outward_walls }
+
+    //Setters
+    pub fn set_inward_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.inward_wall = Some(wall); }
+    pub fn set_clockwise_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.clockwise_wall = Some(wall); }
+    pub fn set_counter_clockwise_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.counter_clockwise_wall = Some(wall); }
+    pub fn add_outward_wall(&mut self, wall: Rc<RefCell<MazeWall>>) { self.outward_walls.push(wall); }
+}
+
+//A circular maze made of rings of cells around a single centre cell. Every ring is one unit thick,
+//and rings are split into more cells as they get further out, so the cells stay roughly square.
+//The entrance is on the outer border and the exit is the centre cell.
+pub struct PolarMaze {
+    maze_algorithm: Box<dyn MazeAlgorithm>,
+    cells: Vec<Vec<PolarCell>>,
+    walls: Vec<Rc<RefCell<MazeWall>>>,
+    seed: u64,
+    ring_offsets: Vec<usize>,
+    rings: usize,
+}
+
+//Works out how many cells each ring has. A ring's cell count is always a multiple of the previous ring's,
+//so every cell has exactly one inward neighbor.
+fn ring_sizes(rings: usize) -> Vec<usize> {
+    let mut sizes: Vec<usize> = Vec::with_capacity(rings);
+
+    for ring in 0..rings {
+        let count = match sizes.last() {
+            None => 1,
+            Some(previous) => {
+                let circumference = 2.0 * PI * ring as f64;
+                let ratio = (circumference / *previous as f64).round().max(1.0) as usize;
+                previous * ratio
+            }
+        };
+
+        sizes.push(count);
+    }
+
+    sizes
+}
+
+impl PolarMaze {
+    //Constructor
+    pub fn new(rings: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        let mut tmp = Self {
+            maze_algorithm,
+            cells: Vec::new(),
+            walls: Vec::new(),
+            seed: 0,
+            ring_offsets: Vec::new(),
+            rings,
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor that checks the number of rings first. Fails if there are none or the maze would be too large.
+    pub fn try_new(rings: usize, maze_algorithm: Box<dyn MazeAlgorithm>) -> Result<Self, MazeError> {
+        check_dimensions(&[rings])?;
+
+        let cells: usize = ring_sizes(rings).iter().sum();
+        if cells > MAX_CELLS {
+            return Err(MazeError::TooLarge(cells));
+        }
+
+        Ok(Self::new(rings, maze_algorithm))
+    }
+
+    //Reconstructs maze with the given number of rings. No algorithm is applied on the maze.
+    pub fn reconstruct(&mut self) {
+        self.cells = Vec::new();
+        self.walls = Vec::new();
+        self.ring_offsets = Vec::new();
+
+        let mut offset = 0;
+        for count in ring_sizes(self.rings) {
+            self.cells.push(vec![PolarCell::new(); count]);
+            self.ring_offsets.push(offset);
+            offset += count;
+        }
+
+        //Build Walls
+        for ring in 1..self.rings {
+            let count = self.cells[ring].len();
+            let ratio = count / self.cells[ring - 1].len();
+
+            for index in 0..count {
+                let inward_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                let clockwise_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+
+                //Set current cell's inward and clockwise walls
+                self.cells[ring][index].set_inward_wall(Rc::clone(&inward_wall));
+                self.cells[ring][index].set_clockwise_wall(Rc::clone(&clockwise_wall));
+
+                //Set neighboring cells' outward and counter clockwise walls
+                self.cells[ring - 1][index / ratio].add_outward_wall(Rc::clone(&inward_wall));
+                self.cells[ring][(index + 1) % count].set_counter_clockwise_wall(Rc::clone(&clockwise_wall));
+
+                self.walls.push(inward_wall);
+                self.walls.push(clockwise_wall);
+            }
+        }
+
+        //Add the outer border
+        if let Some(outer_ring) = self.cells.last_mut() {
+            for cell in outer_ring.iter_mut() {
+                let border_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                cell.add_outward_wall(Rc::clone(&border_wall));
+                self.walls.push(border_wall);
+            }
+        }
+    }
+
+    pub fn set_algorithm(&mut self, new_algorithm: Box<dyn MazeAlgorithm>) {
+        self.maze_algorithm = new_algorithm;
+    }
+
+    //Getters
+    pub fn rings(&self) -> usize { self.rings }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn cells(&self) -> &Vec<Vec<PolarCell>> { &self.cells }
+
+    //Cells are numbered ring by ring from the centre out. A point's x is its index in the ring and y is the ring.
+    pub fn cell_id(&self, point: Point) -> usize { self.ring_offsets[point.y] + point.x }
+
+    pub fn point(&self, cell: usize) -> Point {
+        let ring = self.ring_offsets.iter().rposition(|offset| *offset <= cell).unwrap_or(0);
+        Point { x: cell - self.ring_offsets[ring], y: ring }
+    }
+
+    //Angle covered by a single cell of ring
+    fn cell_angle(&self, ring: usize) -> f64 {
+        2.0 * PI / self.cells[ring].len() as f64
+    }
+}
+
+impl Regenerate for PolarMaze {
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>] { &self.walls }
+
+    //Same as regenerate_with_seed, but reports every step of the algorithm to trace
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep)) {
+        self.seed = seed;
+        self.reset();
+        self.maze_algorithm.generate_traced(&*self, &mut StdRng::seed_from_u64(seed), trace);
+    }
+}
+
+impl MazeGrid for PolarMaze {
+    fn cell_count(&self) -> usize {
+        self.cells.iter().map(|ring| ring.len()).sum()
+    }
+
+    fn is_enabled(&self, _cell: usize) -> bool { true }
+
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        let point = self.point(cell);
+        let (index, ring) = (point.x, point.y);
+        let polar_cell = &self.cells[ring][index];
+        let mut neighbors = Vec::new();
+
+        if ring > 0 {
+            let count = self.cells[ring].len();
+            let ratio = count / self.cells[ring - 1].len();
+
+            if let Some(wall) = polar_cell.inward_wall_mut() {
+                neighbors.push((self.cell_id(Point { x: index / ratio, y: ring - 1 }), Rc::clone(wall)));
+            }
+            if let Some(wall) = polar_cell.clockwise_wall_mut() {
+                neighbors.push((self.cell_id(Point { x: (index + 1) % count, y: ring }), Rc::clone(wall)));
+            }
+            if let Some(wall) = polar_cell.counter_clockwise_wall_mut() {
+                neighbors.push((self.cell_id(Point { x: (index + count - 1) % count, y: ring }), Rc::clone(wall)));
+            }
+        }
+
+        //The outward walls of the outermost ring are border walls without a neighbor
+        if ring + 1 < self.rings {
+            let ratio = self.cells[ring + 1].len() / self.cells[ring].len();
+
+            for (child, wall) in polar_cell.outward_walls_mut().iter().enumerate() {
+                neighbors.push((self.cell_id(Point { x: index * ratio + child, y: ring + 1 }), Rc::clone(wall)));
+            }
+        }
+
+        neighbors
+    }
+
+    //The entrance is the first cell of the outermost ring and the exit is the centre cell
+    fn exits(&self) -> Option<(usize, usize)> {
+        if self.rings == 0 { return None; }
+        Some((self.ring_offsets[self.rings - 1], 0))
+    }
+
+    //Opens the outer border of the entrance. The exit is in the middle of the maze, so it has no wall to open.
+    fn open_exits(&self) {
+        if let Some(outer_ring) = self.cells.last() {
+            if let Some(wall) = outer_ring[0].outward_walls_mut().first() {
+                wall.borrow_mut().active = false;
+            }
+        }
+    }
+}
+
+impl MazeDrawing for PolarMaze {
+    fn extent(&self) -> (f64, f64) {
+        let diameter = 2.0 * self.rings as f64;
+        (diameter, diameter)
+    }
+
+    fn wall_strokes(&self) -> Vec<Stroke> {
+        let centre = (self.rings as f64, self.rings as f64);
+        let mut strokes = Vec::new();
+
+        for ring in 0..self.rings {
+            let angle = self.cell_angle(ring);
+            let inner = ring as f64;
+            let outer = inner + 1.0;
+
+            for (index, cell) in self.cells[ring].iter().enumerate() {
+                let start = angle * index as f64;
+                let end = start + angle;
+
+                if cell.inward_wall().is_some_and(|wall| wall.active) {
+                    strokes.push(Stroke::Arc { centre, radius: inner, start, end });
+                }
+
+                //Counter clockwise walls are drawn as the clockwise wall of the previous cell
+                if cell.clockwise_wall().is_some_and(|wall| wall.active) {
+                    strokes.push(Stroke::Line {
+                        from: (centre.0 + inner * end.cos(), centre.1 + inner * end.sin()),
+                        to: (centre.0 + outer * end.cos(), centre.1 + outer * end.sin()),
+                    });
+                }
+
+                //Outer border
+                if ring == self.rings - 1 && cell.outward_walls_mut().iter().any(|wall| wall.borrow().active) {
+                    strokes.push(Stroke::Arc { centre, radius: outer, start, end });
+                }
+            }
+        }
+
+        strokes
+    }
+}
@@ -0,0 +1,40 @@
+extern crate rand;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use super::maze_algorithm::MazeStep;
+use super::maze_grid::MazeGrid;
+use super::maze_wall::MazeWall;
+
+//A maze that can be carved again from a seed. Each topology only says how it generates for a given seed,
+//the rest of the ways to regenerate it are built on that.
+pub trait Regenerate: MazeGrid {
+    //Every wall of the maze, each listed once
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>];
+
+    //Generates the maze for seed, reporting every step of the algorithm to trace
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep));
+
+    //Resets all walls in maze to active, except the locked ones. Primarily used to reset a maze for regeneration.
+    fn reset(&mut self) {
+        for wall in self.walls() {
+            wall.borrow_mut().reset();
+        }
+    }
+
+    //Generates a new maze from a random seed
+    fn regenerate(&mut self) {
+        self.regenerate_traced_with_seed(rand::random(), &mut |_| {});
+    }
+
+    //Generates the maze for seed. The same seed always gives the same maze, as long as the shape and algorithm are the same.
+    fn regenerate_with_seed(&mut self, seed: u64) {
+        self.regenerate_traced_with_seed(seed, &mut |_| {});
+    }
+
+    //Same as regenerate, but reports every step of the algorithm to trace
+    fn regenerate_traced(&mut self, trace: &mut dyn FnMut(MazeStep)) {
+        self.regenerate_traced_with_seed(rand::random(), trace);
+    }
+}
@@ -0,0 +1,101 @@
+extern crate rand;
+
+use rand::{Rng, RngCore};
+
+use super::maze_algorithm::{MazeAlgorithm, MazeStep, find_set};
+use super::maze_grid::{MazeGrid, Point};
+
+//A rectangle of a maze that is generated with an algorithm of its own. x and y are the top left cell.
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+    pub algorithm: Box<dyn MazeAlgorithm>,
+}
+
+impl Region {
+    //Constructor
+    pub fn new(x: usize, y: usize, width: usize, height: usize, algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        Self { x, y, width, height, algorithm }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.x < self.x + self.width && point.y >= self.y && point.y < self.y + self.height
+    }
+
+    pub fn overlaps(&self, other: &Region) -> bool {
+        self.x < other.x + other.width && other.x < self.x + self.width && self.y < other.y + other.height && other.y < self.y + self.height
+    }
+}
+
+//Which regions a maze is made of. Cells outside every region are generated with the maze's own algorithm.
+//The regions are joined with just enough passages to connect them, and then every region gets extra_connections
+//more passages across its boundary. Each extra passage makes a loop.
+#[derive(Default)]
+pub struct RegionLayout {
+    pub regions: Vec<Region>,
+    pub extra_connections: usize,
+}
+
+//Opens passages across the boundaries of the parts of grid. part_of gives the part each cell belongs to,
+//and only boundaries of the parts listed in parts are opened. Passages are first opened wherever they join cells
+//that aren't connected yet, then every listed part gets extra more.
+pub fn stitch(grid: &dyn MazeGrid, part_of: &[usize], parts: &[usize], extra: usize, rng: &mut dyn RngCore, trace: &mut dyn FnMut(MazeStep)) {
+    //Sets of cells already connected to each other
+    let mut parent: Vec<usize> = (0..grid.cell_count()).collect();
+    for cell in 0..grid.cell_count() {
+        if !grid.is_enabled(cell) { continue; }
+
+        for neighbor in grid.passages(cell) {
+            let one = find_set(&mut parent, cell);
+            let two = find_set(&mut parent, neighbor);
+            parent[one] = two;
+        }
+    }
+
+    //Every closed wall on the boundary of a listed part could be opened, unless it's locked
+    let mut candidates = Vec::new();
+    for cell in 0..grid.cell_count() {
+        if !grid.is_enabled(cell) { continue; }
+
+        for (neighbor, wall) in grid.neighbors(cell) {
+            let listed = parts.contains(&part_of[cell]) || parts.contains(&part_of[neighbor]);
+            let closed = wall.borrow().active && !wall.borrow().locked;
+
+            if cell < neighbor && part_of[cell] != part_of[neighbor] && listed && closed {
+                candidates.push((cell, neighbor, wall));
+            }
+        }
+    }
+
+    for i in (1..candidates.len()).rev() {
+        let random = rng.gen_range(0..=i);
+        candidates.swap(i, random);
+    }
+
+    //Passages that connect the maze
+    for (cell, neighbor, wall) in candidates.iter() {
+        let one = find_set(&mut parent, *cell);
+        let two = find_set(&mut parent, *neighbor);
+        if one == two { continue; }
+
+        parent[one] = two;
+        wall.borrow_mut().active = false;
+        trace(MazeStep::Carve(*cell, *neighbor));
+    }
+
+    //Extra passages out of each listed part
+    for part in parts.iter() {
+        let mut opened = 0;
+
+        for (cell, neighbor, wall) in candidates.iter() {
+            if opened == extra { break; }
+            if !wall.borrow().active || (part_of[*cell] != *part && part_of[*neighbor] != *part) { continue; }
+
+            wall.borrow_mut().active = false;
+            trace(MazeStep::Carve(*cell, *neighbor));
+            opened += 1;
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use super::maze_algorithm::MazeAlgorithm;
+use super::maze_config::{AlgorithmConfig, ConfigError, Bias, DepthFirstSearchConfig, PrimsConfig, KruskalsConfig, FrontierPolicy};
+
+//A value given for one of an algorithm's parameters
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamValue {
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Text(String),
+}
+
+impl fmt::Display for ParamValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamValue::Integer(value) => write!(f, "{}", value),
+            ParamValue::Float(value) => write!(f, "{}", value),
+            ParamValue::Boolean(value) => write!(f, "{}", value),
+            ParamValue::Text(value) => write!(f, "\"{}\"", value),
+        }
+    }
+}
+
+//Which values a parameter accepts
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParamKind {
+    Integer { min: i64, max: i64 },
+    Float { min: f64, max: f64 },
+    Boolean,
+    Choice(Vec<String>),                //One of the listed words
+}
+
+impl ParamKind {
+    //Checks that value is one this kind of parameter accepts, giving the reason if it isn't
+    pub fn check(&self, value: &ParamValue) -> Result<(), String> {
+        match (self, value) {
+            (ParamKind::Integer { min, max }, ParamValue::Integer(value)) => {
+                if value < min || value > max { Err(format!("{} is not from {} to {}", value, min, max)) } else { Ok(()) }
+            }
+            (ParamKind::Float { min, max }, ParamValue::Float(value)) => {
+                if !(*min..=*max).contains(value) { Err(format!("{} is not from {} to {}", value, min, max)) } else { Ok(()) }
+            }
+            (ParamKind::Boolean, ParamValue::Boolean(_)) => Ok(()),
+            (ParamKind::Choice(choices), ParamValue::Text(value)) => {
+                if choices.contains(value) { Ok(()) } else { Err(format!("\"{}\" is not one of {}", value, choices.join(", "))) }
+            }
+            (kind, value) => Err(format!("{} is not {}", value, kind)),
+        }
+    }
+}
+
+impl fmt::Display for ParamKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParamKind::Integer { min, max } => write!(f, "an integer from {} to {}", min, max),
+            ParamKind::Float { min, max } => write!(f, "a number from {} to {}", min, max),
+            ParamKind::Boolean => write!(f, "true or false"),
+            ParamKind::Choice(choices) => write!(f, "one of {}", choices.join(", ")),
+        }
+    }
+}
+
+//Describes a single parameter an algorithm can be given
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParamSchema {
+    pub name: String,
+    pub description: String,
+    pub kind: ParamKind,
+    pub default: ParamValue,
+}
+
+impl ParamSchema {
+    //Constructor
+    pub fn new(name: &str, description: &str, kind: ParamKind, default: ParamValue) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            kind,
+            default,
+        }
+    }
+}
+
+//Everything a caller needs to know to pick an algorithm and fill in its parameters
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlgorithmInfo {
+    pub name: String,
+    pub description: String,
+    pub params: Vec<ParamSchema>,
+}
+
+impl AlgorithmInfo {
+    //Constructor for an algorithm without any parameters
+    pub fn new(name: &str, description: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            params: Vec::new(),
+        }
+    }
+
+    //Adds a parameter to the algorithm's schema
+    pub fn with_param(mut self, param: ParamSchema) -> Self {
+        self.params.push(param);
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Option<&ParamSchema> {
+        self.params.iter().find(|param| param.name == name)
+    }
+}
+
+//Parameters for a single algorithm, by name
+pub type Params = HashMap<String, ParamValue>;
+
+//Builds an algorithm from its parameters. The registry has already checked the parameters against the schema
+//and filled in the defaults for any that were left out, so every parameter in the schema is there.
+pub type AlgorithmFactory = Box<dyn Fn(&Params) -> Result<Box<dyn MazeAlgorithm>, RegistryError>>;
+
+#[derive(Debug, PartialEq)]
+pub enum RegistryError {
+    UnknownAlgorithm(String),
+    DuplicateName(String),
+    UnknownParam(String, String),       //Algorithm name and the parameter it doesn't have
+    InvalidParam(String, String),       //Parameter name and why its value isn't allowed
+    InvalidParams(String),              //Every value is allowed on its own, but not together
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::UnknownAlgorithm(name) => write!(f, "no algorithm is called \"{}\"", name),
+            RegistryError::DuplicateName(name) => write!(f, "an algorithm called \"{}\" is already registered", name),
+            RegistryError::UnknownParam(algorithm, param) => write!(f, "algorithm \"{}\" has no parameter \"{}\"", algorithm, param),
+            RegistryError::InvalidParam(param, reason) => write!(f, "invalid value for \"{}\": {}", param, reason),
+            RegistryError::InvalidParams(reason) => write!(f, "invalid parameters: {}", reason),
+        }
+    }
+}
+
+impl Error for RegistryError {}
+
+struct Entry {
+    info: AlgorithmInfo,
+    factory: AlgorithmFactory,
+}
+
+//Maps stable names to maze algorithms, so they can be listed and picked at runtime, e.g. from a config file.
+//Algorithms are kept in the order they were registered.
+pub struct AlgorithmRegistry {
+    entries: Vec<Entry>,
+}
+
+impl AlgorithmRegistry {
+    //Constructor for a registry with no algorithms in it
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    //Constructor for a registry with every algorithm in this crate. Other crates can register their own on top.
+    pub fn with_builtins() -> Self {
+        let mut tmp = Self::new();
+
+        let probability = |name: &str, description: &str| {
+            ParamSchema::new(name, description, ParamKind::Float { min: 0.0, max: 1.0 }, ParamValue::Float(0.0))
+        };
+        let frontier_policies = FrontierPolicy::ALL.iter().map(|policy| policy.name().to_string()).collect();
+
+        let builtins: Vec<(AlgorithmInfo, AlgorithmFactory)> = vec![
+            (
+                with_bias_params(with_start_param(AlgorithmInfo::new("depth_first_search", "Recursive backtracker. Long winding corridors with few dead ends.")))
+                    .with_param(probability("straightness", "Chance of going straight on when it can also turn"))
+                    .with_param(probability("turn_probability", "Chance of turning when it can also go straight on")),
+                Box::new(|params: &Params| {
+                    build(AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig {
+                        start: start(params),
+                        bias: bias(params),
+                        straightness: float(params, "straightness"),
+                        turn_probability: float(params, "turn_probability"),
+                    }))
+                }),
+            ),
+            (
+                with_bias_params(with_start_param(AlgorithmInfo::new("prims", "Randomized Prim's algorithm. Grows outwards from a cell, giving short branching corridors.")))
+                    .with_param(ParamSchema::new("frontier", "Which wall to carve next", ParamKind::Choice(frontier_policies), ParamValue::Text("random".to_string()))),
+                Box::new(|params: &Params| {
+                    let frontier = match params.get("frontier") {
+                        Some(ParamValue::Text(name)) => FrontierPolicy::from_name(name).unwrap_or_default(),
+                        _ => FrontierPolicy::default(),
+                    };
+
+                    build(AlgorithmConfig::Prims(PrimsConfig { start: start(params), bias: bias(params), frontier }))
+                }),
+            ),
+            (
+                with_bias_params(AlgorithmInfo::new("kruskals", "Randomized Kruskal's algorithm. Joins cells all over the maze at once, giving many short dead ends.")),
+                Box::new(|params: &Params| build(AlgorithmConfig::Kruskals(KruskalsConfig { bias: bias(params) }))),
+            ),
+        ];
+
+        for (info, factory) in builtins {
+            tmp.entries.push(Entry { info, factory });
+        }
+
+        tmp
+    }
+
+    //Adds an algorithm under info's name. Fails if the name is already taken.
+    pub fn register(&mut self, info: AlgorithmInfo, factory: AlgorithmFactory) -> Result<(), RegistryError> {
+        if self.info(&info.name).is_some() {
+            return Err(RegistryError::DuplicateName(info.name));
+        }
+
+        self.entries.push(Entry { info, factory });
+        Ok(())
+    }
+
+    //Getters
+    pub fn algorithms(&self) -> Vec<&AlgorithmInfo> { self.entries.iter().map(|entry| &entry.info).collect() }
+    pub fn names(&self) -> Vec<&str> { self.entries.iter().map(|entry| entry.info.name.as_str()).collect() }
+    pub fn info(&self, name: &str) -> Option<&AlgorithmInfo> { self.entry(name).map(|entry| &entry.info) }
+
+    //Builds the algorithm called name. Parameters that are left out get their defaults.
+    pub fn create(&self, name: &str, params: &Params) -> Result<Box<dyn MazeAlgorithm>, RegistryError> {
+        let entry = self.entry(name).ok_or_else(|| RegistryError::UnknownAlgorithm(name.to_string()))?;
+        let mut full_params = Params::new();
+
+        for (param, value) in params.iter() {
+            let schema = entry.info.param(param).ok_or_else(|| RegistryError::UnknownParam(name.to_string(), param.clone()))?;
+            schema.kind.check(value).map_err(|reason| RegistryError::InvalidParam(param.clone(), reason))?;
+        }
+
+        for schema in entry.info.params.iter() {
+            let value = params.get(&schema.name).unwrap_or(&schema.default);
+            full_params.insert(schema.name.clone(), value.clone());
+        }
+
+        (entry.factory)(&full_params)
+    }
+
+    //Builds the algorithm called name with all of its parameters left at their defaults
+    pub fn create_default(&self, name: &str) -> Result<Box<dyn MazeAlgorithm>, RegistryError> {
+        self.create(name, &Params::new())
+    }
+
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.info.name == name)
+    }
+}
+
+//Parameters shared by the built in algorithms. The registry fills in every parameter before calling a factory,
+//so the defaults here only matter to factories called some other way.
+fn with_start_param(info: AlgorithmInfo) -> AlgorithmInfo {
+    info.with_param(ParamSchema::new("start", "Number of the first cell, -1 for a random one", ParamKind::Integer { min: -1, max: i64::MAX }, ParamValue::Integer(-1)))
+}
+
+fn with_bias_params(info: AlgorithmInfo) -> AlgorithmInfo {
+    let weight = |name: &str, description: &str| ParamSchema::new(name, description, ParamKind::Float { min: 0.0, max: 1000.0 }, ParamValue::Float(1.0));
+
+    info.with_param(weight("horizontal_weight", "How likely passages are to run across"))
+        .with_param(weight("vertical_weight", "How likely passages are to run up and down"))
+}
+
+fn float(params: &Params, name: &str) -> f64 {
+    match params.get(name) {
+        Some(ParamValue::Float(value)) => *value,
+        _ => 0.0,
+    }
+}
+
+fn start(params: &Params) -> Option<usize> {
+    match params.get("start") {
+        Some(ParamValue::Integer(value)) if *value >= 0 => Some(*value as usize),
+        _ => None,
+    }
+}
+
+fn bias(params: &Params) -> Bias {
+    let default = Bias::default();
+
+    Bias {
+        horizontal: if params.contains_key("horizontal_weight") { float(params, "horizontal_weight") } else { default.horizontal },
+        vertical: if params.contains_key("vertical_weight") { float(params, "vertical_weight") } else { default.vertical },
+    }
+}
+
+fn build(config: AlgorithmConfig) -> Result<Box<dyn MazeAlgorithm>, RegistryError> {
+    config.build().map_err(|error| match error {
+        ConfigError::Invalid(reason) => RegistryError::InvalidParams(reason),
+        other => RegistryError::InvalidParams(other.to_string()),
+    })
+}
+
+impl Default for AlgorithmRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,255 @@
+extern crate png;
+
+use std::fmt::Write as FmtWrite;
+use std::io::{self, Write};
+
+use png::{BitDepth, ColorType, Encoder, EncodingError};
+
+pub type Colour = [u8; 3];
+
+//A wall of a maze, measured in cell units. Arc angles are in radians and go clockwise from the positive x axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stroke {
+    Line { from: (f64, f64), to: (f64, f64) },
+    Arc { centre: (f64, f64), radius: f64, start: f64, end: f64 },
+}
+
+//An area of the drawing filled with a single colour, like a cell's terrain. The outline is a polygon in cell units.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fill {
+    pub outline: Vec<(f64, f64)>,
+    pub colour: Colour,
+}
+
+impl Fill {
+    //A unit square with its top left corner at (x, y)
+    pub fn square(x: f64, y: f64, colour: Colour) -> Self {
+        Self { outline: vec![(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)], colour }
+    }
+}
+
+//Point on a circle at angle, in the same units as centre and radius
+fn on_circle(centre: (f64, f64), radius: f64, angle: f64) -> (f64, f64) {
+    (centre.0 + radius * angle.cos(), centre.1 + radius * angle.sin())
+}
+
+//Any maze that can be drawn as a picture. Every topology describes its walls as strokes,
+//and the SVG and PNG renderers draw them the same way.
+pub trait MazeDrawing {
+    //Size of the drawing in cell units
+    fn extent(&self) -> (f64, f64);
+
+    //Every active wall of the maze
+    fn wall_strokes(&self) -> Vec<Stroke>;
+
+    //Coloured areas drawn underneath the walls. Mazes have none unless they say otherwise.
+    fn fills(&self) -> Vec<Fill> { Vec::new() }
+}
+
+//Settings for SVG and PNG output. Sizes are in pixels.
+#[derive(Clone, Debug)]
+pub struct RenderOptions {
+    pub cell_size: f64,     //Pixels per cell unit
+    pub wall_width: f64,
+    pub margin: f64,
+    pub wall: Colour,
+    pub background: Colour,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 20.0,
+            wall_width: 2.0,
+            margin: 10.0,
+            wall: [0, 0, 0],
+            background: [255, 255, 255],
+        }
+    }
+}
+
+//Maps a position in cell units to a position in pixels
+fn to_pixels(point: (f64, f64), options: &RenderOptions) -> (f64, f64) {
+    (options.margin + point.0 * options.cell_size, options.margin + point.1 * options.cell_size)
+}
+
+fn image_size(drawing: &dyn MazeDrawing, options: &RenderOptions) -> (f64, f64) {
+    let (width, height) = drawing.extent();
+    (width * options.cell_size + 2.0 * options.margin, height * options.cell_size + 2.0 * options.margin)
+}
+
+fn hex_colour(colour: Colour) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+pub fn render_svg(drawing: &dyn MazeDrawing, options: &RenderOptions) -> String {
+    let (width, height) = image_size(drawing, options);
+    let mut svg = String::new();
+
+    //Writing to a String can't fail, so the results are ignored
+    let _ = writeln!(svg, "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" viewBox=\"0 0 {:.2} {:.2}\">", width.ceil(), height.ceil(), width, height);
+    let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>", hex_colour(options.background));
+
+    for fill in drawing.fills() {
+        let points: Vec<String> = fill.outline.iter()
+            .map(|point| to_pixels(*point, options))
+            .map(|point| format!("{:.2},{:.2}", point.0, point.1))
+            .collect();
+        let _ = writeln!(svg, "<polygon points=\"{}\" fill=\"{}\"/>", points.join(" "), hex_colour(fill.colour));
+    }
+
+    let _ = writeln!(svg, "<g stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"round\" fill=\"none\">", hex_colour(options.wall), options.wall_width);
+
+    for stroke in drawing.wall_strokes() {
+        match stroke {
+            Stroke::Line { from, to } => {
+                let from = to_pixels(from, options);
+                let to = to_pixels(to, options);
+                let _ = writeln!(svg, "<line x1=\"{:.2}\" y1=\"{:.2}\" x2=\"{:.2}\" y2=\"{:.2}\"/>", from.0, from.1, to.0, to.1);
+            }
+
+            Stroke::Arc { centre, radius, start, end } => {
+                //SVG can't draw a full circle as a single arc, so arcs are split up into halves
+                let halves = if end - start > std::f64::consts::PI { 2 } else { 1 };
+
+                for half in 0..halves {
+                    let angle_one = start + (end - start) * half as f64 / halves as f64;
+                    let angle_two = start + (end - start) * (half + 1) as f64 / halves as f64;
+                    let from = to_pixels(on_circle(centre, radius, angle_one), options);
+                    let to = to_pixels(on_circle(centre, radius, angle_two), options);
+                    let radius = radius * options.cell_size;
+
+                    //Sweep flag 1 draws the arc clockwise, in the direction of increasing angles
+                    let _ = writeln!(svg, "<path d=\"M {:.2} {:.2} A {:.2} {:.2} 0 0 1 {:.2} {:.2}\"/>", from.0, from.1, radius, radius, to.0, to.1);
+                }
+            }
+        }
+    }
+
+    svg += "</g>\n</svg>\n";
+    svg
+}
+
+pub fn render_png<W: Write>(drawing: &dyn MazeDrawing, options: &RenderOptions, writer: W) -> Result<(), EncodingError> {
+    let (width, height) = image_size(drawing, options);
+    let width = width.ceil() as usize;
+    let height = height.ceil() as usize;
+
+    if width == 0 || height == 0 || width > u32::MAX as usize || height > u32::MAX as usize {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "maze is too small or too large to export as a PNG").into());
+    }
+
+    let mut canvas = Canvas::new(width, height, options.background);
+
+    for fill in drawing.fills() {
+        let outline: Vec<(f64, f64)> = fill.outline.iter().map(|point| to_pixels(*point, options)).collect();
+        canvas.fill_polygon(&outline, fill.colour);
+    }
+
+    for stroke in drawing.wall_strokes() {
+        match stroke {
+            Stroke::Line { from, to } => {
+                canvas.draw_line(to_pixels(from, options), to_pixels(to, options), options.wall_width, options.wall);
+            }
+
+            Stroke::Arc { centre, radius, start, end } => {
+                //Arcs are drawn as a series of short lines, about two pixels long each
+                let length = radius * (end - start) * options.cell_size;
+                let segments = ((length / 2.0).ceil() as usize).max(1);
+
+                for segment in 0..segments {
+                    let angle_one = start + (end - start) * segment as f64 / segments as f64;
+                    let angle_two = start + (end - start) * (segment + 1) as f64 / segments as f64;
+                    let from = to_pixels(on_circle(centre, radius, angle_one), options);
+                    let to = to_pixels(on_circle(centre, radius, angle_two), options);
+                    canvas.draw_line(from, to, options.wall_width, options.wall);
+                }
+            }
+        }
+    }
+
+    let mut encoder = Encoder::new(writer, width as u32, height as u32);
+    encoder.set_color(ColorType::Rgb);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&canvas.pixels)
+}
+
+//An RGB image that is drawn on by the PNG renderer
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, background: Colour) -> Self {
+        Self {
+            width,
+            height,
+            pixels: background.iter().cloned().cycle().take(width * height * 3).collect(),
+        }
+    }
+
+    fn set_pixel(&mut self, x: usize, y: usize, colour: Colour) {
+        let index = (y * self.width + x) * 3;
+        self.pixels[index..(index + 3)].copy_from_slice(&colour);
+    }
+
+    //Colours every pixel whose centre is inside the polygon, using the even-odd rule
+    fn fill_polygon(&mut self, outline: &[(f64, f64)], colour: Colour) {
+        if outline.len() < 3 { return; }
+
+        let min_x = outline.iter().map(|point| point.0).fold(f64::INFINITY, f64::min).floor().max(0.0) as usize;
+        let min_y = outline.iter().map(|point| point.1).fold(f64::INFINITY, f64::min).floor().max(0.0) as usize;
+        let max_x = (outline.iter().map(|point| point.0).fold(0.0, f64::max).ceil() as usize).min(self.width);
+        let max_y = (outline.iter().map(|point| point.1).fold(0.0, f64::max).ceil() as usize).min(self.height);
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+                let mut inside = false;
+
+                //Count how many edges a ray going right from the pixel's centre crosses
+                for (index, one) in outline.iter().enumerate() {
+                    let two = outline[(index + 1) % outline.len()];
+                    if (one.1 > py) != (two.1 > py) && px < one.0 + (py - one.1) * (two.0 - one.0) / (two.1 - one.1) {
+                        inside = !inside;
+                    }
+                }
+
+                if inside {
+                    self.set_pixel(x, y, colour);
+                }
+            }
+        }
+    }
+
+    //Colours every pixel whose centre is within thickness / 2 of the line between from and to
+    fn draw_line(&mut self, from: (f64, f64), to: (f64, f64), thickness: f64, colour: Colour) {
+        let radius = thickness / 2.0;
+
+        let min_x = (from.0.min(to.0) - radius).floor().max(0.0) as usize;
+        let min_y = (from.1.min(to.1) - radius).floor().max(0.0) as usize;
+        let max_x = ((from.0.max(to.0) + radius).ceil().max(0.0) as usize).min(self.width);
+        let max_y = ((from.1.max(to.1) + radius).ceil().max(0.0) as usize).min(self.height);
+
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let length_squared = dx * dx + dy * dy;
+
+        for y in min_y..max_y {
+            for x in min_x..max_x {
+                let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+
+                //Closest point on the line to the pixel's centre
+                let t = if length_squared == 0.0 { 0.0 } else { (((px - from.0) * dx + (py - from.1) * dy) / length_squared).clamp(0.0, 1.0) };
+                let (cx, cy) = (from.0 + t * dx, from.1 + t * dy);
+
+                if (px - cx) * (px - cx) + (py - cy) * (py - cy) <= radius * radius {
+                    self.set_pixel(x, y, colour);
+                }
+            }
+        }
+    }
+}
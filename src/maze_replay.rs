@@ -0,0 +1,195 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use serde::{Serialize, Deserialize};
+
+use super::maze_3d::Maze3D;
+use super::maze_enemy::Behaviour;
+use super::maze_error::MazeError;
+use super::maze_items::ItemOptions;
+use super::maze_play::{self, MazeGame};
+use super::maze_regenerate::Regenerate;
+use super::maze_registry::{AlgorithmRegistry, RegistryError};
+use super::maze_visibility::SightOptions;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Parse(String),
+    Registry(RegistryError),    //The replay asks for an algorithm the registry can't build
+    Maze(MazeError),            //The game in the replay can't be set up
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "could not read replay: {}", error),
+            ReplayError::Parse(reason) => write!(f, "could not parse replay: {}", reason),
+            ReplayError::Registry(error) => write!(f, "could not build the replay's maze: {}", error),
+            ReplayError::Maze(error) => write!(f, "could not set up the replay's game: {}", error),
+        }
+    }
+}
+
+impl Error for ReplayError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ReplayError::Io(error) => Some(error),
+            ReplayError::Registry(error) => Some(error),
+            ReplayError::Maze(error) => Some(error),
+            ReplayError::Parse(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+impl From<RegistryError> for ReplayError {
+    fn from(error: RegistryError) -> Self {
+        ReplayError::Registry(error)
+    }
+}
+
+impl From<MazeError> for ReplayError {
+    fn from(error: MazeError) -> Self {
+        ReplayError::Maze(error)
+    }
+}
+
+//Everything needed to set up the same game again. The maze is generated by the registry algorithm
+//of that name from seed, and the items and monsters are placed from the same seed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GameSetup {
+    pub width: usize,
+    pub height: usize,
+    pub floors: usize,
+    pub algorithm: String,
+    pub seed: u64,
+    #[serde(default)]
+    pub sight: Option<SightOptions>,    //Fog of war, if it's played with it
+    #[serde(default)]
+    pub items: Option<ItemOptions>,
+    #[serde(default)]
+    pub monsters: Vec<Behaviour>,
+}
+
+impl GameSetup {
+    //Constructor for a game with no fog, items or monsters
+    pub fn new(width: usize, height: usize, floors: usize, algorithm: &str, seed: u64) -> Self {
+        Self {
+            width,
+            height,
+            floors,
+            algorithm: algorithm.to_string(),
+            seed,
+            sight: None,
+            items: None,
+            monsters: Vec::new(),
+        }
+    }
+
+    //Sets up a new game, with its algorithm taken from registry
+    pub fn build(&self, registry: &AlgorithmRegistry) -> Result<MazeGame, ReplayError> {
+        let mut maze = Maze3D::try_new(self.width, self.height, self.floors, registry.create_default(&self.algorithm)?)?;
+        maze.regenerate_with_seed(self.seed);
+
+        let mut game = match self.sight {
+            Some(sight) => MazeGame::with_fog(maze, sight),
+            None => MazeGame::new(maze),
+        };
+        if let Some(items) = &self.items {
+            game.place_items(items, self.seed)?;
+        }
+        game.spawn_enemies(&self.monsters);
+
+        Ok(game)
+    }
+}
+
+//A line of commands the player entered, millis milliseconds after the game started
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayInput {
+    pub millis: u64,
+    pub line: String,
+}
+
+//A recorded game session, kept as a JSON file so it can be shared and played back
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Replay {
+    pub setup: GameSetup,
+    pub inputs: Vec<ReplayInput>,
+}
+
+impl Replay {
+    //Constructor for a replay with nothing played yet
+    pub fn new(setup: GameSetup) -> Self {
+        Self { setup, inputs: Vec::new() }
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ReplayError> {
+        serde_json::from_str(text).map_err(|error| ReplayError::Parse(error.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, ReplayError> {
+        Self::from_json(&fs::read_to_string(path)?)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_json())
+    }
+}
+
+//Plays game like maze_play::run, and records every line read from input into replay.
+//clock gives the time since any fixed moment, and is read as each line comes in.
+pub fn record<R: BufRead, W: Write>(replay: &mut Replay, game: &mut MazeGame, input: R, mut output: W, clock: &mut dyn FnMut() -> Duration) -> io::Result<()> {
+    let started = clock();
+    writeln!(output, "{}", maze_play::HELP)?;
+    write!(output, "{}", game)?;
+
+    for line in input.lines() {
+        let line = line?;
+        let millis = clock().saturating_sub(started).as_millis() as u64;
+        replay.inputs.push(ReplayInput { millis, line: line.clone() });
+
+        if !maze_play::play_line(game, &line, &mut output)? { break; }
+    }
+
+    Ok(())
+}
+
+//Plays replay again from the start, drawing it to output. wait is given the time to pause before each line,
+//which is the time the player took divided by speed. A speed of 0 plays everything without pausing,
+//and pauses too long to fit in a Duration are cut down to the longest one that does.
+//Returns the game as it was when the replay ended.
+pub fn play_back<W: Write>(replay: &Replay, registry: &AlgorithmRegistry, mut output: W, speed: f64, wait: &mut dyn FnMut(Duration)) -> Result<MazeGame, ReplayError> {
+    let mut game = replay.setup.build(registry)?;
+    write!(output, "{}", game)?;
+
+    let mut last = 0;
+    for input in &replay.inputs {
+        if speed > 0.0 {
+            let seconds = input.millis.saturating_sub(last) as f64 / 1000.0 / speed;
+            wait(Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX));
+        }
+        last = input.millis;
+
+        writeln!(output, "> {}", input.line)?;
+        if !maze_play::play_line(&mut game, &input.line, &mut output)? { break; }
+    }
+
+    Ok(game)
+}
@@ -0,0 +1,182 @@
+extern crate rand;
+
+use rand::{Rng, RngCore};
+
+use super::maze_algorithm::{MazeStep, find_set};
+use super::maze_grid::{MazeGrid, Point};
+
+//A rectangle of cells with every wall inside it removed. x and y are the top left cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Room {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Room {
+    //Constructor
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self { x, y, width, height }
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.x < self.x + self.width && point.y >= self.y && point.y < self.y + self.height
+    }
+
+    //Every cell in the room, row by row
+    pub fn points(&self) -> Vec<Point> {
+        (self.y..self.y + self.height).flat_map(|y| (self.x..self.x + self.width).map(move |x| Point { x, y })).collect()
+    }
+
+    //True if the rooms share a cell, or come closer than gap cells to each other
+    pub fn overlaps(&self, other: &Room, gap: usize) -> bool {
+        self.x < other.x + other.width + gap && other.x < self.x + self.width + gap &&
+            self.y < other.y + other.height + gap && other.y < self.y + self.height + gap
+    }
+}
+
+//Which rooms a maze is generated with. The listed rooms are always placed, then up to random_rooms more are placed
+//at random where they fit, with sides from min_size to max_size cells long. Random rooms keep a corridor's width
+//away from every other room.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoomLayout {
+    pub rooms: Vec<Room>,
+    pub random_rooms: usize,
+    pub min_size: usize,
+    pub max_size: usize,
+    pub doors: usize,       //Doors each room gets at least. A room can get more if it's needed to keep the maze connected.
+}
+
+impl Default for RoomLayout {
+    fn default() -> Self {
+        Self {
+            rooms: Vec::new(),
+            random_rooms: 0,
+            min_size: 2,
+            max_size: 4,
+            doors: 1,
+        }
+    }
+}
+
+//Random rooms are tried this many times over for every room asked for before giving up
+const PLACEMENT_ATTEMPTS: usize = 20;
+
+//Picks the rooms of layout for a width x height maze. fits decides which cells a room may cover.
+//Listed rooms that don't fit are left out.
+pub fn place_rooms(layout: &RoomLayout, width: usize, height: usize, fits: &dyn Fn(Point) -> bool, rng: &mut dyn RngCore) -> Vec<Room> {
+    let mut rooms: Vec<Room> = Vec::new();
+
+    let inside = |room: &Room| room.width > 0 && room.height > 0 && room.x + room.width <= width && room.y + room.height <= height;
+    let free = |room: &Room, rooms: &Vec<Room>, gap: usize| rooms.iter().all(|other| !room.overlaps(other, gap));
+
+    for room in layout.rooms.iter() {
+        if inside(room) && free(room, &rooms, 0) && room.points().into_iter().all(fits) {
+            rooms.push(*room);
+        }
+    }
+
+    let min_size = layout.min_size.max(1);
+    let max_size = layout.max_size.max(min_size);
+    let mut placed = 0;
+
+    for _ in 0..layout.random_rooms * PLACEMENT_ATTEMPTS {
+        if placed == layout.random_rooms { break; }
+
+        let room_width = rng.gen_range(min_size..=max_size);
+        let room_height = rng.gen_range(min_size..=max_size);
+        if room_width > width || room_height > height { continue; }
+
+        let room = Room::new(rng.gen_range(0..=width - room_width), rng.gen_range(0..=height - room_height), room_width, room_height);
+
+        if free(&room, &rooms, 1) && room.points().into_iter().all(fits) {
+            rooms.push(room);
+            placed += 1;
+        }
+    }
+
+    rooms
+}
+
+//Removes every wall inside the rooms, then opens doors between the rooms and the rest of grid.
+//room_of gives the room each cell is in. Doors are first opened wherever they join parts of the maze
+//that aren't connected yet, then more are added until every room has at least doors of them.
+pub fn open_rooms(grid: &dyn MazeGrid, room_of: &[Option<usize>], doors: usize, rng: &mut dyn RngCore, trace: &mut dyn FnMut(MazeStep)) {
+    let room_count = room_of.iter().flatten().max().map_or(0, |last| last + 1);
+    if room_count == 0 { return; }
+
+    //Room interiors
+    for cell in 0..grid.cell_count() {
+        if room_of[cell].is_none() || !grid.is_enabled(cell) { continue; }
+        trace(MazeStep::Visit(cell));
+
+        for (neighbor, wall) in grid.neighbors(cell) {
+            if cell < neighbor && room_of[neighbor] == room_of[cell] && !wall.borrow().locked {
+                wall.borrow_mut().active = false;
+                trace(MazeStep::Carve(cell, neighbor));
+            }
+        }
+    }
+
+    //Sets of cells already connected to each other
+    let mut parent: Vec<usize> = (0..grid.cell_count()).collect();
+    for cell in 0..grid.cell_count() {
+        if !grid.is_enabled(cell) { continue; }
+
+        for neighbor in grid.passages(cell) {
+            let one = find_set(&mut parent, cell);
+            let two = find_set(&mut parent, neighbor);
+            parent[one] = two;
+        }
+    }
+
+    //Every wall between a room and anything outside it could be a door, unless it's locked
+    let mut candidates = Vec::new();
+    for cell in 0..grid.cell_count() {
+        if !grid.is_enabled(cell) { continue; }
+
+        for (neighbor, wall) in grid.neighbors(cell) {
+            if wall.borrow().locked { continue; }
+
+            if cell < neighbor && room_of[cell] != room_of[neighbor] && (room_of[cell].is_some() || room_of[neighbor].is_some()) {
+                candidates.push((cell, neighbor, wall));
+            }
+        }
+    }
+
+    for i in (1..candidates.len()).rev() {
+        let random = rng.gen_range(0..=i);
+        candidates.swap(i, random);
+    }
+
+    let mut door_count = vec![0; room_count];
+    let mut open_door = |cell: usize, neighbor: usize, door_count: &mut Vec<usize>| {
+        for room in [room_of[cell], room_of[neighbor]].iter().flatten() {
+            door_count[*room] += 1;
+        }
+        trace(MazeStep::Carve(cell, neighbor));
+    };
+
+    //Doors that connect the maze
+    for (cell, neighbor, wall) in candidates.iter() {
+        let one = find_set(&mut parent, *cell);
+        let two = find_set(&mut parent, *neighbor);
+        if one == two { continue; }
+
+        parent[one] = two;
+        wall.borrow_mut().active = false;
+        open_door(*cell, *neighbor, &mut door_count);
+    }
+
+    //Extra doors for rooms that don't have enough yet
+    for (cell, neighbor, wall) in candidates.iter() {
+        if !wall.borrow().active { continue; }
+
+        let short = [room_of[*cell], room_of[*neighbor]].iter().flatten().any(|room| door_count[*room] < doors);
+        if !short { continue; }
+
+        wall.borrow_mut().active = false;
+        open_door(*cell, *neighbor, &mut door_count);
+    }
+}
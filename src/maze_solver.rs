@@ -0,0 +1,143 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::maze_algorithm::MazeStep;
+use super::maze_grid::MazeGrid;
+
+//Finds the shortest path from the entrance to the exit of the maze, as a list of cells.
+//Returns None if the exit can't be reached.
+pub fn solve(grid: &dyn MazeGrid) -> Option<Vec<usize>> {
+    solve_traced(grid, &mut |_| {})
+}
+
+//Same as solve, but reports every step of the search to trace as it happens
+pub fn solve_traced(grid: &dyn MazeGrid, trace: &mut dyn FnMut(MazeStep)) -> Option<Vec<usize>> {
+    /*
+    Breadth first search
+    1. Add the entrance to the queue.
+    2. While there are cells in the queue:
+        1. Take the first cell out of the queue. If it is the exit, we are done.
+        2. Add every unvisited neighbor that isn't blocked by a wall to the queue,
+           remembering which cell it was reached from.
+    3. Walk back from the exit to the entrance to build the path.
+    */
+
+    let (entrance, exit) = grid.exits()?;
+
+    let mut previous: Vec<Option<usize>> = vec![None; grid.cell_count()];
+    let mut queued = vec![false; grid.cell_count()];
+    let mut queue: VecDeque<usize> = VecDeque::new();
+
+    queued[entrance] = true;
+    queue.push_back(entrance);
+    trace(MazeStep::Frontier(entrance));
+
+    while let Some(current) = queue.pop_front() {
+        trace(MazeStep::Visit(current));
+
+        if current == exit {
+            //Walk back through the previous cells to rebuild the path
+            let mut path = vec![exit];
+            let mut cell = exit;
+            while let Some(value) = previous[cell] {
+                path.push(value);
+                cell = value;
+            }
+            path.reverse();
+
+            for cell in path.iter() {
+                trace(MazeStep::Path(*cell));
+            }
+
+            return Some(path);
+        }
+
+        for neighbor in grid.passages(current) {
+            if queued[neighbor] { continue; }
+
+            queued[neighbor] = true;
+            previous[neighbor] = Some(current);
+            queue.push_back(neighbor);
+            trace(MazeStep::Frontier(neighbor));
+        }
+    }
+
+    None
+}
+
+//Finds the cheapest path from the entrance to the exit, where every cell costs what the grid says it does to step into.
+//Returns the path and its cost, None if the exit can't be reached.
+//A perfect maze only has one path between two cells, so this only differs from solve on mazes with loops.
+pub fn solve_cheapest(grid: &dyn MazeGrid) -> Option<(Vec<usize>, u32)> {
+    solve_astar_traced(grid, &|_| 0, &mut |_| {})
+}
+
+//Same as solve_cheapest, but guided by heuristic, which estimates the cost from a cell to the exit.
+//The heuristic must never overestimate, or the path found might not be the cheapest.
+pub fn solve_astar(grid: &dyn MazeGrid, heuristic: &dyn Fn(usize) -> u32) -> Option<(Vec<usize>, u32)> {
+    solve_astar_traced(grid, heuristic, &mut |_| {})
+}
+
+//Same as solve_astar, but reports every step of the search to trace as it happens
+pub fn solve_astar_traced(grid: &dyn MazeGrid, heuristic: &dyn Fn(usize) -> u32, trace: &mut dyn FnMut(MazeStep)) -> Option<(Vec<usize>, u32)> {
+    /*
+    A* search, which is Dijkstra's algorithm when the heuristic is always 0
+    1. Add the entrance to the queue with a cost of 0.
+    2. While there are cells in the queue:
+        1. Take the cell with the lowest cost plus estimate out of the queue. If it is the exit, we are done.
+        2. For every neighbor that isn't blocked by a wall, if going through this cell is cheaper than
+           the best way found to it so far, remember that and add it to the queue.
+    3. Walk back from the exit to the entrance to build the path.
+    */
+
+    let (entrance, exit) = grid.exits()?;
+
+    let mut cost: Vec<Option<u32>> = vec![None; grid.cell_count()];
+    let mut previous: Vec<Option<usize>> = vec![None; grid.cell_count()];
+    let mut done = vec![false; grid.cell_count()];
+    let mut queue: BinaryHeap<Reverse<(u32, usize)>> = BinaryHeap::new();
+
+    cost[entrance] = Some(0);
+    queue.push(Reverse((heuristic(entrance), entrance)));
+    trace(MazeStep::Frontier(entrance));
+
+    while let Some(Reverse((_, current))) = queue.pop() {
+        //A cell can be queued several times as cheaper ways to it are found, only the first one counts
+        if done[current] { continue; }
+        done[current] = true;
+        trace(MazeStep::Visit(current));
+
+        let current_cost = cost[current].unwrap_or(0);
+
+        if current == exit {
+            //Walk back through the previous cells to rebuild the path
+            let mut path = vec![exit];
+            let mut cell = exit;
+            while let Some(value) = previous[cell] {
+                path.push(value);
+                cell = value;
+            }
+            path.reverse();
+
+            for cell in path.iter() {
+                trace(MazeStep::Path(*cell));
+            }
+
+            return Some((path, current_cost));
+        }
+
+        for neighbor in grid.passages(current) {
+            if done[neighbor] { continue; }
+
+            let new_cost = current_cost + grid.cost(neighbor);
+            if cost[neighbor].is_some_and(|value| value <= new_cost) { continue; }
+
+            cost[neighbor] = Some(new_cost);
+            previous[neighbor] = Some(current);
+            queue.push(Reverse((new_cost + heuristic(neighbor), neighbor)));
+            trace(MazeStep::Frontier(neighbor));
+        }
+    }
+
+    None
+}
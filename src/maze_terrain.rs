@@ -0,0 +1,36 @@
+use super::maze_render::Colour;
+
+//What the ground of a cell is made of. Every terrain has a cost for stepping into the cell,
+//which the cost aware solvers add up along a path.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    #[default]
+    Floor,
+    Ice,
+    Mud,
+    Water,
+}
+
+impl Terrain {
+    pub const ALL: [Terrain; 4] = [Terrain::Floor, Terrain::Ice, Terrain::Mud, Terrain::Water];
+
+    //Cost of stepping into a cell with this terrain. Floor is the cheapest, so an estimate of one per cell never overestimates.
+    pub fn cost(self) -> u32 {
+        match self {
+            Terrain::Floor => 1,
+            Terrain::Ice => 2,
+            Terrain::Mud => 4,
+            Terrain::Water => 8,
+        }
+    }
+
+    //Colour the renderers fill the cell with. Floor is left as the background.
+    pub fn colour(self) -> Option<Colour> {
+        match self {
+            Terrain::Floor => None,
+            Terrain::Ice => Some([200, 235, 250]),
+            Terrain::Mud => Some([150, 110, 70]),
+            Terrain::Water => Some([90, 150, 230]),
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+
+use super::maze::{Maze, Wraparound};
+use super::maze_grid::{MazeGrid, Point};
+
+//A single way in which a maze isn't a valid perfect maze. Cells are given as the grid's cell numbers,
+//except for the checks that only make sense on a square maze, which give points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MazeViolation {
+    NoCells,                        //The maze has no enabled cells, so there's nothing to walk through
+    Unreachable(usize),             //Cell can't be reached from the entrance
+    Cycle(usize, usize),            //The passage between the two cells makes a loop
+    UnsharedWall(Point, Point),     //The two neighboring cells each have their own wall between them instead of sharing one
+    BorderOpening(Point),           //Cell has an open wall on the border of the maze that isn't an exit
+    ClosedExit(Point),              //The entrance or exit at this cell doesn't lead out of the maze
+}
+
+impl fmt::Display for MazeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MazeViolation::NoCells => write!(f, "maze has no enabled cells"),
+            MazeViolation::Unreachable(cell) => write!(f, "cell {} can't be reached from the entrance", cell),
+            MazeViolation::Cycle(one, two) => write!(f, "passage between cells {} and {} makes a loop", one, two),
+            MazeViolation::UnsharedWall(one, two) => {
+                write!(f, "cells ({}, {}) and ({}, {}) don't share the wall between them", one.x, one.y, two.x, two.y)
+            }
+            MazeViolation::BorderOpening(point) => write!(f, "cell ({}, {}) has an opening in the border", point.x, point.y),
+            MazeViolation::ClosedExit(point) => write!(f, "exit at cell ({}, {}) isn't open", point.x, point.y),
+        }
+    }
+}
+
+impl Error for MazeViolation {}
+
+//Checks that every enabled cell can be reached from the entrance, and that there is only one way to reach it.
+//Works on any topology. Returns every violation found.
+pub fn validate_grid(grid: &dyn MazeGrid) -> Result<(), Vec<MazeViolation>> {
+    let (entrance, _) = match grid.exits() {
+        Some(value) => value,
+        None => return Err(vec![MazeViolation::NoCells]),
+    };
+
+    let mut violations = Vec::new();
+    let mut parent: Vec<Option<usize>> = vec![None; grid.cell_count()];
+    let mut reached = vec![false; grid.cell_count()];
+
+    search(grid, entrance, &mut reached, &mut parent, &mut violations);
+
+    for (cell, reached) in reached.iter().enumerate() {
+        if grid.is_enabled(cell) && !reached {
+            violations.push(MazeViolation::Unreachable(cell));
+        }
+    }
+
+    //Cells cut off from the entrance can still have loops between them
+    for cell in 0..grid.cell_count() {
+        if grid.is_enabled(cell) && !reached[cell] {
+            search(grid, cell, &mut reached, &mut parent, &mut violations);
+        }
+    }
+
+    if violations.is_empty() { Ok(()) } else { Err(violations) }
+}
+
+//Breadth first search over the passages from start, marking every cell it reaches. Every passage other than
+//the one a cell was reached through that leads back to a reached cell closes a loop, which is added to violations.
+fn search(grid: &dyn MazeGrid, start: usize, reached: &mut [bool], parent: &mut [Option<usize>], violations: &mut Vec<MazeViolation>) {
+    let mut queue: VecDeque<usize> = VecDeque::new();
+
+    reached[start] = true;
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        let mut skipped_parent = false;
+
+        for neighbor in grid.passages(current) {
+            //Skip the passage back to the parent once. A second passage to it is a loop of its own.
+            if parent[current] == Some(neighbor) && !skipped_parent {
+                skipped_parent = true;
+                continue;
+            }
+
+            if reached[neighbor] {
+                //Each loop is seen from both ends, only report it from the lower numbered cell
+                if current < neighbor {
+                    violations.push(MazeViolation::Cycle(current, neighbor));
+                }
+                continue;
+            }
+
+            reached[neighbor] = true;
+            parent[neighbor] = Some(current);
+            queue.push_back(neighbor);
+        }
+    }
+}
+
+//Checks everything validate_grid does, plus that neighboring cells share their walls
+//and that the only openings in the border are the entrance and exit. Returns every violation found.
+pub fn validate(maze: &Maze) -> Result<(), Vec<MazeViolation>> {
+    let mut violations = match validate_grid(maze) {
+        Ok(()) => Vec::new(),
+        Err(value) => value,
+    };
+
+    let width = maze.width();
+    let height = maze.height();
+    let cells = maze.cells();
+    let wraparound = maze.wraparound();
+    let wraps_x = wraparound.wraps_horizontally() && width > 1;
+    let wraps_y = wraparound.wraps_vertically() && height > 1;
+
+    //Shared walls, including the ones across joined edges
+    for x in 0..width {
+        for y in 0..height {
+            let right = if x + 1 < width { Some(x + 1) } else if wraps_x { Some(0) } else { None };
+            let down = if y + 1 < height { Some(y + 1) } else if wraps_y { Some(0) } else { None };
+
+            if let Some(right) = right {
+                if !Rc::ptr_eq(cells[x][y].right_wall_mut(), cells[right][y].left_wall_mut()) {
+                    violations.push(MazeViolation::UnsharedWall(Point { x, y }, Point { x: right, y }));
+                }
+            }
+            if let Some(down) = down {
+                if !Rc::ptr_eq(cells[x][y].bottom_wall_mut(), cells[x][down].top_wall_mut()) {
+                    violations.push(MazeViolation::UnsharedWall(Point { x, y }, Point { x, y: down }));
+                }
+            }
+        }
+    }
+
+    //Openings out of the maze. A wall leads out if there's no enabled cell on its other side.
+    let (entrance, exit) = match maze.exits() {
+        Some((entrance, exit)) => (maze.point(entrance), maze.point(exit)),
+        None => return Err(violations),
+    };
+
+    let leads_out = |point: Point, dx: isize, dy: isize| {
+        let mut x = point.x as isize + dx;
+        let mut y = point.y as isize + dy;
+        if wraps_x { x = x.rem_euclid(width as isize); }
+        if wraps_y { y = y.rem_euclid(height as isize); }
+
+        x < 0 || y < 0 || !maze.is_enabled_at(Point { x: x as usize, y: y as usize })
+    };
+
+    //Which side each exit opens through depends on how the maze wraps around
+    let (entrance_side, exit_side) = match wraparound {
+        Wraparound::None => (Some((-1, 0)), Some((1, 0))),
+        Wraparound::Cylinder => (Some((0, -1)), Some((0, 1))),
+        Wraparound::Torus => (None, None),
+    };
+
+    for (x, column) in cells.iter().enumerate() {
+        for (y, cell) in column.iter().enumerate() {
+            let point = Point { x, y };
+            if !cell.is_enabled() { continue; }
+
+            let sides = [
+                ((0, -1), !cell.top_wall().active),
+                ((0, 1), !cell.bottom_wall().active),
+                ((-1, 0), !cell.left_wall().active),
+                ((1, 0), !cell.right_wall().active),
+            ];
+
+            for (side, open) in sides.iter() {
+                if !leads_out(point, side.0, side.1) { continue; }
+
+                let is_exit = (point == entrance && entrance_side == Some(*side)) || (point == exit && exit_side == Some(*side));
+
+                if is_exit && !open {
+                    violations.push(MazeViolation::ClosedExit(point));
+                }
+                else if !is_exit && *open {
+                    violations.push(MazeViolation::BorderOpening(point));
+                }
+            }
+        }
+    }
+
+    if violations.is_empty() { Ok(()) } else { Err(violations) }
+}
@@ -0,0 +1,105 @@
+use serde::{Serialize, Deserialize};
+
+use super::maze_grid::{Point, Direction};
+
+//What the player knows about a cell when playing with fog of war
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CellView {
+    Hidden,         //Never seen
+    Remembered,     //Seen before, but not in sight now
+    Visible,
+}
+
+//How far the player can see. With line_of_sight, walls that are up block the view.
+//radius is how many cells away can be seen, measured in a straight line between the middles of the cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SightOptions {
+    pub radius: Option<usize>,
+    pub line_of_sight: bool,
+}
+
+impl Default for SightOptions {
+    fn default() -> Self {
+        Self {
+            radius: Some(4),
+            line_of_sight: true,
+        }
+    }
+}
+
+//Every cell of a width x height grid that can be seen from origin, origin included.
+//blocked tells if the wall on the side of a cell in direction is up. It's only asked about walls between two cells of the grid.
+pub fn visible_cells(width: usize, height: usize, origin: Point, options: &SightOptions, blocked: &dyn Fn(Point, Direction) -> bool) -> Vec<Point> {
+    if origin.x >= width || origin.y >= height { return Vec::new(); }
+
+    //Only cells within the radius are looked at, or the whole grid without one
+    let reach = options.radius.unwrap_or(width.max(height));
+    let mut visible = Vec::new();
+
+    for x in origin.x.saturating_sub(reach)..(origin.x + reach + 1).min(width) {
+        for y in origin.y.saturating_sub(reach)..(origin.y + reach + 1).min(height) {
+            let target = Point { x, y };
+            let dx = x.abs_diff(origin.x);
+            let dy = y.abs_diff(origin.y);
+
+            let in_range = options.radius.is_none_or(|radius| dx * dx + dy * dy <= radius * radius);
+            if in_range && (!options.line_of_sight || clear_line(origin, target, blocked)) {
+                visible.push(target);
+            }
+        }
+    }
+
+    visible
+}
+
+//Walks the cells a straight line from the middle of origin to the middle of target passes through,
+//and checks that no wall is up along the way. A line that goes exactly through a corner gets through
+//if either way around the corner is open.
+fn clear_line(origin: Point, target: Point, blocked: &dyn Fn(Point, Direction) -> bool) -> bool {
+    let steps_x = target.x.abs_diff(origin.x);
+    let steps_y = target.y.abs_diff(origin.y);
+    let direction_x = if target.x > origin.x { Direction::East } else { Direction::West };
+    let direction_y = if target.y > origin.y { Direction::South } else { Direction::North };
+
+    let step = |point: Point, direction: Direction| match direction {
+        Direction::North => Point { x: point.x, y: point.y - 1 },
+        Direction::South => Point { x: point.x, y: point.y + 1 },
+        Direction::East => Point { x: point.x + 1, y: point.y },
+        Direction::West => Point { x: point.x - 1, y: point.y },
+    };
+
+    let mut current = origin;
+    let mut taken_x = 0;
+    let mut taken_y = 0;
+
+    while taken_x < steps_x || taken_y < steps_y {
+        //Compares how far along the line the next vertical and horizontal grid lines are crossed
+        let cross_x = (1 + 2 * taken_x) * steps_y;
+        let cross_y = (1 + 2 * taken_y) * steps_x;
+
+        if cross_x == cross_y {
+            let via_x = !blocked(current, direction_x) && !blocked(step(current, direction_x), direction_y);
+            let via_y = !blocked(current, direction_y) && !blocked(step(current, direction_y), direction_x);
+            if !via_x && !via_y { return false; }
+
+            current = step(step(current, direction_x), direction_y);
+            taken_x += 1;
+            taken_y += 1;
+        }
+        else if cross_x < cross_y {
+            if blocked(current, direction_x) { return false; }
+
+            current = step(current, direction_x);
+            taken_x += 1;
+        }
+        else {
+            if blocked(current, direction_y) { return false; }
+
+            current = step(current, direction_y);
+            taken_y += 1;
+        }
+    }
+
+    true
+}
@@ -1,11 +1,28 @@
 pub struct MazeWall {
     pub active: bool,
+    pub locked: bool,       //A locked wall keeps its active state. The algorithms never carve it, and resetting the maze leaves it alone.
+    pub door: Option<usize>,    //A door stands in the passage, opened by the key with this number. Only means anything when the wall is down.
 }
 
 impl MazeWall {
     pub fn new(active: bool) -> Self {
         Self {
             active,
+            locked: false,
+            door: None,
         }
     }
-}
\ No newline at end of file
+
+    //Makes the wall active again, unless it's locked. Any door in it goes with the passage.
+    pub fn reset(&mut self) {
+        if !self.locked {
+            self.active = true;
+        }
+        self.door = None;
+    }
+
+    //Whether the wall can be walked through by someone holding keys
+    pub fn is_passable(&self, keys: &[usize]) -> bool {
+        !self.active && self.door.is_none_or(|door| keys.contains(&door))
+    }
+}
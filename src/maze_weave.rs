@@ -0,0 +1,378 @@
+extern crate rand;
+
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use rand::{Rng, RngCore, SeedableRng};
+use rand::rngs::StdRng;
+
+use super::maze_cell::MazeCell;
+use super::maze_wall::MazeWall;
+use super::maze_algorithm::{MazeAlgorithm, MazeStep, KruskalsAlgorithm};
+pub use super::maze_grid::Axis;
+use super::maze_regenerate::Regenerate;
+use super::maze_grid::{MazeGrid, Point};
+use super::maze_render::{MazeDrawing, Stroke};
+use super::maze_error::{MazeError, check_dimensions};
+
+//A cell where one passage goes over another. The passage on top runs along over and goes through the cell,
+//the one underneath runs across it and joins the two cells on either side through tunnel.
+#[derive(Clone)]
+pub struct Crossing {
+    over: Axis,
+    tunnel: Rc<RefCell<MazeWall>>,
+}
+
+impl Crossing {
+    //Getters
+    pub fn over(&self) -> Axis { self.over }
+    pub fn under(&self) -> Axis { if self.over == Axis::Horizontal { Axis::Vertical } else { Axis::Horizontal } }
+    pub fn tunnel_open(&self) -> bool { !self.tunnel.borrow().active }
+}
+
+//Fraction of the inner cells that crossings are tried on when none is given
+const DEFAULT_DENSITY: f64 = 0.2;
+
+//A square maze where passages can cross under each other. Crossings are placed first,
+//then Kruskal's algorithm fills in the rest of the maze around them.
+pub struct WeaveMaze {
+    cells: Vec<Vec<MazeCell>>,
+    walls: Vec<Rc<RefCell<MazeWall>>>,
+    crossings: Vec<Vec<Option<Crossing>>>,
+    density: f64,
+    seed: u64,
+    width: usize,
+    height: usize,
+}
+
+impl WeaveMaze {
+    //Constructor
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_density(width, height, DEFAULT_DENSITY)
+    }
+
+    //Constructor where density is the fraction of inner cells to try placing a crossing on, from 0 to 1
+    pub fn with_density(width: usize, height: usize, density: f64) -> Self {
+        let mut tmp = Self {
+            cells: Vec::new(),
+            walls: Vec::new(),
+            crossings: Vec::new(),
+            density: density.clamp(0.0, 1.0),
+            seed: 0,
+            width,
+            height,
+        };
+
+        tmp.reconstruct();
+        tmp.regenerate();
+
+        tmp
+    }
+
+    //Constructor that checks the dimensions first. Fails if either is zero or the maze would be too large.
+    pub fn try_new(width: usize, height: usize) -> Result<Self, MazeError> {
+        Self::try_with_density(width, height, DEFAULT_DENSITY)
+    }
+
+    //Same as with_density, but fails on bad dimensions or a density that isn't from 0 to 1 instead of clamping it
+    pub fn try_with_density(width: usize, height: usize, density: f64) -> Result<Self, MazeError> {
+        check_dimensions(&[width, height])?;
+        if !(0.0..=1.0).contains(&density) {
+            return Err(MazeError::InvalidDensity(density));
+        }
+
+        Ok(Self::with_density(width, height, density))
+    }
+
+    //Reconstructs maze with the dimensions width and height. No algorithm is applied on the maze.
+    pub fn reconstruct(&mut self) {
+        let width = self.width;
+        let height = self.height;
+        self.cells = vec![vec![MazeCell::new(); height]; width];
+        self.walls = Vec::new();
+        self.crossings = vec![vec![None; height]; width];
+
+        //Build Walls
+        for x in 0..width {
+            for y in 0..height {
+                let top_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                let left_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+
+                //Set current cell's top and left walls
+                self.cells[x][y].set_top_wall(Rc::clone(&top_wall));
+                self.cells[x][y].set_left_wall(Rc::clone(&left_wall));
+
+                //Set neighboring cell's bottom and right walls
+                if x != 0 {
+                    self.cells[x - 1][y].set_right_wall(Rc::clone(&left_wall));
+                }
+                if y != 0 {
+                    self.cells[x][y - 1].set_bottom_wall(Rc::clone(&top_wall));
+                }
+
+                self.walls.push(top_wall);
+                self.walls.push(left_wall);
+            }
+        }
+
+        //Add walls to the bottom row and right column
+        for column in self.cells.iter_mut() {
+            if let Some(cell) = column.last_mut() {
+                let bottom_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                cell.set_bottom_wall(Rc::clone(&bottom_wall));
+                self.walls.push(bottom_wall);
+            }
+        }
+
+        if let Some(column) = self.cells.last_mut() {
+            for cell in column.iter_mut() {
+                let right_wall = Rc::new(RefCell::new(MazeWall::new(true)));
+                cell.set_right_wall(Rc::clone(&right_wall));
+                self.walls.push(right_wall);
+            }
+        }
+    }
+
+    //Getters
+    pub fn width(&self) -> usize { self.width }
+    pub fn height(&self) -> usize { self.height }
+    pub fn density(&self) -> f64 { self.density }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn cells(&self) -> &Vec<Vec<MazeCell>> { &self.cells }
+    pub fn crossing(&self, point: Point) -> Option<&Crossing> { self.crossings[point.x][point.y].as_ref() }
+
+    //Cells are numbered column by column for the maze algorithms
+    pub fn cell_id(&self, point: Point) -> usize { point.x * self.height + point.y }
+    pub fn point(&self, cell: usize) -> Point { Point { x: cell / self.height, y: cell % self.height } }
+
+    //Tries to place a crossing on random inner cells. A crossing is only placed if neither it nor its four neighbors
+    //are crossings already, and if none of the five cells are connected yet, so the crossings never make a loop.
+    fn place_crossings(&mut self, rng: &mut dyn RngCore) {
+        if self.width < 3 || self.height < 3 { return; }
+
+        let attempts = (self.density * ((self.width - 2) * (self.height - 2)) as f64).round() as usize;
+
+        //Every cell starts out in a set of its own. Crossings join the cells they connect.
+        let mut set_of: Vec<usize> = (0..self.cell_count()).collect();
+
+        for _ in 0..attempts {
+            let x = rng.gen_range(1..(self.width - 1));
+            let y = rng.gen_range(1..(self.height - 1));
+
+            let centre = Point { x, y };
+            let north = Point { x, y: y - 1 };
+            let south = Point { x, y: y + 1 };
+            let west = Point { x: x - 1, y };
+            let east = Point { x: x + 1, y };
+            let points = [centre, north, south, west, east];
+
+            if points.iter().any(|point| self.crossings[point.x][point.y].is_some()) { continue; }
+
+            let sets: Vec<usize> = points.iter().map(|point| set_of[self.cell_id(*point)]).collect();
+            if (1..sets.len()).any(|index| sets[..index].contains(&sets[index])) { continue; }
+
+            let over = if rng.gen::<bool>() { Axis::Horizontal } else { Axis::Vertical };
+            let cell = &self.cells[x][y];
+            let (over_ends, under_ends) = match over {
+                Axis::Vertical => {
+                    cell.top_wall_mut().borrow_mut().active = false;
+                    cell.bottom_wall_mut().borrow_mut().active = false;
+                    ([north, south], [west, east])
+                }
+
+                Axis::Horizontal => {
+                    cell.left_wall_mut().borrow_mut().active = false;
+                    cell.right_wall_mut().borrow_mut().active = false;
+                    ([west, east], [north, south])
+                }
+            };
+
+            self.crossings[x][y] = Some(Crossing { over, tunnel: Rc::new(RefCell::new(MazeWall::new(false))) });
+
+            //Join the sets of the cells on each passage
+            let mut join = |one: Point, two: Point| {
+                let from = set_of[self.cell_id(one)];
+                let into = set_of[self.cell_id(two)];
+                for set in set_of.iter_mut() {
+                    if *set == from { *set = into; }
+                }
+            };
+
+            join(over_ends[0], centre);
+            join(over_ends[1], centre);
+            join(under_ends[0], under_ends[1]);
+        }
+    }
+
+    //Returns whether the side of point facing (dx, dy) leads somewhere, either through an open wall
+    //or through a tunnel under the neighboring cell
+    fn side_open(&self, point: Point, dx: isize, dy: isize) -> bool {
+        let cell = &self.cells[point.x][point.y];
+        let wall_open = match (dx, dy) {
+            (0, -1) => !cell.top_wall().active,
+            (0, 1) => !cell.bottom_wall().active,
+            (-1, 0) => !cell.left_wall().active,
+            _ => !cell.right_wall().active,
+        };
+
+        let neighbor = Point { x: (point.x as isize + dx) as usize, y: (point.y as isize + dy) as usize };
+        let axis = if dx != 0 { Axis::Horizontal } else { Axis::Vertical };
+        let tunnel_open = neighbor.x < self.width && neighbor.y < self.height &&
+            self.crossing(neighbor).is_some_and(|crossing| crossing.under() == axis && crossing.tunnel_open());
+
+        wall_open || tunnel_open
+    }
+}
+
+impl Regenerate for WeaveMaze {
+    fn walls(&self) -> &[Rc<RefCell<MazeWall>>] { &self.walls }
+
+    //Resets all walls in maze to active and removes the crossings. Primarily used to reset a maze for regeneration.
+    fn reset(&mut self) {
+        for wall in self.walls.iter_mut() {
+            wall.borrow_mut().reset();
+        }
+
+        self.crossings = vec![vec![None; self.height]; self.width];
+    }
+
+    //Same as regenerate_with_seed, but reports every step of the algorithm to trace
+    fn regenerate_traced_with_seed(&mut self, seed: u64, trace: &mut dyn FnMut(MazeStep)) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        self.seed = seed;
+        self.reset();
+        self.place_crossings(&mut rng);
+        KruskalsAlgorithm::new().generate_traced(&*self, &mut rng, trace);
+    }
+}
+
+impl MazeGrid for WeaveMaze {
+    fn cell_count(&self) -> usize { self.width * self.height }
+
+    fn is_enabled(&self, _cell: usize) -> bool { true }
+
+    //Neighbors in the same row are side by side, including the ones reached through a tunnel
+    fn axis(&self, cell: usize, neighbor: usize) -> Option<Axis> {
+        if self.point(cell).y == self.point(neighbor).y { Some(Axis::Horizontal) } else { Some(Axis::Vertical) }
+    }
+
+    //Neighbors on the under side of a crossing are reached through its tunnel instead of through the crossing itself
+    fn neighbors(&self, cell: usize) -> Vec<(usize, Rc<RefCell<MazeWall>>)> {
+        let point = self.point(cell);
+        let maze_cell = &self.cells[point.x][point.y];
+        let mut neighbors = Vec::new();
+
+        let sides = [
+            (-1, 0, Axis::Horizontal, maze_cell.left_wall_mut()),
+            (1, 0, Axis::Horizontal, maze_cell.right_wall_mut()),
+            (0, -1, Axis::Vertical, maze_cell.top_wall_mut()),
+            (0, 1, Axis::Vertical, maze_cell.bottom_wall_mut()),
+        ];
+
+        for (dx, dy, axis, wall) in sides.iter() {
+            let neighbor = Point { x: (point.x as isize + dx) as usize, y: (point.y as isize + dy) as usize };
+            if neighbor.x >= self.width || neighbor.y >= self.height { continue; }
+
+            //A crossing only connects to the cells on its over side
+            if self.crossing(point).is_some_and(|crossing| crossing.under() == *axis) { continue; }
+
+            match self.crossing(neighbor) {
+                Some(crossing) if crossing.under() == *axis => {
+                    let beyond = Point { x: (neighbor.x as isize + dx) as usize, y: (neighbor.y as isize + dy) as usize };
+                    neighbors.push((self.cell_id(beyond), Rc::clone(&crossing.tunnel)));
+                }
+
+                _ => neighbors.push((self.cell_id(neighbor), Rc::clone(wall))),
+            }
+        }
+
+        neighbors
+    }
+
+    //The entrance is the top left cell and the exit is the bottom right cell
+    fn exits(&self) -> Option<(usize, usize)> {
+        if self.cell_count() == 0 { return None; }
+        Some((0, self.cell_count() - 1))
+    }
+
+    //Opens the left wall of the entrance and the right wall of the exit. Crossings are never on the border.
+    fn open_exits(&self) {
+        if self.cell_count() == 0 { return; }
+
+        self.cells[0][0].left_wall_mut().borrow_mut().active = false;
+        self.cells[self.width - 1][self.height - 1].right_wall_mut().borrow_mut().active = false;
+    }
+}
+
+//Passages are drawn as corridors narrower than the cells, so the passage under a crossing shows up
+//as a corridor that stops at both sides of the passage on top
+impl MazeDrawing for WeaveMaze {
+    fn extent(&self) -> (f64, f64) { (self.width as f64, self.height as f64) }
+
+    fn wall_strokes(&self) -> Vec<Stroke> {
+        const INSET: f64 = 0.2;
+
+        let mut strokes = Vec::new();
+        let mut line = |from: (f64, f64), to: (f64, f64)| strokes.push(Stroke::Line { from, to });
+
+        for x in 0..self.width {
+            for y in 0..self.height {
+                let point = Point { x, y };
+
+                //Cell edges and the corridor inside the cell
+                let (x0, y0, x1, y1) = (x as f64, y as f64, x as f64 + 1.0, y as f64 + 1.0);
+                let (left, top, right, bottom) = (x0 + INSET, y0 + INSET, x1 - INSET, y1 - INSET);
+
+                match self.crossing(point).map(|crossing| crossing.over()) {
+                    Some(Axis::Vertical) => {
+                        line((left, y0), (left, y1));
+                        line((right, y0), (right, y1));
+                        line((x0, top), (left, top));
+                        line((x0, bottom), (left, bottom));
+                        line((right, top), (x1, top));
+                        line((right, bottom), (x1, bottom));
+                    }
+
+                    Some(Axis::Horizontal) => {
+                        line((x0, top), (x1, top));
+                        line((x0, bottom), (x1, bottom));
+                        line((left, y0), (left, top));
+                        line((right, y0), (right, top));
+                        line((left, bottom), (left, y1));
+                        line((right, bottom), (right, y1));
+                    }
+
+                    //Open sides get the corridor's two side walls, closed sides are a single wall across the corridor
+                    None => {
+                        if self.side_open(point, 0, -1) {
+                            line((left, y0), (left, top));
+                            line((right, y0), (right, top));
+                        }
+                        else { line((left, top), (right, top)); }
+
+                        if self.side_open(point, 0, 1) {
+                            line((left, bottom), (left, y1));
+                            line((right, bottom), (right, y1));
+                        }
+                        else { line((left, bottom), (right, bottom)); }
+
+                        if self.side_open(point, -1, 0) {
+                            line((x0, top), (left, top));
+                            line((x0, bottom), (left, bottom));
+                        }
+                        else { line((left, top), (left, bottom)); }
+
+                        if self.side_open(point, 1, 0) {
+                            line((right, top), (x1, top));
+                            line((right, bottom), (x1, bottom));
+                        }
+                        else { line((right, top), (right, bottom)); }
+                    }
+                }
+            }
+        }
+
+        strokes
+    }
+}
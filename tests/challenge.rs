@@ -0,0 +1,137 @@
+extern crate maze_game;
+extern crate proptest;
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use proptest::prelude::*;
+
+use maze_game::maze_3d::Direction3D;
+use maze_game::maze_challenge::{self, Challenge, ChallengeOptions, LevelResult};
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_leaderboard::{Leaderboard, LeaderboardEntry, MAX_ENTRIES};
+use maze_game::maze_play::MazeGame;
+
+fn options(levels: usize) -> ChallengeOptions {
+    ChallengeOptions { levels, first_size: 3, growth: 1, floor_every: 2, items: false, ..ChallengeOptions::default() }
+}
+
+//Keys that walk the shortest way from the player to the goal
+fn solution(game: &MazeGame) -> String {
+    let maze = game.maze();
+    let start = maze.cell_id(game.player());
+    let goal = maze.cell_id(game.goal());
+    let mut came_from = vec![None; maze.cell_count()];
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        let point = maze.point(current);
+        for direction in Direction3D::ALL.iter() {
+            let next = match maze.neighbor(point, *direction) {
+                Some(next) => maze.cell_id(next),
+                None => continue,
+            };
+
+            if next == start || came_from[next].is_some() || maze.cell(point).wall(*direction).active { continue; }
+            came_from[next] = Some((current, *direction));
+            queue.push_back(next);
+        }
+    }
+
+    let mut keys = Vec::new();
+    let mut cell = goal;
+    while let Some((previous, direction)) = came_from[cell] {
+        keys.push(match direction {
+            Direction3D::North => 'w',
+            Direction3D::South => 's',
+            Direction3D::East => 'd',
+            Direction3D::West => 'a',
+            Direction3D::Up => '<',
+            Direction3D::Down => '>',
+        });
+        cell = previous;
+    }
+
+    keys.iter().rev().collect()
+}
+
+fn entry(name: &str, score: u64) -> LeaderboardEntry {
+    LeaderboardEntry { name: name.to_string(), seed: score, options: ChallengeOptions::default(), score, levels: Vec::new() }
+}
+
+#[test]
+fn perfect_run_scores_every_level() {
+    let seed = 12;
+    let plan = Challenge::new(options(3), seed).unwrap();
+    let input: String = (0..3).map(|index| solution(&plan.level(index)) + "\n").collect();
+
+    //Every line takes a second
+    let mut challenge = Challenge::new(options(3), seed).unwrap();
+    let mut now = Duration::ZERO;
+    let mut output = Vec::new();
+    maze_challenge::run(&mut challenge, input.as_bytes(), &mut output, &mut || { now += Duration::from_secs(1); now }).unwrap();
+
+    assert!(challenge.is_over());
+    assert_eq!(challenge.results().len(), 3);
+    for result in challenge.results() {
+        assert!(result.escaped);
+        assert_eq!(result.moves, result.optimal);
+        assert_eq!(result.seconds, 1);
+        assert_eq!(result.score(), 1000 + 10 * (result.time_limit - 1));
+    }
+    assert_eq!(challenge.score(), challenge.results().iter().map(LevelResult::score).sum::<u64>());
+}
+
+#[test]
+fn running_out_of_time_ends_the_challenge() {
+    let mut challenge = Challenge::new(options(3), 5).unwrap();
+    let input = solution(&challenge.level(0)) + "\n";
+
+    let mut now = Duration::ZERO;
+    let mut output = Vec::new();
+    maze_challenge::run(&mut challenge, input.as_bytes(), &mut output, &mut || { now += Duration::from_secs(60); now }).unwrap();
+
+    assert!(String::from_utf8(output).unwrap().contains("Time's up!"));
+    assert_eq!(challenge.results().len(), 1);
+    assert!(!challenge.results()[0].escaped);
+    assert_eq!(challenge.score(), 0);
+    assert!(challenge.is_over());
+}
+
+#[test]
+fn same_seed_same_levels() {
+    let options = ChallengeOptions { monsters: true, ..ChallengeOptions::default() };
+    let one = Challenge::new(options, 99).unwrap();
+    let two = Challenge::new(options, 99).unwrap();
+
+    for index in 0..options.levels {
+        assert_eq!(one.level(index).to_string(), two.level(index).to_string());
+        assert_eq!(one.level(index).enemies(), two.level(index).enemies());
+    }
+    assert_ne!(one.level_seed(0), one.level_seed(1));
+}
+
+proptest! {
+    //The leaderboard stays sorted and short, and reads back what it wrote
+    #[test]
+    fn leaderboard_keeps_the_best(scores in prop::collection::vec(0..1000u64, 0..40)) {
+        let mut leaderboard = Leaderboard::default();
+        for (index, score) in scores.iter().enumerate() {
+            let place = leaderboard.add(entry(&format!("player {}", index), *score));
+            if let Some(place) = place {
+                prop_assert_eq!(leaderboard.entries[place].score, *score);
+            }
+        }
+
+        prop_assert_eq!(leaderboard.entries.len(), scores.len().min(MAX_ENTRIES));
+        prop_assert!(leaderboard.entries.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        let mut best = scores.clone();
+        best.sort_unstable_by(|one, two| two.cmp(one));
+        let kept: Vec<u64> = leaderboard.entries.iter().map(|entry| entry.score).collect();
+        prop_assert_eq!(&kept[..], &best[..kept.len()]);
+
+        prop_assert_eq!(Leaderboard::from_json(&leaderboard.to_json()).unwrap(), leaderboard);
+    }
+}
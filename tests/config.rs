@@ -0,0 +1,195 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::Maze;
+use maze_game::maze_algorithm::{DepthFirstSearch, KruskalsAlgorithm, MazeAlgorithm, PrimsAlgorithm};
+use maze_game::maze_config::{AlgorithmConfig, Bias, ConfigError, DepthFirstSearchConfig, PrimsConfig, KruskalsConfig, FrontierPolicy};
+use maze_game::maze_distance::DistanceMap;
+use maze_game::maze_grid::Point;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_validator;
+
+#[test]
+fn configs_load_from_toml_and_json() {
+    let toml = "algorithm = \"depth_first_search\"\nstraightness = 0.6\nbias = { horizontal = 2.0, vertical = 1.0 }\n";
+    let json = r#"{ "algorithm": "depth_first_search", "straightness": 0.6, "bias": { "horizontal": 2.0 } }"#;
+
+    let expected = AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig {
+        straightness: 0.6,
+        bias: Bias { horizontal: 2.0, vertical: 1.0 },
+        ..DepthFirstSearchConfig::default()
+    });
+
+    assert_eq!(AlgorithmConfig::from_toml(toml).unwrap(), expected);
+    assert_eq!(AlgorithmConfig::from_json(json).unwrap(), expected);
+    assert_eq!(AlgorithmConfig::from_toml(&expected.to_toml()).unwrap(), expected);
+    assert_eq!(AlgorithmConfig::from_json(&expected.to_json()).unwrap(), expected);
+}
+
+#[test]
+fn bad_configs_are_rejected() {
+    assert!(matches!(AlgorithmConfig::from_toml("algorithm = \"wilsons\""), Err(ConfigError::Parse(_))));
+    assert!(matches!(AlgorithmConfig::from_toml("algorithm = \"kruskals\"\nstart = 3"), Err(ConfigError::Parse(_))));
+    assert!(matches!(AlgorithmConfig::from_json(r#"{ "algorithm": "prims", "frontier": "middle" }"#), Err(ConfigError::Parse(_))));
+    assert!(matches!(AlgorithmConfig::from_toml("algorithm = \"depth_first_search\"\nturn_probability = 1.5"), Err(ConfigError::Invalid(_))));
+    assert!(matches!(AlgorithmConfig::from_toml("algorithm = \"prims\"\nbias = { horizontal = 0.0, vertical = 0.0 }"), Err(ConfigError::Invalid(_))));
+}
+
+//Active state of every wall in the maze, cell by cell, so two mazes can be compared
+fn wall_states(maze: &Maze) -> Vec<[bool; 4]> {
+    maze.cells().iter().flatten().map(|cell| {
+        [cell.top_wall().active, cell.bottom_wall().active, cell.left_wall().active, cell.right_wall().active]
+    }).collect()
+}
+
+fn generate(algorithm: Box<dyn MazeAlgorithm>, seed: u64) -> Maze {
+    let mut maze = Maze::new(12, 9, algorithm);
+    maze.regenerate_with_seed(seed);
+    maze
+}
+
+#[test]
+fn default_configs_match_the_unconfigured_algorithms() {
+    for seed in 0..8 {
+        let unconfigured = vec![
+            (AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig::default()), Box::new(DepthFirstSearch::new()) as Box<dyn MazeAlgorithm>),
+            (AlgorithmConfig::Prims(PrimsConfig::default()), Box::new(PrimsAlgorithm::new())),
+            (AlgorithmConfig::Kruskals(KruskalsConfig::default()), Box::new(KruskalsAlgorithm::new())),
+        ];
+
+        for (config, algorithm) in unconfigured {
+            assert_eq!(AlgorithmConfig::from_toml(&config.to_toml()).unwrap(), config);
+
+            let configured = generate(config.build().unwrap(), seed);
+            assert_eq!(wall_states(&configured), wall_states(&generate(algorithm, seed)), "{:?} {}", config, seed);
+        }
+    }
+}
+
+//Passages and cell shapes added up over mazes generated with several seeds
+#[derive(Default)]
+struct Shape {
+    horizontal: usize,
+    vertical: usize,
+    straight: usize,
+    dead_ends: usize,
+}
+
+impl Shape {
+    //How many of the passages between cells go sideways
+    fn horizontal_ratio(&self) -> f64 { self.horizontal as f64 / (self.horizontal + self.vertical) as f64 }
+}
+
+fn shape(config: AlgorithmConfig) -> Shape {
+    let mut shape = Shape::default();
+
+    for seed in 0..8 {
+        let maze = generate(config.build().unwrap(), seed);
+
+        for (x, column) in maze.cells().iter().enumerate() {
+            for (y, cell) in column.iter().enumerate() {
+                if x + 1 < maze.width() && !cell.right_wall().active { shape.horizontal += 1; }
+                if y + 1 < maze.height() && !cell.bottom_wall().active { shape.vertical += 1; }
+
+                //A straight cell is a corridor running through it from one side to the opposite one
+                let open = [cell.top_wall().active, cell.bottom_wall().active, cell.left_wall().active, cell.right_wall().active].iter().filter(|active| !**active).count();
+                let through = !cell.left_wall().active && !cell.right_wall().active || !cell.top_wall().active && !cell.bottom_wall().active;
+                if open == 2 && through { shape.straight += 1; }
+                if open == 1 { shape.dead_ends += 1; }
+            }
+        }
+    }
+
+    shape
+}
+
+#[test]
+fn bias_favours_passages_along_its_axis() {
+    let sideways = Bias { horizontal: 8.0, vertical: 1.0 };
+    let upright = Bias { horizontal: 1.0, vertical: 8.0 };
+    let configs = |bias: Bias| [
+        AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig { bias, ..DepthFirstSearchConfig::default() }),
+        AlgorithmConfig::Prims(PrimsConfig { bias, ..PrimsConfig::default() }),
+        AlgorithmConfig::Kruskals(KruskalsConfig { bias }),
+    ];
+
+    for config in configs(Bias::default()).iter() {
+        let ratio = shape(*config).horizontal_ratio();
+        assert!(ratio > 0.4 && ratio < 0.6, "{:?} {}", config, ratio);
+    }
+    for config in configs(sideways).iter() {
+        let ratio = shape(*config).horizontal_ratio();
+        assert!(ratio > 0.7, "{:?} {}", config, ratio);
+    }
+    for config in configs(upright).iter() {
+        let ratio = shape(*config).horizontal_ratio();
+        assert!(ratio < 0.3, "{:?} {}", config, ratio);
+    }
+}
+
+#[test]
+fn straightness_makes_longer_corridors() {
+    let plain = shape(AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig::default()));
+    let straight = shape(AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig { straightness: 1.0, ..DepthFirstSearchConfig::default() }));
+
+    assert!(straight.straight > 2 * plain.straight, "{} {}", straight.straight, plain.straight);
+    assert!(straight.dead_ends < plain.dead_ends);
+}
+
+#[test]
+fn turn_probability_makes_winding_corridors() {
+    let plain = shape(AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig::default()));
+    let winding = shape(AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig { turn_probability: 1.0, ..DepthFirstSearchConfig::default() }));
+
+    assert!(2 * winding.straight < plain.straight, "{} {}", winding.straight, plain.straight);
+}
+
+#[test]
+fn newest_frontier_makes_fewer_dead_ends() {
+    let random = shape(AlgorithmConfig::Prims(PrimsConfig::default()));
+    let newest = shape(AlgorithmConfig::Prims(PrimsConfig { frontier: FrontierPolicy::Newest, ..PrimsConfig::default() }));
+
+    assert!(2 * newest.dead_ends < random.dead_ends, "{} {}", newest.dead_ends, random.dead_ends);
+}
+
+#[test]
+fn oldest_frontier_grows_evenly_from_the_start() {
+    //Taking the oldest wall carves the cells in the order a breadth first search reaches them,
+    //so every cell is as few steps from the start along the maze as it is across the grid
+    for seed in 0..8 {
+        let maze = generate(AlgorithmConfig::Prims(PrimsConfig { start: Some(0), frontier: FrontierPolicy::Oldest, ..PrimsConfig::default() }).build().unwrap(), seed);
+        let distances = DistanceMap::new(&maze, 0);
+
+        for x in 0..maze.width() {
+            for y in 0..maze.height() {
+                assert_eq!(distances.distance(maze.cell_id(Point { x, y })), Some(x + y), "{}", seed);
+            }
+        }
+    }
+}
+
+fn configs() -> impl Strategy<Value = AlgorithmConfig> {
+    let bias = (0.0..4.0f64, 0.1..4.0f64).prop_map(|(horizontal, vertical)| Bias { horizontal, vertical });
+    let start = proptest::option::of(0..400usize);
+    let frontier = prop_oneof![Just(FrontierPolicy::Random), Just(FrontierPolicy::Newest), Just(FrontierPolicy::Oldest)];
+
+    prop_oneof![
+        (start.clone(), bias.clone(), 0.0..=1.0f64, 0.0..=1.0f64).prop_map(|(start, bias, straightness, turn_probability)| {
+            AlgorithmConfig::DepthFirstSearch(DepthFirstSearchConfig { start, bias, straightness, turn_probability })
+        }),
+        (start, bias.clone(), frontier).prop_map(|(start, bias, frontier)| AlgorithmConfig::Prims(PrimsConfig { start, bias, frontier })),
+        bias.prop_map(|bias| AlgorithmConfig::Kruskals(KruskalsConfig { bias })),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn every_config_makes_a_valid_maze(config in configs(), width in 1..20usize, height in 1..20usize, seed in any::<u64>()) {
+        let mut maze = Maze::new(width, height, config.build().unwrap());
+        maze.regenerate_with_seed(seed);
+
+        prop_assert_eq!(maze_validator::validate(&maze), Ok(()));
+    }
+}
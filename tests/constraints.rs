@@ -0,0 +1,83 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::{Maze, Wraparound};
+use maze_game::maze_error::MazeError;
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_validator;
+
+//Locked walls of the maze along with the active state they were locked in
+fn locked_walls(maze: &Maze) -> Vec<(usize, usize, bool)> {
+    (0..maze.cell_count())
+        .flat_map(|cell| maze.neighbors(cell).into_iter().map(move |(neighbor, wall)| (cell, neighbor, wall)))
+        .filter(|(_, _, wall)| wall.borrow().locked)
+        .map(|(cell, neighbor, wall)| (cell, neighbor, wall.borrow().active))
+        .collect()
+}
+
+#[test]
+fn impossible_constraints_are_rejected() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut maze = Maze::new(4, 4, registry.create_default("kruskals").unwrap());
+    let point = |x, y| Point { x, y };
+
+    assert!(matches!(maze.force_wall(point(0, 0), point(2, 0)), Err(MazeError::NotNeighbors(_, _))));
+    assert!(matches!(maze.force_passage(point(3, 3), point(4, 3)), Err(MazeError::NotNeighbors(_, _))));
+
+    //Three passages around a square are fine, the fourth closes the loop
+    maze.force_passage(point(0, 0), point(1, 0)).unwrap();
+    maze.force_passage(point(1, 0), point(1, 1)).unwrap();
+    maze.force_passage(point(1, 1), point(0, 1)).unwrap();
+    assert!(matches!(maze.force_passage(point(0, 1), point(0, 0)), Err(MazeError::ForcedLoop(_, _))));
+    assert!(!maze.is_locked(point(0, 1), point(0, 0)));
+
+    //Walling in the corner cell cuts it off
+    maze.force_wall(point(3, 3), point(2, 3)).unwrap();
+    assert!(matches!(maze.force_wall(point(3, 3), point(3, 2)), Err(MazeError::WalledOff(_, _))));
+
+    maze.unlock_wall(point(3, 3), point(2, 3)).unwrap();
+    maze.force_wall(point(3, 3), point(3, 2)).unwrap();
+
+    maze.unlock_all_walls();
+    assert!(locked_walls(&maze).is_empty());
+}
+
+proptest! {
+    //Whatever constraints are accepted, every algorithm keeps them and still makes a perfect maze
+    #[test]
+    fn locked_walls_are_honoured(
+        width in 2..16usize,
+        height in 2..16usize,
+        torus in any::<bool>(),
+        constraints in prop::collection::vec((0..16usize, 0..16usize, any::<bool>(), any::<bool>()), 0..40),
+        seed in any::<u64>(),
+    ) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let wraparound = if torus { Wraparound::Torus } else { Wraparound::None };
+        let mut maze = Maze::with_wraparound(width, height, wraparound, registry.create_default("kruskals").unwrap());
+
+        for (x, y, down, open) in constraints {
+            let one = Point { x: x % width, y: y % height };
+            let two = if down { Point { x: one.x, y: one.y + 1 } } else { Point { x: one.x + 1, y: one.y } };
+
+            let result = if open { maze.force_passage(one, two) } else { maze.force_wall(one, two) };
+            if result.is_ok() {
+                prop_assert!(maze.is_locked(one, two));
+            }
+        }
+
+        let locked = locked_walls(&maze);
+
+        for name in registry.names() {
+            maze.set_algorithm(registry.create_default(name).unwrap());
+            maze.regenerate_with_seed(seed);
+
+            prop_assert!(maze_validator::validate(&maze).is_ok(), "{}: {:?}", name, maze_validator::validate(&maze));
+            prop_assert_eq!(&locked_walls(&maze), &locked);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+extern crate maze_game;
+
+use std::rc::Rc;
+
+use maze_game::maze_delta::DeltaMaze;
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_render::{MazeDrawing, Stroke};
+
+fn delta_maze(width: usize, height: usize) -> DeltaMaze {
+    DeltaMaze::new(width, height, AlgorithmRegistry::with_builtins().create_default("depth_first_search").unwrap())
+}
+
+//Every neighbor of the cell at point, as points, in the order neighbors gives them
+fn neighbor_points(maze: &DeltaMaze, point: Point) -> Vec<Point> {
+    maze.neighbors(maze.cell_id(point)).into_iter().map(|(cell, _)| maze.point(cell)).collect()
+}
+
+fn lines(maze: &DeltaMaze) -> Vec<((f64, f64), (f64, f64))> {
+    maze.wall_strokes().into_iter().map(|stroke| match stroke {
+        Stroke::Line { from, to } => (from, to),
+        other => panic!("triangles have no arcs: {:?}", other),
+    }).collect()
+}
+
+#[test]
+fn upward_triangles_have_a_neighbor_below() {
+    let maze = delta_maze(3, 3);
+
+    assert!(maze.points_up(Point { x: 1, y: 1 }));
+    assert_eq!(neighbor_points(&maze, Point { x: 1, y: 1 }), vec![Point { x: 0, y: 1 }, Point { x: 2, y: 1 }, Point { x: 1, y: 2 }]);
+
+    //On the bottom row the base is on the border
+    assert!(maze.points_up(Point { x: 0, y: 2 }));
+    assert_eq!(neighbor_points(&maze, Point { x: 0, y: 2 }), vec![Point { x: 1, y: 2 }]);
+}
+
+#[test]
+fn downward_triangles_have_a_neighbor_above() {
+    let maze = delta_maze(3, 3);
+
+    assert!(!maze.points_up(Point { x: 2, y: 1 }));
+    assert_eq!(neighbor_points(&maze, Point { x: 2, y: 1 }), vec![Point { x: 1, y: 1 }, Point { x: 2, y: 0 }]);
+
+    //On the top row the base is on the border
+    assert!(!maze.points_up(Point { x: 1, y: 0 }));
+    assert_eq!(neighbor_points(&maze, Point { x: 1, y: 0 }), vec![Point { x: 0, y: 0 }, Point { x: 2, y: 0 }]);
+}
+
+#[test]
+fn neighbors_share_one_wall() {
+    let maze = delta_maze(5, 4);
+
+    for cell in 0..maze.cell_count() {
+        for (neighbor, wall) in maze.neighbors(cell) {
+            let back = maze.neighbors(neighbor).into_iter().find(|(other, _)| *other == cell).map(|(_, wall)| wall);
+            assert!(back.is_some_and(|back| Rc::ptr_eq(&wall, &back)), "{} {}", cell, neighbor);
+        }
+    }
+
+    //A downward triangle's base is the base of the upward one above it, unless it's on the top row
+    let cells = maze.cells();
+    assert!(Rc::ptr_eq(cells[0][1].base_wall_mut(), cells[0][0].base_wall_mut()));
+    assert!(!Rc::ptr_eq(cells[1][0].base_wall_mut(), cells[1][1].base_wall_mut()));
+}
+
+#[test]
+fn every_active_wall_is_one_stroke() {
+    let mut maze = delta_maze(4, 2);
+    maze.reset();
+    maze.open_exits();
+
+    //8 walls between cells, 8 on the border and two of those are the entrance and exit
+    assert_eq!(maze.walls().len(), 16);
+    let strokes = lines(&maze);
+    assert_eq!(strokes.len(), 14);
+
+    //Every side of a triangle is one unit long, and no side is drawn twice
+    let same = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+    for (index, (from, to)) in strokes.iter().enumerate() {
+        assert!(((to.0 - from.0).hypot(to.1 - from.1) - 1.0).abs() < 1e-9);
+        for (other_from, other_to) in &strokes[index + 1..] {
+            assert!(!(same(*from, *other_from) && same(*to, *other_to) || same(*from, *other_to) && same(*to, *other_from)));
+        }
+    }
+
+    //The downward triangle on the top row has its base along the top edge
+    assert!(strokes.iter().any(|(from, to)| same(*from, (0.5, 0.0)) && same(*to, (1.5, 0.0))));
+
+    //Opening the base between two rows takes away one stroke
+    maze.cells()[0][0].base_wall_mut().borrow_mut().active = false;
+    assert_eq!(lines(&maze).len(), 13);
+}
+
+#[test]
+fn entrance_and_exit_are_open() {
+    for seed in 0..8 {
+        let mut maze = delta_maze(4, 3);
+        maze.regenerate_with_seed(seed);
+
+        assert_eq!(maze.exits(), Some((0, 11)));
+        assert!(!maze.cells()[0][0].left_wall().active);
+        assert!(!maze.cells()[3][2].right_wall().active);
+    }
+}
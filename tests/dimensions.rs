@@ -0,0 +1,88 @@
+extern crate maze_game;
+
+use maze_game::maze::Maze;
+use maze_game::maze_algorithm::MazeAlgorithm;
+use maze_game::maze_3d::Maze3D;
+use maze_game::maze_cell::MazeCell;
+use maze_game::maze_delta::DeltaMaze;
+use maze_game::maze_editor::{EditorSession, MazeEditor};
+use maze_game::maze_error::{MazeError, MAX_CELLS};
+use maze_game::maze_file::{FileError, MazeFile};
+use maze_game::maze_grid::Point;
+use maze_game::maze_hex::HexMaze;
+use maze_game::maze_polar::PolarMaze;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_weave::WeaveMaze;
+
+//Any algorithm will do for the shapes that are turned down before anything is generated
+fn algorithm() -> Box<dyn MazeAlgorithm> {
+    AlgorithmRegistry::with_builtins().create_default("depth_first_search").unwrap()
+}
+
+#[test]
+fn zero_sized_mazes_are_rejected() {
+    let registry = AlgorithmRegistry::with_builtins();
+
+    for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+        for name in registry.names() {
+            assert!(matches!(Maze::try_new(width, height, registry.create_default(name).unwrap()), Err(MazeError::ZeroSize)), "{}", name);
+        }
+        assert!(matches!(WeaveMaze::try_new(width, height), Err(MazeError::ZeroSize)));
+    }
+
+    assert!(matches!(PolarMaze::try_new(0, algorithm()), Err(MazeError::ZeroSize)));
+    assert!(matches!(Maze3D::try_new(3, 3, 0, algorithm()), Err(MazeError::ZeroSize)));
+}
+
+#[test]
+fn oversized_mazes_are_rejected() {
+    assert!(matches!(Maze::try_new(MAX_CELLS + 1, 1, algorithm()), Err(MazeError::TooLarge(_))));
+    assert!(matches!(Maze::try_new(usize::MAX, usize::MAX, algorithm()), Err(MazeError::TooLarge(usize::MAX))));
+    assert!(matches!(HexMaze::try_new(MAX_CELLS, 2, algorithm()), Err(MazeError::TooLarge(_))));
+    assert!(matches!(PolarMaze::try_new(MAX_CELLS, algorithm()), Err(MazeError::TooLarge(_))));
+}
+
+#[test]
+fn bad_configurations_are_rejected() {
+    assert!(matches!(DeltaMaze::try_new(1, 3, algorithm()), Err(MazeError::TooNarrow(3))));
+    assert!(matches!(WeaveMaze::try_with_density(5, 5, 1.5), Err(MazeError::InvalidDensity(_))));
+    assert!(matches!(WeaveMaze::try_with_density(5, 5, f64::NAN), Err(MazeError::InvalidDensity(_))));
+}
+
+#[test]
+fn areas_reaching_past_the_end_of_usize_are_outside() {
+    let mut maze = Maze::new(4, 4, algorithm());
+    let result = maze.regenerate_area(usize::MAX, 0, 2, 2, algorithm().as_ref(), 0);
+    assert!(matches!(result, Err(MazeError::OutsideMaze(Point { x: usize::MAX, y: 1 }))));
+    let result = maze.regenerate_area(1, 1, usize::MAX, usize::MAX, algorithm().as_ref(), 0);
+    assert!(matches!(result, Err(MazeError::OutsideMaze(Point { x: usize::MAX, y: usize::MAX }))));
+
+    let mut editor = MazeEditor::new(maze);
+    assert!(matches!(editor.fill(2, usize::MAX, 1, 1, true), Err(MazeError::OutsideMaze(_))));
+    assert!(matches!(editor.fill(0, 0, usize::MAX, 1, false), Err(MazeError::OutsideMaze(_))));
+    assert!(editor.fill(0, 0, 4, 4, true).is_ok());
+}
+
+#[test]
+fn huge_maze_files_are_rejected() {
+    let result = MazeFile::from_text("maze 1 9223372036854775808 none\n", algorithm());
+    assert!(matches!(result, Err(FileError::Maze(MazeError::TooLarge(_)))));
+    let result = MazeFile::from_text("maze 18446744073709551615 18446744073709551615 torus\n", algorithm());
+    assert!(matches!(result, Err(FileError::Maze(MazeError::TooLarge(usize::MAX)))));
+    let result = MazeFile::from_text("maze 0 3 none\n", algorithm());
+    assert!(matches!(result, Err(FileError::Maze(MazeError::ZeroSize))));
+}
+
+#[test]
+fn empty_mazes_are_not_regenerated_in_the_editor() {
+    let mut session = EditorSession::new(MazeFile::new(Maze::new(0, 0, algorithm())));
+    assert!(matches!(session.regenerate(3), Err(MazeError::ZeroSize)));
+}
+
+#[test]
+fn cells_on_their_own_are_closed() {
+    let cell = MazeCell::new();
+
+    assert!(cell.top_wall().active && cell.bottom_wall().active && cell.left_wall().active && cell.right_wall().active);
+    assert!(!std::rc::Rc::ptr_eq(cell.top_wall_mut(), cell.bottom_wall_mut()));
+}
@@ -0,0 +1,93 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::Maze;
+use maze_game::maze_distance::{DistanceMap, Heatmap};
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_mask::MazeMask;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_render::{self, MazeDrawing, RenderOptions};
+use maze_game::maze_solver;
+
+fn maze(width: usize, height: usize, algorithm: usize, seed: u64) -> Maze {
+    let registry = AlgorithmRegistry::with_builtins();
+    let name = registry.names()[algorithm % registry.names().len()];
+    let mut maze = Maze::new(width, height, registry.create_default(name).unwrap());
+    maze.regenerate_with_seed(seed);
+    maze
+}
+
+#[test]
+fn heatmap_runs_from_near_to_far() {
+    let maze = maze(6, 4, 0, 11);
+    let (entrance, _) = maze.exits().unwrap();
+    let distances = DistanceMap::new(&maze, entrance);
+    let heatmap = Heatmap::new(&maze, &distances);
+    let farthest = maze.point(distances.farthest().unwrap());
+
+    assert_eq!(heatmap.colour(maze.point(entrance)), Some(heatmap.near));
+    assert_eq!(heatmap.colour(farthest), Some(heatmap.far));
+    assert_eq!(heatmap.fills().len(), 6 * 4);
+
+    let svg = maze_render::render_svg(&heatmap, &RenderOptions::default());
+    assert!(svg.contains("fill=\"#fff096\""));
+    assert!(svg.contains("fill=\"#aa143c\""));
+    assert!(heatmap.ansi_string().contains("\x1b[48;2;255;240;150m"));
+
+    let mut png = Vec::new();
+    maze_render::render_png(&heatmap, &RenderOptions::default(), &mut png).unwrap();
+    assert_eq!(&png[1..4], b"PNG");
+}
+
+#[test]
+fn cells_cut_off_from_the_source_stay_blank() {
+    //The two halves of the mask only touch at a corner, so each gets a maze of its own
+    let registry = AlgorithmRegistry::with_builtins();
+    let mask = MazeMask::from_ascii("##..\n##..\n..##\n..##\n").unwrap();
+    let maze = Maze::with_mask(mask, registry.create_default("kruskals").unwrap());
+    let (entrance, exit) = maze.exits().unwrap();
+    let distances = DistanceMap::new(&maze, entrance);
+    let heatmap = Heatmap::new(&maze, &distances);
+
+    assert_eq!(distances.distance(exit), None);
+    assert_eq!(distances.distances().iter().flatten().count(), 4);
+    assert_eq!(heatmap.colour(Point { x: 3, y: 3 }), None);
+    assert_eq!(heatmap.fills().len(), 4);
+}
+
+proptest! {
+    //The distance from the entrance to any cell is the length of the breadth first solution between them
+    #[test]
+    fn distances_match_solutions(width in 1..10usize, height in 1..10usize, algorithm in 0..8usize, seed in any::<u64>()) {
+        let maze = maze(width, height, algorithm, seed);
+        let (entrance, exit) = maze.exits().unwrap();
+        let distances = DistanceMap::new(&maze, entrance);
+
+        let solution = maze_solver::solve(&maze).unwrap();
+        prop_assert_eq!(distances.distance(exit), Some(solution.len() - 1));
+        prop_assert_eq!(distances.distance(entrance), Some(0));
+
+        //In a perfect maze the way back is the same length as the way there
+        for cell in 0..maze.cell_count() {
+            prop_assert_eq!(distances.distance(cell), DistanceMap::new(&maze, cell).distance(entrance));
+        }
+    }
+
+    //The largest distance is at the cell farthest says, and nothing is further
+    #[test]
+    fn farthest_cell_has_the_largest_distance(width in 1..10usize, height in 1..10usize, algorithm in 0..8usize, seed in any::<u64>(), source in 0..100usize) {
+        let maze = maze(width, height, algorithm, seed);
+        let distances = DistanceMap::new(&maze, source % maze.cell_count());
+        let max = distances.max_distance().unwrap();
+        let farthest = distances.farthest().unwrap();
+
+        prop_assert_eq!(distances.distance(farthest), Some(max));
+        prop_assert!(distances.distances().iter().all(|distance| distance.is_some_and(|distance| distance <= max)));
+        prop_assert!((0..farthest).all(|cell| distances.distance(cell) != Some(max)));
+        prop_assert_eq!(distances.fraction(farthest), Some(if max == 0 { 0.0 } else { 1.0 }));
+        prop_assert_eq!(distances.fraction(source % maze.cell_count()), Some(0.0));
+    }
+}
@@ -0,0 +1,162 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::{Maze, Wraparound};
+use maze_game::maze_editor::{self, MazeEditor, EditorSession};
+use maze_game::maze_file::MazeFile;
+use maze_game::maze_error::MazeError;
+use maze_game::maze_grid::Point;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_validator;
+
+//Active state of every wall in the maze, cell by cell, so two mazes can be compared
+fn wall_states(maze: &Maze) -> Vec<[bool; 4]> {
+    maze.cells().iter().flatten().map(|cell| {
+        [cell.top_wall().active, cell.bottom_wall().active, cell.left_wall().active, cell.right_wall().active]
+    }).collect()
+}
+
+fn editor(width: usize, height: usize, wraparound: Wraparound) -> MazeEditor {
+    let registry = AlgorithmRegistry::with_builtins();
+    MazeEditor::new(Maze::with_wraparound(width, height, wraparound, registry.create_default("depth_first_search").unwrap()))
+}
+
+#[test]
+fn edits_are_checked() {
+    let mut editor = editor(5, 5, Wraparound::None);
+    let point = |x, y| Point { x, y };
+
+    assert!(matches!(editor.toggle_wall(point(0, 0), point(1, 1)), Err(MazeError::NotNeighbors(_, _))));
+    assert!(matches!(editor.fill(3, 3, 3, 1, false), Err(MazeError::OutsideMaze(_))));
+
+    //A path with a gap in it carves nothing at all
+    let before = wall_states(editor.maze());
+    assert!(editor.carve_path(&[point(0, 0), point(1, 0), point(3, 0)]).is_err());
+    assert_eq!(wall_states(editor.maze()), before);
+    assert!(!editor.can_undo());
+
+    editor.carve_path(&[point(0, 0), point(1, 0), point(1, 1)]).unwrap();
+    assert!(!editor.wall_active(point(1, 0), point(1, 1)).unwrap());
+
+    editor.fill(0, 0, 5, 5, true).unwrap();
+    assert!(editor.wall_active(point(0, 0), point(1, 0)).unwrap());
+
+    let mut maze = editor.into_maze();
+    maze.force_passage(point(2, 2), point(2, 3)).unwrap();
+    let mut editor = MazeEditor::new(maze);
+    assert!(matches!(editor.set_wall(point(2, 3), point(2, 2), true), Err(MazeError::LockedWall(_, _))));
+}
+
+#[test]
+fn session_runs_commands() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let maze = Maze::new(6, 5, registry.create_default("prims").unwrap());
+    let mut session = EditorSession::new(MazeFile::new(maze));
+    let before = wall_states(session.editor().maze());
+
+    //Toggle the wall below (1, 0), then put the start on (3, 2), mark a selection from there to (5, 4) and regenerate it
+    let input = "dS\nddssb\nmddss\nalgorithm kruskals\nn\nalgorithm nonsense\nq\nd\n";
+    let mut output = Vec::new();
+    maze_editor::run(&mut session, "unused.maze", input.as_bytes(), &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert_eq!(session.start(), Point { x: 3, y: 2 });
+    assert_eq!(session.cursor(), Point { x: 5, y: 4 });
+    assert_eq!(session.algorithm_name(), "kruskals");
+    assert!(output.contains("no algorithm is called \"nonsense\""));
+    assert!(output.contains("The goal is"));
+
+    //Cells are stored column by column, so (1, 0) comes after the 5 cells of the first column
+    let maze = session.editor().maze();
+    assert_ne!(maze.cells()[1][0].bottom_wall().active, before[5][1]);
+    assert_eq!(maze.cells()[0][0].left_wall().active, before[0][2]);
+
+    //The toggle and the regeneration are both undone
+    while session.undo() {}
+    assert_eq!(wall_states(session.editor().maze()), before);
+}
+
+#[test]
+fn live_session_acts_on_every_key() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let maze = Maze::new(6, 5, registry.create_default("prims").unwrap());
+    let mut session = EditorSession::new(MazeFile::new(maze));
+    let before = wall_states(session.editor().maze());
+
+    //Right twice with an arrow and a key, down with an arrow, toggle the wall below, then pick an algorithm on the prompt,
+    //with a typo taken back. Nothing after q is read.
+    let input = b"\x1b[Cd\x1b[BS:algorithm kruskalx\x7fs\r:nonsense\rqd";
+    let mut output = Vec::new();
+    maze_editor::run_live(&mut session, "unused.maze", &input[..], &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert_eq!(session.cursor(), Point { x: 2, y: 1 });
+    assert_eq!(session.algorithm_name(), "kruskals");
+    assert_ne!(session.editor().maze().cells()[2][1].bottom_wall().active, before[2 * 5 + 1][1]);
+    assert!(output.contains("Unknown command \"nonsense\"."));
+
+    //The screen is drawn once to start with, then again after each of the six commands before q
+    assert_eq!(output.matches("\x1b[2J\x1b[H").count(), 7);
+}
+
+#[test]
+fn regenerating_is_undone_along_with_the_seed() {
+    let mut editor = editor(6, 5, Wraparound::None);
+    editor.regenerate_with_seed(4);
+    let first = wall_states(editor.maze());
+
+    editor.regenerate_with_seed(9);
+    editor.toggle_wall(Point { x: 0, y: 0 }, Point { x: 1, y: 0 }).unwrap();
+    assert_eq!(editor.maze().seed(), 9);
+
+    //Undoing the toggle keeps the seed, undoing the regeneration puts back the one before it
+    assert!(editor.undo());
+    assert_eq!(editor.maze().seed(), 9);
+    assert!(editor.undo());
+    assert_eq!(editor.maze().seed(), 4);
+    assert_eq!(wall_states(editor.maze()), first);
+
+    assert!(editor.redo());
+    assert_eq!(editor.maze().seed(), 9);
+}
+
+proptest! {
+    //Undoing every edit brings back the maze it started from, and redoing them all brings back the edited maze
+    #[test]
+    fn edits_undo_and_redo(
+        width in 2..10usize,
+        height in 2..10usize,
+        torus in any::<bool>(),
+        edits in prop::collection::vec((0..4usize, 0..10usize, 0..10usize, any::<bool>(), any::<u64>()), 1..20),
+    ) {
+        let wraparound = if torus { Wraparound::Torus } else { Wraparound::None };
+        let mut editor = editor(width, height, wraparound);
+        let start = (wall_states(editor.maze()), editor.maze().seed());
+
+        for (kind, x, y, flag, seed) in edits {
+            let point = Point { x: x % width, y: y % height };
+            let next = if flag { Point { x: (point.x + 1) % width, y: point.y } } else { Point { x: point.x, y: (point.y + 1) % height } };
+
+            let _ = match kind {
+                0 => editor.toggle_wall(point, next),
+                1 => editor.carve_path(&[point, next, Point { x: next.x, y: (next.y + 1) % height }]),
+                2 => editor.fill(point.x, point.y, (width - point.x).min(3), (height - point.y).min(3), flag),
+                _ => {
+                    editor.regenerate_with_seed(seed);
+                    prop_assert!(maze_validator::validate(editor.maze()).is_ok());
+                    Ok(())
+                }
+            };
+        }
+
+        let end = (wall_states(editor.maze()), editor.maze().seed());
+
+        while editor.undo() {}
+        prop_assert_eq!((wall_states(editor.maze()), editor.maze().seed()), start);
+
+        while editor.redo() {}
+        prop_assert_eq!((wall_states(editor.maze()), editor.maze().seed()), end);
+    }
+}
@@ -0,0 +1,105 @@
+extern crate maze_game;
+extern crate proptest;
+extern crate rand;
+
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use maze_game::maze_3d::{Maze3D, Point3D, Direction3D};
+use maze_game::maze_enemy::{self, Enemy, Behaviour};
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_play::MazeGame;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+
+fn maze(width: usize, height: usize, floors: usize, seed: u64) -> Maze3D {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut maze = Maze3D::new(width, height, floors, registry.create_default("depth_first_search").unwrap());
+    maze.regenerate_with_seed(seed);
+    maze
+}
+
+fn behaviours(maze: &Maze3D) -> Vec<Behaviour> {
+    let corner = Point3D { x: maze.width() - 1, y: 0, z: 0 };
+    vec![Behaviour::RandomWalker, Behaviour::WallFollower, Behaviour::Pursuer, Behaviour::Patrol(vec![corner, maze.point(0)])]
+}
+
+#[test]
+fn pursuer_catches_a_waiting_player() {
+    let mut game = MazeGame::new(maze(8, 8, 2, 7));
+    let goal = game.goal();
+    game.add_enemy(Enemy::new(Behaviour::Pursuer, goal));
+
+    let mut turns = 0;
+    while !game.is_lost() && turns < 128 {
+        game.wait();
+        turns += 1;
+    }
+
+    assert!(game.is_lost());
+    assert!(!game.try_move(Direction3D::East));
+    assert!(!game.try_move(Direction3D::South));
+}
+
+proptest! {
+    //Two games with the same maze seed and the same moves play out the same
+    #[test]
+    fn enemies_replay_from_the_seed(width in 3..10usize, height in 3..10usize, seed in any::<u64>(), moves in prop::collection::vec(0..7usize, 0..40)) {
+        let mut games: Vec<MazeGame> = (0..2).map(|_| {
+            let maze = maze(width, height, 1, seed);
+            let behaviours = behaviours(&maze);
+            let mut game = MazeGame::new(maze);
+            game.spawn_enemies(&behaviours);
+            game
+        }).collect();
+
+        for choice in moves {
+            for game in games.iter_mut() {
+                match Direction3D::ALL.get(choice) {
+                    Some(direction) => { game.try_move(*direction); }
+                    None => game.wait(),
+                }
+            }
+
+            prop_assert_eq!(games[0].enemies(), games[1].enemies());
+            prop_assert_eq!(games[0].player(), games[1].player());
+            prop_assert_eq!(games[0].is_lost(), games[1].is_lost());
+        }
+    }
+
+    //Monsters only ever step through open sides, and a wall follower gets everywhere in a perfect maze of one floor.
+    //With more floors, a floor can be split into parts only joined by stairs.
+    #[test]
+    fn enemies_respect_walls(width in 2..10usize, height in 2..10usize, floors in 1..3usize, seed in any::<u64>()) {
+        let maze = maze(width, height, floors, seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let player = maze.point(maze.cell_count() - 1);
+
+        let mut enemies: Vec<Enemy> = behaviours(&maze).into_iter().map(|behaviour| Enemy::new(behaviour, maze.point(0))).collect();
+        let mut followed = vec![false; width * height];
+        followed[0] = true;
+
+        for _ in 0..2 * width * height {
+            for enemy in enemies.iter_mut() {
+                let before = enemy.position();
+                enemy.take_turn(&maze, player, &mut rng);
+                let after = enemy.position();
+
+                let stepped = Direction3D::ALL.iter().any(|direction| {
+                    maze.neighbor(before, *direction) == Some(after) && maze_enemy::can_move(&maze, before, *direction)
+                });
+                prop_assert!(before == after || stepped, "{:?} jumped from {:?} to {:?}", enemy.behaviour(), before, after);
+
+                if *enemy.behaviour() == Behaviour::WallFollower {
+                    prop_assert_eq!(after.z, 0);
+                    followed[maze.cell_id(after)] = true;
+                }
+            }
+        }
+
+        if floors == 1 {
+            prop_assert!(followed.iter().all(|cell| *cell));
+        }
+    }
+}
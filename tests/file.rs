@@ -0,0 +1,78 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::{Maze, Wraparound};
+use maze_game::maze_algorithm::DepthFirstSearch;
+use maze_game::maze_file::{MazeFile, FileError};
+use maze_game::maze_grid::Point;
+use maze_game::maze_mask::MazeMask;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+
+//Active and locked state of every wall in the maze, cell by cell, so two mazes can be compared
+fn wall_states(maze: &Maze) -> Vec<[(bool, bool); 4]> {
+    maze.cells().iter().flatten().map(|cell| {
+        [cell.top_wall(), cell.bottom_wall(), cell.left_wall(), cell.right_wall()].map(|wall| (wall.active, wall.locked))
+    }).collect()
+}
+
+#[test]
+fn bad_files_are_rejected() {
+    let read = |text: &str| MazeFile::from_text(text, Box::new(DepthFirstSearch::new()));
+
+    assert!(matches!(read("labyrinth 2 2 none\n"), Err(FileError::Parse(1, _))));
+    assert!(matches!(read("maze 2 2 sphere\n"), Err(FileError::Parse(1, _))));
+    assert!(matches!(read("maze 2 2 none\n+#+#+\n"), Err(FileError::Parse(_, _))));
+    assert!(matches!(read("maze 0 2 none\n+\n+\n+\n+\n+\n"), Err(FileError::Maze(_))));
+    assert!(matches!(read("maze 1 1 none\n+#+\n#?#\n+#+\n"), Err(FileError::Parse(3, _))));
+
+    //Trailing spaces can be left out, and the start and goal default to the exits
+    let file = read("maze 2 1 none\n+#+#+\n     \n+#+#+\n").unwrap();
+    assert_eq!((file.start, file.goal), (Point { x: 0, y: 0 }, Point { x: 1, y: 0 }));
+    assert!(!file.maze.cells()[0][0].right_wall().active);
+}
+
+proptest! {
+    //Writing a maze out and reading it back gives the same walls, locks, mask, start and goal
+    #[test]
+    fn files_round_trip(
+        width in 2..12usize,
+        height in 2..12usize,
+        wraparound in prop::sample::select(vec![Wraparound::None, Wraparound::Cylinder, Wraparound::Torus]),
+        holes in prop::collection::vec((0..12usize, 0..12usize), 0..4),
+        locks in prop::collection::vec((0..12usize, 0..12usize, any::<bool>()), 0..6),
+        seed in any::<u64>(),
+    ) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let mut maze = Maze::with_wraparound(width, height, wraparound, registry.create_default("prims").unwrap());
+
+        if wraparound == Wraparound::None {
+            let mut mask = MazeMask::new(width, height);
+            for (x, y) in holes {
+                if (x % width, y % height) != (0, 0) {
+                    mask.set_enabled(x % width, y % height, false);
+                }
+            }
+            maze.set_mask(Some(mask));
+        }
+
+        for (x, y, open) in locks {
+            let one = Point { x: x % width, y: y % height };
+            let two = Point { x: (one.x + 1) % width, y: one.y };
+            let _ = if open { maze.force_passage(one, two) } else { maze.force_wall(one, two) };
+        }
+
+        maze.regenerate_with_seed(seed);
+
+        let mut file = MazeFile::new(maze);
+        file.start = Point { x: 0, y: 0 };
+
+        let read = MazeFile::from_text(&file.to_text(), Box::new(DepthFirstSearch::new())).unwrap();
+
+        prop_assert_eq!(read.to_text(), file.to_text());
+        prop_assert_eq!(wall_states(&read.maze), wall_states(&file.maze));
+        prop_assert_eq!((read.start, read.goal), (file.start, file.goal));
+    }
+}
@@ -0,0 +1,83 @@
+extern crate maze_game;
+
+use std::rc::Rc;
+
+use maze_game::maze_3d::{Direction3D, Maze3D, Point3D};
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_visibility::CellView;
+
+fn maze_3d(width: usize, height: usize, depth: usize) -> Maze3D {
+    Maze3D::new(width, height, depth, AlgorithmRegistry::with_builtins().create_default("depth_first_search").unwrap())
+}
+
+//A maze with every wall up except the entrance and exit
+fn closed_maze(width: usize, height: usize, depth: usize) -> Maze3D {
+    let mut maze = maze_3d(width, height, depth);
+    maze.reset();
+    maze.open_exits();
+    maze
+}
+
+#[test]
+fn floors_are_stacked_up_and_down() {
+    let maze = maze_3d(2, 2, 3);
+    let middle = Point3D { x: 1, y: 0, z: 1 };
+
+    assert_eq!(maze.neighbor(middle, Direction3D::Up), Some(Point3D { x: 1, y: 0, z: 2 }));
+    assert_eq!(maze.neighbor(middle, Direction3D::Down), Some(Point3D { x: 1, y: 0, z: 0 }));
+    assert_eq!(maze.neighbor(Point3D { x: 0, y: 0, z: 0 }, Direction3D::Down), None);
+    assert_eq!(maze.neighbor(Point3D { x: 0, y: 0, z: 2 }, Direction3D::Up), None);
+
+    //The ceiling of one floor is the floor of the next
+    let above = Point3D { x: 1, y: 0, z: 2 };
+    assert!(Rc::ptr_eq(maze.cell(middle).wall_mut(Direction3D::Up), maze.cell(above).wall_mut(Direction3D::Down)));
+    assert_eq!(maze.cell_id(above), 10);
+    assert_eq!(maze.point(10), above);
+}
+
+#[test]
+fn stairs_join_every_floor_to_the_next() {
+    for seed in 0..16 {
+        let mut maze = maze_3d(3, 3, 4);
+        maze.regenerate_with_seed(seed);
+
+        for z in 0..4 {
+            let floor: Vec<Point3D> = (0..3).flat_map(|x| (0..3).map(move |y| Point3D { x, y, z })).collect();
+
+            //Stairs always lead somewhere, so none go down from the bottom floor or up from the top one
+            assert_eq!(z == 0, floor.iter().all(|point| !maze.cell(*point).has_stairs(Direction3D::Down)), "{}", seed);
+            assert_eq!(z == 3, floor.iter().all(|point| !maze.cell(*point).has_stairs(Direction3D::Up)), "{}", seed);
+
+            for point in floor {
+                if maze.cell(point).has_stairs(Direction3D::Up) {
+                    assert!(maze.cell(Point3D { z: z + 1, ..point }).has_stairs(Direction3D::Down));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn stairs_are_marked_on_both_floors() {
+    let maze = closed_maze(2, 1, 3);
+    maze.cell(Point3D { x: 0, y: 0, z: 0 }).wall_mut(Direction3D::Up).borrow_mut().active = false;
+    maze.cell(Point3D { x: 0, y: 0, z: 1 }).wall_mut(Direction3D::Up).borrow_mut().active = false;
+    maze.cell(Point3D { x: 1, y: 0, z: 1 }).wall_mut(Direction3D::Up).borrow_mut().active = false;
+
+    assert_eq!(maze.floor_string(0), "██████████\n  /\\██  ██\n██████████\n");
+    assert_eq!(maze.floor_string(1), "██████████\n██<>██/\\██\n██████████\n");
+    assert_eq!(maze.floor_string(2), "██████████\n██\\/██\\/  \n██████████\n");
+}
+
+#[test]
+fn missing_floors_are_drawn_empty() {
+    let maze = maze_3d(3, 2, 2);
+
+    assert_eq!(maze.floor_string(2), "");
+    assert_eq!(maze.floor_string(usize::MAX), "");
+    assert_eq!(maze.floor_string_with(5, &|_| Some("@@".to_string())), "");
+    assert_eq!(maze.floor_string_fogged(2, &|_| CellView::Visible, &|_| None), "");
+    assert_ne!(maze.floor_string(1), "");
+}
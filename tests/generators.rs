@@ -0,0 +1,125 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::{Maze, Wraparound};
+use maze_game::maze_algorithm::MazeAlgorithm;
+use maze_game::maze_3d::Maze3D;
+use maze_game::maze_delta::DeltaMaze;
+use maze_game::maze_hex::HexMaze;
+use maze_game::maze_polar::PolarMaze;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_validator;
+use maze_game::maze_weave::WeaveMaze;
+
+//Every registered algorithm with its default parameters, in the order they were registered, with its name so a failure says which one it was.
+//New algorithms are tested as soon as they're registered.
+fn all_algorithms() -> Vec<(String, Box<dyn MazeAlgorithm>)> {
+    let registry = AlgorithmRegistry::with_builtins();
+    registry.names().iter().map(|name| (name.to_string(), registry.create_default(name).unwrap())).collect()
+}
+
+//Active state of every wall in the maze, cell by cell, so two mazes can be compared
+fn wall_states(maze: &Maze) -> Vec<[bool; 4]> {
+    maze.cells().iter().flatten().map(|cell| {
+        [cell.top_wall().active, cell.bottom_wall().active, cell.left_wall().active, cell.right_wall().active]
+    }).collect()
+}
+
+//Sizes from a single cell up, with 1xN and Nx1 mazes picked often enough to always be covered
+fn sizes() -> impl Strategy<Value = (usize, usize)> {
+    prop_oneof![
+        (1..20usize, 1..20usize),
+        (1..20usize).prop_map(|length| (1, length)),
+        (1..20usize).prop_map(|length| (length, 1)),
+    ]
+}
+
+fn wraparounds() -> impl Strategy<Value = Wraparound> {
+    prop_oneof![Just(Wraparound::None), Just(Wraparound::Cylinder), Just(Wraparound::Torus)]
+}
+
+proptest! {
+    //The validator checks that the maze is a spanning tree with shared walls and that exactly the exits are open
+    #[test]
+    fn every_algorithm_makes_a_valid_maze((width, height) in sizes(), seed in any::<u64>()) {
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = Maze::new(width, height, algorithm);
+            maze.regenerate_with_seed(seed);
+
+            prop_assert_eq!(maze_validator::validate(&maze), Ok(()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn every_algorithm_makes_a_valid_wrapped_maze((width, height) in sizes(), wraparound in wraparounds(), seed in any::<u64>()) {
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = Maze::with_wraparound(width, height, wraparound, algorithm);
+            maze.regenerate_with_seed(seed);
+
+            prop_assert_eq!(maze_validator::validate(&maze), Ok(()), "{}", name);
+        }
+    }
+
+    #[test]
+    fn every_algorithm_is_reproducible_from_its_seed((width, height) in sizes(), seed in any::<u64>()) {
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = Maze::new(width, height, algorithm);
+            maze.regenerate_with_seed(seed);
+            let first = wall_states(&maze);
+
+            maze.regenerate_with_seed(seed.wrapping_add(1));
+            maze.regenerate_with_seed(seed);
+
+            prop_assert_eq!(maze.seed(), seed, "{}", name);
+            prop_assert_eq!(wall_states(&maze), first, "{}", name);
+        }
+    }
+
+    #[test]
+    fn every_algorithm_opens_both_exits((width, height) in sizes(), seed in any::<u64>()) {
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = Maze::new(width, height, algorithm);
+            maze.regenerate_with_seed(seed);
+
+            let cells = maze.cells();
+            prop_assert!(!cells[0][0].left_wall().active, "{}", name);
+            prop_assert!(!cells[width - 1][height - 1].right_wall().active, "{}", name);
+        }
+    }
+
+    //The other shapes only have the checks that work on any grid
+    #[test]
+    fn every_algorithm_makes_a_valid_maze_of_every_shape((width, height) in sizes(), depth in 1..4usize, seed in any::<u64>()) {
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = HexMaze::new(width, height, algorithm);
+            maze.regenerate_with_seed(seed);
+            prop_assert_eq!(maze_validator::validate_grid(&maze), Ok(()), "{}", name);
+        }
+
+        //A delta maze one triangle wide can't be connected, as the triangles only touch through their bases
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = DeltaMaze::new(width.max(2), height, algorithm);
+            maze.regenerate_with_seed(seed);
+            prop_assert_eq!(maze_validator::validate_grid(&maze), Ok(()), "{}", name);
+        }
+
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = PolarMaze::new(height, algorithm);
+            maze.regenerate_with_seed(seed);
+            prop_assert_eq!(maze_validator::validate_grid(&maze), Ok(()), "{}", name);
+        }
+
+        for (name, algorithm) in all_algorithms() {
+            let mut maze = Maze3D::new(width, height, depth, algorithm);
+            maze.regenerate_with_seed(seed);
+            prop_assert_eq!(maze_validator::validate_grid(&maze), Ok(()), "{}", name);
+        }
+
+        let mut maze = WeaveMaze::new(width, height);
+        maze.regenerate_with_seed(seed);
+        prop_assert_eq!(maze_validator::validate_grid(&maze), Ok(()));
+    }
+}
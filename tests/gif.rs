@@ -0,0 +1,162 @@
+extern crate gif;
+extern crate maze_game;
+
+use maze_game::maze::Maze;
+use maze_game::maze_algorithm::{DepthFirstSearch, KruskalsAlgorithm, PrimsAlgorithm};
+use maze_game::maze_gif::{self, Colour, GifOptions};
+use maze_game::maze_grid::Point;
+use maze_game::maze_solver;
+
+//Width, height, global palette and the delay of every frame of a GIF
+fn decode(bytes: &[u8]) -> (u16, u16, Vec<u8>, Vec<u16>) {
+    let mut decoder = gif::DecodeOptions::new().read_info(bytes).unwrap();
+    let palette = decoder.global_palette().unwrap().to_vec();
+
+    let mut delays = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        delays.push(frame.delay);
+    }
+
+    (decoder.width(), decoder.height(), palette, delays)
+}
+
+//Every frame of a GIF as RGBA pixels, with the width of the image
+fn frames(bytes: &[u8]) -> (usize, Vec<Vec<u8>>) {
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options.read_info(bytes).unwrap();
+
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        frames.push(frame.buffer.to_vec());
+    }
+
+    (decoder.width() as usize, frames)
+}
+
+//Colour of a block of a frame exported with a scale of 2, in the text renderer's layout where cell (x, y) is block (2x + 1, 2y + 1)
+fn block(frame: &[u8], width: usize, block_x: usize, block_y: usize) -> Colour {
+    let start = (block_y * 2 * width + block_x * 2) * 4;
+    [frame[start], frame[start + 1], frame[start + 2]]
+}
+
+#[test]
+fn generation_frames_show_visited_frontier_and_carved_cells() {
+    let mut maze = Maze::new(2, 1, Box::new(PrimsAlgorithm::new()));
+
+    let options = GifOptions { scale: 2, ..GifOptions::default() };
+    let mut bytes = Vec::new();
+    maze_gif::export_generation(&mut maze, &options, &mut bytes).unwrap();
+
+    //Prim's algorithm on two cells visits one of them, lists the other as frontier, carves through to it and visits it
+    let (width, frames) = frames(&bytes);
+    assert_eq!(frames.len(), 6);
+    let cells = |frame: &[u8]| [block(frame, width, 1, 1), block(frame, width, 3, 1)];
+    let passage = |frame: &[u8]| block(frame, width, 2, 1);
+
+    assert_eq!(cells(&frames[0]), [options.background; 2]);
+    assert_eq!(passage(&frames[0]), options.wall);
+
+    let start = cells(&frames[1]).iter().position(|colour| *colour == options.visited).unwrap();
+    let other = 1 - start;
+    assert_eq!(cells(&frames[1])[other], options.background);
+
+    assert_eq!((cells(&frames[2])[start], cells(&frames[2])[other]), (options.visited, options.frontier));
+    assert_eq!(passage(&frames[2]), options.wall);
+
+    //The cell being carved into stays on the frontier until it's visited, and the passage takes the colour of the less advanced cell
+    assert_eq!((cells(&frames[3])[start], cells(&frames[3])[other]), (options.carved, options.frontier));
+    assert_eq!(passage(&frames[3]), options.carved);
+
+    assert_eq!(cells(&frames[4]), [options.carved; 2]);
+    assert_eq!(passage(&frames[4]), options.carved);
+    assert_eq!(frames[5], frames[4]);
+}
+
+#[test]
+fn solve_frames_show_the_search_and_the_path() {
+    let maze = Maze::new(3, 1, Box::new(DepthFirstSearch::new()));
+
+    let options = GifOptions { scale: 2, ..GifOptions::default() };
+    let mut bytes = Vec::new();
+    maze_gif::export_solve(&maze, &options, &mut bytes).unwrap();
+
+    //A single corridor is searched one cell at a time, each one listed as frontier and then visited, and then all of it is the path
+    let (width, frames) = frames(&bytes);
+    assert_eq!(frames.len(), 11);
+    let cells = |frame: &[u8]| [block(frame, width, 1, 1), block(frame, width, 3, 1), block(frame, width, 5, 1)];
+    let passages = |frame: &[u8]| [block(frame, width, 2, 1), block(frame, width, 4, 1)];
+
+    assert_eq!(cells(&frames[0]), [options.background; 3]);
+    assert_eq!(cells(&frames[1]), [options.frontier, options.background, options.background]);
+    assert_eq!(cells(&frames[2]), [options.visited, options.background, options.background]);
+    assert_eq!(cells(&frames[3]), [options.visited, options.frontier, options.background]);
+    assert_eq!(passages(&frames[3]), [options.visited, options.background]);
+    assert_eq!(cells(&frames[6]), [options.visited; 3]);
+    assert_eq!(cells(&frames[7]), [options.path, options.visited, options.visited]);
+    assert_eq!(cells(&frames[10]), [options.path; 3]);
+    assert_eq!(passages(&frames[10]), [options.path; 2]);
+}
+
+#[test]
+fn only_the_solution_is_drawn_as_the_path() {
+    let maze = Maze::new(4, 4, Box::new(KruskalsAlgorithm::new()));
+
+    let options = GifOptions { scale: 2, ..GifOptions::default() };
+    let mut bytes = Vec::new();
+    maze_gif::export_solve(&maze, &options, &mut bytes).unwrap();
+
+    let (width, frames) = frames(&bytes);
+    let solution: Vec<Point> = maze_solver::solve(&maze).unwrap().into_iter().map(|cell| maze.point(cell)).collect();
+    let last = frames.last().unwrap();
+
+    for x in 0..4 {
+        for y in 0..4 {
+            let on_path = solution.contains(&Point { x, y });
+            assert_eq!(block(last, width, 2 * x + 1, 2 * y + 1) == options.path, on_path, "{} {}", x, y);
+        }
+    }
+}
+
+#[test]
+fn solve_has_a_frame_per_step() {
+    let maze = Maze::new(4, 3, Box::new(DepthFirstSearch::new()));
+
+    let mut steps = 0;
+    maze_solver::solve_traced(&maze, &mut |_| steps += 1);
+
+    let options = GifOptions { scale: 2, frame_delay: 3, final_delay: 50, ..GifOptions::default() };
+    let mut bytes = Vec::new();
+    maze_gif::export_solve(&maze, &options, &mut bytes).unwrap();
+    assert_eq!(&bytes[..6], b"GIF89a");
+
+    //The maze before the first step, one frame per step, then the finished maze held on screen
+    let (width, height, palette, delays) = decode(&bytes);
+    assert_eq!((width, height), (9 * 2, 7 * 2));
+    assert_eq!(&palette[..6], &[255, 255, 255, 0, 0, 0]);
+    assert_eq!(delays.len(), steps + 2);
+    assert!(delays[..steps + 1].iter().all(|delay| *delay == 3));
+    assert_eq!(delays.last(), Some(&50));
+}
+
+#[test]
+fn steps_are_grouped_into_frames() {
+    let mut maze = Maze::new(3, 3, Box::new(KruskalsAlgorithm::new()));
+
+    let options = GifOptions { steps_per_frame: 1000, wall: [10, 20, 30], ..GifOptions::default() };
+    let mut bytes = Vec::new();
+    maze_gif::export_generation(&mut maze, &options, &mut bytes).unwrap();
+    assert_eq!(&bytes[..6], b"GIF89a");
+
+    let (width, height, palette, delays) = decode(&bytes);
+    assert_eq!((width, height), (7 * 8, 7 * 8));
+    assert_eq!(&palette[3..6], &[10, 20, 30]);
+    assert_eq!(delays.len(), 3);
+}
+
+#[test]
+fn empty_maze_is_not_exported() {
+    let maze = Maze::new(0, 3, Box::new(KruskalsAlgorithm::new()));
+
+    assert!(maze_gif::export_solve(&maze, &GifOptions::default(), Vec::new()).is_err());
+}
@@ -0,0 +1,150 @@
+extern crate maze_game;
+
+use std::rc::Rc;
+
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_hex::{HexDirection, HexMaze};
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_render::{MazeDrawing, Stroke};
+
+fn hex_maze(width: usize, height: usize) -> HexMaze {
+    HexMaze::new(width, height, AlgorithmRegistry::with_builtins().create_default("depth_first_search").unwrap())
+}
+
+//A maze with every wall up except the entrance and exit
+fn closed_maze(width: usize, height: usize) -> HexMaze {
+    let mut maze = hex_maze(width, height);
+    maze.reset();
+    maze.open_exits();
+    maze
+}
+
+fn neighbors(maze: &HexMaze, point: Point) -> Vec<Option<Point>> {
+    HexDirection::ALL.iter().map(|direction| maze.neighbor(point, *direction)).collect()
+}
+
+#[test]
+fn neighbors_of_an_even_column() {
+    let maze = hex_maze(3, 3);
+
+    //North, north east, south east, south, south west, north west
+    assert_eq!(neighbors(&maze, Point { x: 0, y: 1 }), vec![
+        Some(Point { x: 0, y: 0 }),
+        Some(Point { x: 1, y: 0 }),
+        Some(Point { x: 1, y: 1 }),
+        Some(Point { x: 0, y: 2 }),
+        None,
+        None,
+    ]);
+    assert_eq!(neighbors(&maze, Point { x: 2, y: 0 }), vec![
+        None,
+        None,
+        None,
+        Some(Point { x: 2, y: 1 }),
+        Some(Point { x: 1, y: 0 }),
+        None,
+    ]);
+}
+
+#[test]
+fn neighbors_of_an_odd_column() {
+    let maze = hex_maze(3, 3);
+
+    //Odd columns sit half a cell lower, so their diagonals reach one row further down than in an even column
+    assert_eq!(neighbors(&maze, Point { x: 1, y: 1 }), vec![
+        Some(Point { x: 1, y: 0 }),
+        Some(Point { x: 2, y: 1 }),
+        Some(Point { x: 2, y: 2 }),
+        Some(Point { x: 1, y: 2 }),
+        Some(Point { x: 0, y: 2 }),
+        Some(Point { x: 0, y: 1 }),
+    ]);
+    assert_eq!(neighbors(&maze, Point { x: 1, y: 2 }), vec![
+        Some(Point { x: 1, y: 1 }),
+        Some(Point { x: 2, y: 2 }),
+        None,
+        None,
+        None,
+        Some(Point { x: 0, y: 2 }),
+    ]);
+}
+
+#[test]
+fn neighbors_share_one_wall() {
+    let maze = hex_maze(4, 3);
+
+    for x in 0..4 {
+        for y in 0..3 {
+            for direction in HexDirection::ALL.iter() {
+                if let Some(neighbor) = maze.neighbor(Point { x, y }, *direction) {
+                    let wall = maze.cells()[x][y].wall_mut(*direction);
+                    let shared = maze.cells()[neighbor.x][neighbor.y].wall_mut(direction.opposite());
+                    assert!(Rc::ptr_eq(wall, shared), "({}, {}) {:?}", x, y, direction);
+                }
+            }
+        }
+    }
+
+    //23 walls between cells, and 26 sides on the border
+    assert_eq!(maze.walls().len(), 49);
+}
+
+#[test]
+fn closed_maze_is_drawn() {
+    let maze = closed_maze(2, 2);
+
+    let expected = concat!(
+        " __\n",
+        "   \\__\n",
+        "\\__/  \\\n",
+        "/  \\__/\n",
+        "\\__/  \\\n",
+        "   \\__\n",
+    );
+    assert_eq!(maze.to_string(), expected);
+}
+
+#[test]
+fn every_active_wall_is_one_stroke() {
+    let maze = closed_maze(3, 2);
+    let active = maze.walls().iter().filter(|wall| wall.borrow().active).count();
+    let strokes = maze.wall_strokes();
+
+    //Every side of a hexagon is one unit long, and no side is drawn twice
+    assert_eq!(strokes.len(), active);
+    for (index, stroke) in strokes.iter().enumerate() {
+        let (from, to) = match stroke {
+            Stroke::Line { from, to } => (*from, *to),
+            other => panic!("hexagons have no arcs: {:?}", other),
+        };
+        assert!(((to.0 - from.0).hypot(to.1 - from.1) - 1.0).abs() < 1e-9);
+
+        for other in &strokes[index + 1..] {
+            if let Stroke::Line { from: other_from, to: other_to } = other {
+                let same = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+                assert!(!(same(from, *other_from) && same(to, *other_to) || same(from, *other_to) && same(to, *other_from)));
+            }
+        }
+    }
+
+    //Opening a wall between two cells takes away exactly one stroke
+    maze.cells()[0][0].wall_mut(HexDirection::South).borrow_mut().active = false;
+    assert_eq!(maze.wall_strokes().len(), active - 1);
+}
+
+#[test]
+fn entrance_and_exit_are_open() {
+    for seed in 0..8 {
+        let mut maze = hex_maze(4, 3);
+        maze.regenerate_with_seed(seed);
+
+        //Both sides are on the border
+        assert_eq!(maze.neighbor(Point { x: 0, y: 0 }, HexDirection::NorthWest), None);
+        assert_eq!(maze.neighbor(Point { x: 3, y: 2 }, HexDirection::SouthEast), None);
+
+        assert!(!maze.cells()[0][0].wall(HexDirection::NorthWest).active);
+        assert!(!maze.cells()[3][2].wall(HexDirection::SouthEast).active);
+        assert_eq!(maze.exits(), Some((0, 11)));
+    }
+}
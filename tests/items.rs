@@ -0,0 +1,152 @@
+extern crate maze_game;
+extern crate proptest;
+extern crate rand;
+
+use std::collections::VecDeque;
+
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use maze_game::maze::Maze;
+use maze_game::maze_3d::{Maze3D, Direction3D};
+use maze_game::maze_error::MazeError;
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_items::{self, Item, ItemOptions};
+use maze_game::maze_play::MazeGame;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+
+//Directions to walk from the player to target, going only through walls the player can pass.
+//The goal is gone round, as reaching it ends the game.
+fn route(game: &MazeGame, target: usize) -> Option<Vec<Direction3D>> {
+    let maze = game.maze();
+    let start = maze.cell_id(game.player());
+    let goal = maze.cell_id(game.goal());
+    let mut came_from = vec![None; maze.cell_count()];
+    let mut queue = VecDeque::new();
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        if current == target {
+            let mut directions = Vec::new();
+            let mut cell = current;
+            while let Some((previous, direction)) = came_from[cell] {
+                directions.push(direction);
+                cell = previous;
+            }
+            directions.reverse();
+            return Some(directions);
+        }
+
+        let point = maze.point(current);
+        for direction in Direction3D::ALL.iter() {
+            let next = match maze.neighbor(point, *direction) {
+                Some(next) => maze.cell_id(next),
+                None => continue,
+            };
+
+            if next == start || (next == goal && next != target) || came_from[next].is_some() || !maze.cell(point).wall(*direction).is_passable(game.keys()) { continue; }
+            came_from[next] = Some((current, *direction));
+            queue.push_back(next);
+        }
+    }
+
+    None
+}
+
+#[test]
+fn items_need_room() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut game = MazeGame::new(Maze3D::new(2, 2, 1, registry.create_default("kruskals").unwrap()));
+
+    assert!(matches!(game.place_items(&ItemOptions { coins: 0, doors: 3 }, 1), Err(MazeError::NoRoomForItems)));
+    assert!(matches!(game.place_items(&ItemOptions { coins: 3, doors: 0 }, 1), Err(MazeError::NoRoomForItems)));
+}
+
+#[test]
+fn coins_are_not_put_past_the_goal() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut dead_ends = Vec::new();
+
+    //Two coins only fit in a 2x2 maze when there's nothing past the goal for them to go to
+    for seed in 0..16 {
+        let mut maze = Maze3D::new(2, 2, 1, registry.create_default("kruskals").unwrap());
+        maze.regenerate_with_seed(seed);
+        let goal = maze.cell_count() - 1;
+        let dead_end = maze.neighbors(goal).iter().filter(|(_, wall)| !wall.borrow().active).count() == 1;
+
+        let mut game = MazeGame::new(maze);
+        let placed = game.place_items(&ItemOptions { coins: 2, doors: 0 }, seed);
+        assert_eq!(placed.is_ok(), dead_end);
+        if !dead_end {
+            assert!(matches!(placed, Err(MazeError::NoRoomForItems)));
+        }
+        dead_ends.push(dead_end);
+    }
+
+    assert!(dead_ends.contains(&true) && dead_ends.contains(&false));
+}
+
+proptest! {
+    //Every key can be reached with the keys before it, and the goal only with all of them
+    #[test]
+    fn keys_come_before_their_doors(width in 2..16usize, height in 2..16usize, coins in 0..5usize, doors in 0..4usize, seed in any::<u64>()) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let mut maze = Maze::new(width, height, registry.create_default("prims").unwrap());
+        maze.regenerate_with_seed(seed);
+        let (start, goal) = maze.exits().unwrap();
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let layout = match maze_items::place_items(&maze, start, goal, &ItemOptions { coins, doors }, &mut rng) {
+            Ok(layout) => layout,
+            Err(_) => return Ok(()),
+        };
+
+        prop_assert_eq!(layout.doors.len(), doors);
+        prop_assert_eq!(layout.items.len(), coins + doors);
+        prop_assert!(layout.items.iter().all(|(cell, _)| *cell != start && *cell != goal));
+
+        for (cell, item) in &layout.items {
+            if let Item::Key(key) = item {
+                let keys: Vec<usize> = (0..*key).collect();
+                prop_assert!(maze_items::reachable(&maze, start, &keys)[*cell]);
+            }
+        }
+
+        let keys: Vec<usize> = (0..doors).collect();
+        prop_assert!(maze_items::reachable(&maze, start, &keys)[goal]);
+        if doors > 0 {
+            prop_assert!(!maze_items::reachable(&maze, start, &keys[..doors - 1])[goal]);
+        }
+    }
+
+    //A player who fetches the keys in order, then the coins, wins the game with everything collected
+    #[test]
+    fn game_can_be_won_with_every_item(width in 3..10usize, height in 3..10usize, floors in 1..3usize, seed in any::<u64>()) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let mut maze = Maze3D::new(width, height, floors, registry.create_default("depth_first_search").unwrap());
+        maze.regenerate_with_seed(seed);
+
+        let mut game = MazeGame::new(maze);
+        let options = ItemOptions { coins: 2, doors: 1 };
+        if game.place_items(&options, seed).is_err() { return Ok(()); }
+
+        let cells = game.maze().cell_count();
+        let key = (0..cells).find(|cell| game.item(game.maze().point(*cell)) == Some(Item::Key(0))).unwrap();
+        let coins: Vec<usize> = (0..cells).filter(|cell| game.item(game.maze().point(*cell)) == Some(Item::Coin)).collect();
+        let goal = game.maze().cell_id(game.goal());
+
+        prop_assert!(route(&game, goal).is_none());
+
+        for target in std::iter::once(key).chain(coins).chain(std::iter::once(goal)) {
+            for direction in route(&game, target).unwrap() {
+                prop_assert!(game.try_move(direction));
+            }
+        }
+
+        prop_assert!(game.is_won());
+        prop_assert_eq!(game.keys(), &[0][..]);
+        prop_assert_eq!(game.coins(), options.coins);
+    }
+}
@@ -0,0 +1,116 @@
+extern crate maze_game;
+
+use std::fs;
+
+use maze_game::maze::Maze;
+use maze_game::maze_error::MazeError;
+use maze_game::maze_mask::{MaskError, MazeMask};
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_validator;
+
+//Every row of mask as a string, with '#' for enabled cells and '.' for disabled ones
+fn rows(mask: &MazeMask) -> Vec<String> {
+    (0..mask.height()).map(|y| (0..mask.width()).map(|x| if mask.is_enabled(x, y) { '#' } else { '.' }).collect()).collect()
+}
+
+fn invalid_image(result: Result<MazeMask, MaskError>) -> bool {
+    matches!(result, Err(MaskError::InvalidImage(_)))
+}
+
+#[test]
+fn ascii_masks() {
+    let mask = MazeMask::from_ascii("#X#\r\n. o\n##\n\n  \n").unwrap();
+
+    //Short lines are padded, and blank lines at the end are dropped
+    assert_eq!((mask.width(), mask.height()), (3, 3));
+    assert_eq!(rows(&mask), vec!["###", "..#", "##."]);
+
+    assert!(matches!(MazeMask::from_ascii(""), Err(MaskError::Empty)));
+    assert!(matches!(MazeMask::from_ascii(". .\n...\n"), Err(MaskError::Empty)));
+}
+
+#[test]
+fn plain_pbm_masks() {
+    let mask = MazeMask::from_pbm(b"P1\n# a comment\n3 2\n1 0 1\n011").unwrap();
+    assert_eq!(rows(&mask), vec!["#.#", ".##"]);
+
+    //Whitespace between pixels is optional
+    let mask = MazeMask::from_pbm(b"P1 2 2 1001").unwrap();
+    assert_eq!(rows(&mask), vec!["#.", ".#"]);
+
+    assert!(invalid_image(MazeMask::from_pbm(b"P1\n2 2\n1 0 1")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P1\n2 2\n1 0 2 1")));
+    assert!(matches!(MazeMask::from_pbm(b"P1\n2 1\n0 0"), Err(MaskError::Empty)));
+}
+
+#[test]
+fn raw_pbm_masks() {
+    //Ten pixels to a row take two bytes, the last six bits of the second one being padding
+    let mask = MazeMask::from_pbm(b"P4\n10 2\n\xa5\x40\x00\xff").unwrap();
+    assert_eq!(rows(&mask), vec!["#.#..#.#.#", "........##"]);
+
+    //Set padding bits are ignored
+    let padded = MazeMask::from_pbm(b"P4\n10 2\n\xa5\x7f\x00\xff").unwrap();
+    assert_eq!(padded, mask);
+
+    //Rows packed without padding leave the image a byte short
+    assert!(invalid_image(MazeMask::from_pbm(b"P4\n10 2\n\xa5\x40\x3f")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P4\n10 2\n")));
+}
+
+#[test]
+fn broken_pbm_headers() {
+    assert!(invalid_image(MazeMask::from_pbm(b"P2\n2 2\n1 1 1 1")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P1\n2")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P1\n0 2\n")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P1\ntwo 2\n1 1 1 1")));
+    assert!(invalid_image(MazeMask::from_pbm(b"# only a comment")));
+}
+
+#[test]
+fn huge_pbm_headers_are_turned_down() {
+    //Far too many pixels for a maze, with hardly any data behind them
+    assert!(invalid_image(MazeMask::from_pbm(b"P4 4000000000 4000000000\n\xff")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P1 18446744073709551615 2\n1")));
+    assert!(invalid_image(MazeMask::from_pbm(b"P1 1025 1024\n1")));
+
+    //The largest allowed image still needs all of its data
+    assert!(invalid_image(MazeMask::from_pbm(b"P4 1024 1024\n\xff\xff")));
+    let mut full = b"P4 1024 1024\n".to_vec();
+    full.extend(vec![0xff; 128 * 1024]);
+    assert_eq!(MazeMask::from_pbm(&full).unwrap().width(), 1024);
+}
+
+#[test]
+fn masks_load_by_content() {
+    //Named after the process, so test runs going at the same time don't write over each other's files
+    let directory = std::env::temp_dir();
+    let ascii = directory.join(format!("maze_game_mask_test_{}.txt", std::process::id()));
+    let pbm = directory.join(format!("maze_game_mask_test_{}.pbm", std::process::id()));
+    fs::write(&ascii, "##\n#.\n").unwrap();
+    fs::write(&pbm, b"P4 2 2\n\xc0\x80").unwrap();
+
+    assert_eq!(MazeMask::load(&ascii).unwrap(), MazeMask::load(&pbm).unwrap());
+    assert!(matches!(MazeMask::load(directory.join("maze_game_no_such_mask.txt")), Err(MaskError::Io(_))));
+
+    fs::remove_file(ascii).unwrap();
+    fs::remove_file(pbm).unwrap();
+}
+
+#[test]
+fn disconnected_masks_are_turned_down() {
+    let registry = AlgorithmRegistry::with_builtins();
+
+    //Cells that only touch at a corner aren't connected
+    let mask = MazeMask::from_ascii("##.\n##.\n..#\n").unwrap();
+    assert_eq!(mask.unreachable_cell(), Some((2, 2)));
+    let result = Maze::try_with_mask(mask, registry.create_default("prims").unwrap());
+    assert!(matches!(result, Err(MazeError::Mask(MaskError::Disconnected(2, 2)))));
+
+    let mask = MazeMask::from_ascii("##.\n.#.\n.##\n").unwrap();
+    assert_eq!(mask.unreachable_cell(), None);
+    for name in registry.names() {
+        let maze = Maze::try_with_mask(mask.clone(), registry.create_default(name).unwrap()).unwrap();
+        assert_eq!(maze_validator::validate(&maze), Ok(()), "{}", name);
+    }
+}
@@ -0,0 +1,133 @@
+extern crate maze_game;
+
+use std::f64::consts::PI;
+use std::rc::Rc;
+
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_polar::PolarMaze;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_render::{MazeDrawing, Stroke};
+
+fn polar_maze(rings: usize) -> PolarMaze {
+    PolarMaze::new(rings, AlgorithmRegistry::with_builtins().create_default("depth_first_search").unwrap())
+}
+
+//Every neighbor of the cell at point, as points, in the order neighbors gives them
+fn neighbor_points(maze: &PolarMaze, point: Point) -> Vec<Point> {
+    maze.neighbors(maze.cell_id(point)).into_iter().map(|(cell, _)| maze.point(cell)).collect()
+}
+
+#[test]
+fn rings_are_split_into_more_cells_further_out() {
+    let maze = polar_maze(5);
+    let sizes: Vec<usize> = maze.cells().iter().map(|ring| ring.len()).collect();
+
+    assert_eq!(sizes, vec![1, 6, 12, 24, 24]);
+    assert_eq!(maze.cell_count(), 67);
+
+    //Cells are numbered ring by ring from the centre
+    assert_eq!(maze.cell_id(Point { x: 0, y: 2 }), 7);
+    assert_eq!(maze.point(30), Point { x: 11, y: 3 });
+}
+
+#[test]
+fn centre_cell_only_has_outward_neighbors() {
+    let maze = polar_maze(3);
+    let centre = &maze.cells()[0][0];
+
+    assert!(centre.inward_wall().is_none());
+    assert!(centre.clockwise_wall().is_none());
+    assert!(centre.counter_clockwise_wall().is_none());
+    assert_eq!(neighbor_points(&maze, Point { x: 0, y: 0 }), (0..6).map(|x| Point { x, y: 1 }).collect::<Vec<_>>());
+}
+
+#[test]
+fn neighbors_inward_outward_and_around() {
+    let maze = polar_maze(3);
+
+    //Inward, clockwise, counter clockwise, then the two cells of the next ring it's split into
+    assert_eq!(neighbor_points(&maze, Point { x: 0, y: 1 }), vec![
+        Point { x: 0, y: 0 },
+        Point { x: 1, y: 1 },
+        Point { x: 5, y: 1 },
+        Point { x: 0, y: 2 },
+        Point { x: 1, y: 2 },
+    ]);
+
+    //The outermost ring has nothing further out, and wraps around at the end of the ring
+    assert_eq!(neighbor_points(&maze, Point { x: 11, y: 2 }), vec![
+        Point { x: 5, y: 1 },
+        Point { x: 0, y: 2 },
+        Point { x: 10, y: 2 },
+    ]);
+}
+
+#[test]
+fn neighbors_share_one_wall() {
+    let maze = polar_maze(4);
+
+    for cell in 0..maze.cell_count() {
+        for (neighbor, wall) in maze.neighbors(cell) {
+            let back = maze.neighbors(neighbor).into_iter().find(|(other, _)| *other == cell).map(|(_, wall)| wall);
+            assert!(back.is_some_and(|back| Rc::ptr_eq(&wall, &back)), "{} {}", cell, neighbor);
+        }
+    }
+}
+
+#[test]
+fn walls_are_drawn_as_arcs_and_lines() {
+    let mut maze = polar_maze(3);
+    maze.reset();
+    maze.open_exits();
+
+    let strokes = maze.wall_strokes();
+    let arcs: Vec<(f64, f64, f64)> = strokes.iter().filter_map(|stroke| match stroke {
+        Stroke::Arc { centre, radius, start, end } => {
+            assert_eq!(*centre, (3.0, 3.0));
+            Some((*radius, *start, *end))
+        }
+        Stroke::Line { .. } => None,
+    }).collect();
+
+    //The inward walls of the two outer rings, and the border less the entrance
+    assert_eq!(arcs.iter().filter(|arc| arc.0 == 1.0).count(), 6);
+    assert_eq!(arcs.iter().filter(|arc| arc.0 == 2.0).count(), 12);
+    assert_eq!(arcs.iter().filter(|arc| arc.0 == 3.0).count(), 11);
+    for (_, start, end) in &arcs {
+        assert!(*end > *start);
+    }
+
+    //The entrance is the first cell of the outer ring
+    assert!(!arcs.iter().any(|arc| arc.0 == 3.0 && arc.1 == 0.0));
+
+    //Walls between cells of a ring are lines one ring long, pointing away from the centre
+    let lines: Vec<((f64, f64), (f64, f64))> = strokes.iter().filter_map(|stroke| match stroke {
+        Stroke::Line { from, to } => Some((*from, *to)),
+        Stroke::Arc { .. } => None,
+    }).collect();
+    assert_eq!(lines.len(), 18);
+    for (from, to) in lines {
+        let inner = (from.0 - 3.0).hypot(from.1 - 3.0);
+        let outer = (to.0 - 3.0).hypot(to.1 - 3.0);
+        assert!((outer - inner - 1.0).abs() < 1e-9);
+
+        let angle = |point: (f64, f64)| (point.1 - 3.0).atan2(point.0 - 3.0);
+        assert!((angle(from) - angle(to)).abs() < 1e-9);
+    }
+
+    //Each cell of the first ring covers a sixth of the circle
+    assert!(arcs.iter().filter(|arc| arc.0 == 1.0).all(|arc| (arc.2 - arc.1 - PI / 3.0).abs() < 1e-9));
+}
+
+#[test]
+fn entrance_is_open_and_exit_is_the_centre() {
+    for seed in 0..8 {
+        let mut maze = polar_maze(4);
+        maze.regenerate_with_seed(seed);
+
+        assert_eq!(maze.exits(), Some((19, 0)));
+        assert!(!maze.cells()[3][0].outward_walls_mut()[0].borrow().active);
+        assert!(maze.cells()[3][1..].iter().all(|cell| cell.outward_walls_mut()[0].borrow().active));
+    }
+}
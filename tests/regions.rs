@@ -0,0 +1,102 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::Maze;
+use maze_game::maze_error::MazeError;
+use maze_game::maze_grid::Point;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_region::{Region, RegionLayout};
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_validator::{self, MazeViolation};
+
+//Splits a width x height maze into four quadrants, with the algorithms picked by index from the registry
+fn quadrants(width: usize, height: usize, picks: [usize; 4], extra_connections: usize) -> RegionLayout {
+    let registry = AlgorithmRegistry::with_builtins();
+    let names = registry.names();
+    let algorithm = |pick: usize| registry.create_default(names[pick % names.len()]).unwrap();
+
+    let half_width = width / 2;
+    let half_height = height / 2;
+
+    RegionLayout {
+        regions: vec![
+            Region::new(0, 0, half_width, half_height, algorithm(picks[0])),
+            Region::new(half_width, 0, width - half_width, half_height, algorithm(picks[1])),
+            Region::new(0, half_height, half_width, height - half_height, algorithm(picks[2])),
+            Region::new(half_width, half_height, width - half_width, height - half_height, algorithm(picks[3])),
+        ],
+        extra_connections,
+    }
+}
+
+//Active state of every wall of the cells outside region, so the rest of the maze can be compared
+fn walls_outside(maze: &Maze, region: usize) -> Vec<[bool; 4]> {
+    let mut states = Vec::new();
+
+    for (x, column) in maze.cells().iter().enumerate() {
+        for (y, cell) in column.iter().enumerate() {
+            let point = Point { x, y };
+            let touches = [(0, 1), (2, 1), (1, 0), (1, 2)].iter().any(|(dx, dy)| {
+                let next = Point { x: (x + dx).wrapping_sub(1), y: (y + dy).wrapping_sub(1) };
+                maze.region_at(next) == Some(region)
+            });
+
+            if maze.region_at(point) != Some(region) && !touches {
+                states.push([cell.top_wall().active, cell.bottom_wall().active, cell.left_wall().active, cell.right_wall().active]);
+            }
+        }
+    }
+
+    states
+}
+
+#[test]
+fn regions_must_fit() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut maze = Maze::new(10, 10, registry.create_default("prims").unwrap());
+    let algorithm = || registry.create_default("kruskals").unwrap();
+
+    let outside = RegionLayout { regions: vec![Region::new(5, 5, 6, 2, algorithm())], extra_connections: 0 };
+    assert!(matches!(maze.set_region_layout(Some(outside)), Err(MazeError::InvalidRegion(0))));
+
+    let overlapping = RegionLayout { regions: vec![Region::new(0, 0, 5, 5, algorithm()), Region::new(4, 4, 2, 2, algorithm())], extra_connections: 0 };
+    assert!(matches!(maze.set_region_layout(Some(overlapping)), Err(MazeError::InvalidRegion(1))));
+
+    assert!(matches!(maze.regenerate_region(0, 1), Err(MazeError::NoSuchRegion(0))));
+}
+
+proptest! {
+    //Stitched regions make a perfect maze, and every extra connection only adds loops
+    #[test]
+    fn stitched_regions_stay_connected(width in 2..24usize, height in 2..24usize, picks in any::<[usize; 4]>(), extra_connections in 0..3usize, seed in any::<u64>()) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let mut maze = Maze::new(width, height, registry.create_default("depth_first_search").unwrap());
+        maze.set_region_layout(Some(quadrants(width, height, picks, extra_connections))).unwrap();
+        maze.regenerate_with_seed(seed);
+
+        let violations = maze_validator::validate(&maze).err().unwrap_or_default();
+        if extra_connections == 0 {
+            prop_assert!(violations.is_empty(), "{:?}", violations);
+        }
+        else {
+            prop_assert!(violations.iter().all(|violation| matches!(violation, MazeViolation::Cycle(_, _))), "{:?}", violations);
+        }
+    }
+
+    //Carving one region again leaves the rest of the maze alone and keeps it perfect
+    #[test]
+    fn regions_regenerate_on_their_own(width in 2..24usize, height in 2..24usize, picks in any::<[usize; 4]>(), region in 0..4usize, seed in any::<u64>()) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let mut maze = Maze::new(width, height, registry.create_default("kruskals").unwrap());
+        maze.set_region_layout(Some(quadrants(width, height, picks, 0))).unwrap();
+        maze.regenerate_with_seed(seed);
+
+        let before = walls_outside(&maze, region);
+        maze.regenerate_region(region, seed.wrapping_add(1)).unwrap();
+
+        prop_assert_eq!(walls_outside(&maze, region), before);
+        prop_assert!(maze_validator::validate(&maze).is_ok(), "{:?}", maze_validator::validate(&maze));
+    }
+}
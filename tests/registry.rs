@@ -0,0 +1,44 @@
+extern crate maze_game;
+
+use maze_game::maze::Maze;
+use maze_game::maze_algorithm::{MazeAlgorithm, DepthFirstSearch};
+use maze_game::maze_registry::{AlgorithmRegistry, AlgorithmInfo, ParamSchema, ParamKind, ParamValue, Params, RegistryError};
+use maze_game::maze_validator;
+
+#[test]
+fn builtins_are_listed_in_order() {
+    let registry = AlgorithmRegistry::with_builtins();
+    assert_eq!(registry.names(), vec!["depth_first_search", "prims", "kruskals"]);
+}
+
+#[test]
+fn unknown_names_are_rejected() {
+    let registry = AlgorithmRegistry::with_builtins();
+    assert_eq!(registry.create_default("aldous_broder").err(), Some(RegistryError::UnknownAlgorithm("aldous_broder".to_string())));
+}
+
+#[test]
+fn downstream_algorithms_can_be_registered_with_params() {
+    let mut registry = AlgorithmRegistry::with_builtins();
+    let info = AlgorithmInfo::new("custom", "Depth first search under another name")
+        .with_param(ParamSchema::new("passes", "Unused", ParamKind::Integer { min: 1, max: 4 }, ParamValue::Integer(1)));
+
+    registry.register(info, Box::new(|params: &Params| {
+        assert_eq!(params.get("passes"), Some(&ParamValue::Integer(1)));
+        Ok(Box::new(DepthFirstSearch::new()) as Box<dyn MazeAlgorithm>)
+    })).unwrap();
+
+    let maze = Maze::new(6, 6, registry.create_default("custom").unwrap());
+    assert_eq!(maze_validator::validate(&maze), Ok(()));
+
+    let duplicate = registry.register(AlgorithmInfo::new("prims", ""), Box::new(|_: &Params| Ok(Box::new(DepthFirstSearch::new()) as Box<dyn MazeAlgorithm>)));
+    assert_eq!(duplicate, Err(RegistryError::DuplicateName("prims".to_string())));
+
+    let mut params = Params::new();
+    params.insert("passes".to_string(), ParamValue::Integer(9));
+    assert!(matches!(registry.create("custom", &params), Err(RegistryError::InvalidParam(_, _))));
+
+    params.clear();
+    params.insert("speed".to_string(), ParamValue::Boolean(true));
+    assert_eq!(registry.create("custom", &params).err(), Some(RegistryError::UnknownParam("custom".to_string(), "speed".to_string())));
+}
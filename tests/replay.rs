@@ -0,0 +1,85 @@
+extern crate maze_game;
+extern crate proptest;
+
+use std::time::Duration;
+
+use proptest::prelude::*;
+
+use maze_game::maze_enemy::Behaviour;
+use maze_game::maze_items::ItemOptions;
+use maze_game::maze_registry::{AlgorithmRegistry, RegistryError};
+use maze_game::maze_replay::{self, GameSetup, Replay, ReplayError, ReplayInput};
+use maze_game::maze_visibility::SightOptions;
+
+#[test]
+fn replay_needs_a_known_algorithm() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let replay = Replay::new(GameSetup::new(5, 5, 1, "nonsense", 3));
+
+    let result = maze_replay::play_back(&replay, &registry, Vec::new(), 0.0, &mut |_| {});
+    assert!(matches!(result, Err(ReplayError::Registry(RegistryError::UnknownAlgorithm(_)))));
+    assert!(matches!(Replay::from_json("{\"setup\": 1}"), Err(ReplayError::Parse(_))));
+}
+
+#[test]
+fn long_pauses_are_cut_down() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut replay = Replay::new(GameSetup::new(3, 3, 1, "depth_first_search", 3));
+    replay.inputs.push(ReplayInput { millis: 1000, line: String::new() });
+    replay.inputs.push(ReplayInput { millis: u64::MAX, line: String::new() });
+
+    //Slowed down so far the pauses don't fit in a Duration
+    let mut pauses = Vec::new();
+    maze_replay::play_back(&replay, &registry, Vec::new(), 1e-300, &mut |pause| pauses.push(pause)).unwrap();
+    assert_eq!(pauses, vec![Duration::MAX, Duration::MAX]);
+
+    //The longest gap there can be still fits when it isn't slowed down
+    let mut pauses = Vec::new();
+    maze_replay::play_back(&replay, &registry, Vec::new(), 1.0, &mut |pause| pauses.push(pause)).unwrap();
+    assert_eq!(pauses[0], Duration::from_secs(1));
+    assert!(pauses[1] > Duration::from_secs(u64::MAX / 1001));
+}
+
+proptest! {
+    //A recorded game played back, even after going through JSON, ends up exactly where it was left,
+    //with the pauses between lines scaled by the speed
+    #[test]
+    fn playback_matches_the_recording(
+        width in 2..8usize,
+        height in 2..8usize,
+        floors in 1..3usize,
+        seed in any::<u64>(),
+        extras in any::<[bool; 3]>(),
+        algorithm in 0..3usize,
+        lines in prop::collection::vec("[wasd<>.x]{0,6}", 0..12),
+    ) {
+        let registry = AlgorithmRegistry::with_builtins();
+        let mut setup = GameSetup::new(width, height, floors, registry.names()[algorithm % registry.names().len()], seed);
+        if extras[0] { setup.sight = Some(SightOptions { radius: Some(2), line_of_sight: true }); }
+        if extras[1] { setup.items = Some(ItemOptions { coins: 1, doors: 1 }); }
+        if extras[2] { setup.monsters = vec![Behaviour::RandomWalker, Behaviour::Pursuer]; }
+
+        let mut game = match setup.build(&registry) {
+            Ok(game) => game,
+            Err(_) => return Ok(()),
+        };
+
+        //Every line takes the player a quarter of a second
+        let mut replay = Replay::new(setup);
+        let input = lines.join("\n");
+        let mut now = Duration::ZERO;
+        maze_replay::record(&mut replay, &mut game, input.as_bytes(), Vec::new(), &mut || { now += Duration::from_millis(250); now }).unwrap();
+
+        let replay = Replay::from_json(&replay.to_json()).unwrap();
+        let mut paused = Duration::ZERO;
+        let played = maze_replay::play_back(&replay, &registry, Vec::new(), 2.0, &mut |pause| paused += pause).unwrap();
+
+        prop_assert_eq!(played.to_string(), game.to_string());
+        prop_assert_eq!(played.player(), game.player());
+        prop_assert_eq!(played.moves(), game.moves());
+        prop_assert_eq!(played.enemies(), game.enemies());
+        prop_assert_eq!(played.keys(), game.keys());
+        prop_assert_eq!((played.is_won(), played.is_lost()), (game.is_won(), game.is_lost()));
+        prop_assert_eq!(paused, Duration::from_millis(replay.inputs.last().map_or(0, |input| input.millis)) / 2);
+    }
+}
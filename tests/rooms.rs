@@ -0,0 +1,55 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::Maze;
+use maze_game::maze_error::MazeError;
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_room::{Room, RoomLayout};
+use maze_game::maze_validator::{self, MazeViolation};
+
+//Open walls between a room and the cells around it
+fn doors(maze: &Maze, room: usize) -> usize {
+    (0..maze.cell_count())
+        .filter(|cell| maze.room_at(maze.point(*cell)) == Some(room))
+        .flat_map(|cell| maze.passages(cell))
+        .filter(|neighbor| maze.room_at(maze.point(*neighbor)) != Some(room))
+        .count()
+}
+
+#[test]
+fn listed_rooms_must_fit() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut maze = Maze::new(10, 10, registry.create_default("prims").unwrap());
+
+    let outside = RoomLayout { rooms: vec![Room::new(8, 8, 3, 3)], ..RoomLayout::default() };
+    assert!(matches!(maze.set_room_layout(Some(outside)), Err(MazeError::InvalidRoom(_))));
+
+    let overlapping = RoomLayout { rooms: vec![Room::new(1, 1, 3, 3), Room::new(3, 3, 2, 2)], ..RoomLayout::default() };
+    assert!(matches!(maze.set_room_layout(Some(overlapping)), Err(MazeError::InvalidRoom(_))));
+}
+
+proptest! {
+    //Rooms have loops inside them, but every cell is still reachable and every room has its doors
+    #[test]
+    fn rooms_are_open_and_connected(width in 3..24usize, height in 3..24usize, random_rooms in 0..6usize, doors_wanted in 1..4usize, seed in any::<u64>()) {
+        let registry = AlgorithmRegistry::with_builtins();
+
+        for name in registry.names() {
+            let mut maze = Maze::new(width, height, registry.create_default(name).unwrap());
+            let layout = RoomLayout { rooms: vec![Room::new(0, 0, 2, 2)], random_rooms, doors: doors_wanted, ..RoomLayout::default() };
+            maze.set_room_layout(Some(layout)).unwrap();
+            maze.regenerate_with_seed(seed);
+
+            let violations = maze_validator::validate(&maze).err().unwrap_or_default();
+            prop_assert!(violations.iter().all(|violation| matches!(violation, MazeViolation::Cycle(_, _))), "{:?}", violations);
+
+            for index in 0..maze.rooms().len() {
+                prop_assert!(doors(&maze, index) >= doors_wanted);
+            }
+        }
+    }
+}
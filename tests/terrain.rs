@@ -0,0 +1,80 @@
+extern crate maze_game;
+extern crate proptest;
+extern crate rand;
+
+use proptest::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand::rngs::StdRng;
+
+use maze_game::maze::Maze;
+use maze_game::maze_grid::MazeGrid;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_solver;
+use maze_game::maze_terrain::Terrain;
+
+//A maze with rough terrain on about fraction of its cells, and some extra walls knocked down
+//so there's more than one way through it
+fn braided(width: usize, height: usize, fraction: f64, seed: u64) -> Maze {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut maze = Maze::new(width, height, registry.create_default("prims").unwrap());
+    maze.regenerate_with_seed(seed);
+
+    let mut rng = StdRng::seed_from_u64(seed);
+    for cell in 0..maze.cell_count() {
+        for (neighbor, wall) in maze.neighbors(cell) {
+            if neighbor > cell && rng.gen_bool(0.3) {
+                wall.borrow_mut().active = false;
+            }
+        }
+    }
+    maze.randomize_terrain(fraction, &mut rng);
+
+    maze
+}
+
+//What it costs to walk path, the first cell being where it starts
+fn path_cost(maze: &Maze, path: &[usize]) -> u32 {
+    path[1..].iter().map(|cell| maze.cost(*cell)).sum()
+}
+
+#[test]
+fn terrain_comes_from_the_rng() {
+    let terrain = |maze: &Maze| (0..maze.cell_count()).map(|cell| maze.terrain(maze.point(cell))).collect::<Vec<_>>();
+
+    assert_eq!(terrain(&braided(8, 8, 0.5, 3)), terrain(&braided(8, 8, 0.5, 3)));
+    assert_ne!(terrain(&braided(8, 8, 0.5, 3)), terrain(&braided(8, 8, 0.5, 4)));
+    assert!(terrain(&braided(8, 8, 0.0, 3)).iter().all(|cell| *cell == Terrain::Floor));
+}
+
+#[test]
+fn cheapest_way_goes_round_rough_terrain() {
+    //The shortest way through is often not the cheapest one once there's terrain to go round
+    let cheaper = (0..20).filter(|seed| {
+        let maze = braided(10, 10, 0.5, *seed);
+        let shortest = maze_solver::solve(&maze).unwrap();
+        let (_, cheapest) = maze_solver::solve_cheapest(&maze).unwrap();
+
+        cheapest < path_cost(&maze, &shortest)
+    }).count();
+
+    assert!(cheaper > 0);
+}
+
+proptest! {
+    //A* finds a way exactly as cheap as Dijkstra's, and neither is ever dearer than the shortest way
+    #[test]
+    fn astar_and_dijkstra_agree(width in 2..12usize, height in 2..12usize, fraction in 0.0..1.0f64, seed in any::<u64>()) {
+        let maze = braided(width, height, fraction, seed);
+        let (_, exit) = maze.exits().unwrap();
+
+        let shortest = maze_solver::solve(&maze).unwrap();
+        let (dijkstra_path, dijkstra) = maze_solver::solve_cheapest(&maze).unwrap();
+        let (astar_path, astar) = maze_solver::solve_astar(&maze, &|cell| maze.estimated_cost(cell, exit)).unwrap();
+
+        prop_assert_eq!(astar, dijkstra);
+        prop_assert_eq!(path_cost(&maze, &dijkstra_path), dijkstra);
+        prop_assert_eq!(path_cost(&maze, &astar_path), astar);
+        prop_assert!(dijkstra <= path_cost(&maze, &shortest));
+    }
+}
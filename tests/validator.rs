@@ -0,0 +1,105 @@
+extern crate maze_game;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use maze_game::maze::Maze;
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_mask::MazeMask;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_validator::{self, MazeViolation};
+use maze_game::maze_wall::MazeWall;
+
+//A valid 6x5 maze generated by every algorithm in the registry, each from the same seed
+fn generated_mazes() -> Vec<Maze> {
+    let registry = AlgorithmRegistry::with_builtins();
+
+    registry.names().iter().map(|name| {
+        let mut maze = Maze::new(6, 5, registry.create_default(name).unwrap());
+        maze.regenerate_with_seed(7);
+        assert_eq!(maze_validator::validate(&maze), Ok(()), "{}", name);
+        maze
+    }).collect()
+}
+
+#[test]
+fn unshared_wall_is_reported() {
+    for mut maze in generated_mazes() {
+        let point = Point { x: 2, y: 3 };
+        let copy = MazeWall::new(maze.cells()[2][3].right_wall().active);
+        maze.cell_mut(point).set_right_wall(Rc::new(RefCell::new(copy)));
+
+        assert_eq!(maze_validator::validate(&maze), Err(vec![MazeViolation::UnsharedWall(point, Point { x: 3, y: 3 })]));
+    }
+}
+
+#[test]
+fn border_opening_is_reported() {
+    for maze in generated_mazes() {
+        maze.cells()[4][0].top_wall_mut().borrow_mut().active = false;
+
+        assert_eq!(maze_validator::validate(&maze), Err(vec![MazeViolation::BorderOpening(Point { x: 4, y: 0 })]));
+    }
+}
+
+#[test]
+fn closed_exit_is_reported() {
+    for maze in generated_mazes() {
+        let (_, exit) = maze.exits().unwrap();
+        let exit = maze.point(exit);
+        maze.cells()[exit.x][exit.y].right_wall_mut().borrow_mut().active = true;
+
+        assert_eq!(maze_validator::validate(&maze), Err(vec![MazeViolation::ClosedExit(exit)]));
+    }
+}
+
+#[test]
+fn sealed_cell_is_reported() {
+    for maze in generated_mazes() {
+        //A dead end away from the exits, so sealing it cuts off nothing else
+        let (entrance, exit) = maze.exits().unwrap();
+        let dead_end = (0..maze.cell_count())
+            .find(|cell| *cell != entrance && *cell != exit && maze.passages(*cell).len() == 1)
+            .unwrap();
+
+        for (_, wall) in maze.neighbors(dead_end) {
+            wall.borrow_mut().active = true;
+        }
+
+        assert_eq!(maze_validator::validate(&maze), Err(vec![MazeViolation::Unreachable(dead_end)]));
+    }
+}
+
+#[test]
+fn mask_without_cells_is_reported() {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut mask = MazeMask::new(3, 2);
+    for x in 0..3 {
+        for y in 0..2 {
+            mask.set_enabled(x, y, false);
+        }
+    }
+    let maze = Maze::with_mask(mask, registry.create_default("kruskals").unwrap());
+
+    assert_eq!(maze_validator::validate(&maze), Err(vec![MazeViolation::NoCells]));
+    assert_eq!(maze_validator::validate_grid(&maze), Err(vec![MazeViolation::NoCells]));
+}
+
+#[test]
+fn loop_cut_off_from_the_entrance_is_reported() {
+    for maze in generated_mazes() {
+        //Wall a 2x2 block off from the rest of the maze and open every wall inside it
+        let block: Vec<usize> = [(2, 1), (3, 1), (2, 2), (3, 2)].iter().map(|&(x, y)| maze.cell_id(Point { x, y })).collect();
+        for cell in block.iter() {
+            for (neighbor, wall) in maze.neighbors(*cell) {
+                wall.borrow_mut().active = !block.contains(&neighbor);
+            }
+        }
+
+        let violations = maze_validator::validate(&maze).unwrap_err();
+        let cycles: Vec<MazeViolation> = violations.iter().copied().filter(|violation| matches!(violation, MazeViolation::Cycle(..))).collect();
+        assert!(matches!(cycles[..], [MazeViolation::Cycle(one, two)] if block.contains(&one) && block.contains(&two)), "{:?}", violations);
+        assert!(block.iter().all(|cell| violations.contains(&MazeViolation::Unreachable(*cell))));
+    }
+}
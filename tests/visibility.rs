@@ -0,0 +1,107 @@
+extern crate maze_game;
+extern crate proptest;
+
+use proptest::prelude::*;
+
+use maze_game::maze::Maze;
+use maze_game::maze_3d::{Maze3D, Point3D, Direction3D};
+use maze_game::maze_editor::MazeEditor;
+use maze_game::maze_grid::Point;
+use maze_game::maze_play::MazeGame;
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_visibility::{CellView, SightOptions};
+
+fn maze(width: usize, height: usize, seed: u64) -> Maze {
+    let registry = AlgorithmRegistry::with_builtins();
+    let mut maze = Maze::new(width, height, registry.create_default("depth_first_search").unwrap());
+    maze.regenerate_with_seed(seed);
+    maze
+}
+
+#[test]
+fn walls_block_sight() {
+    let point = |x, y| Point { x, y };
+
+    //An open room, with a single wall put back up to the right of the middle cell
+    let mut editor = MazeEditor::new(maze(7, 7, 0));
+    editor.fill(0, 0, 7, 7, false).unwrap();
+    editor.set_wall(point(3, 3), point(4, 3), true).unwrap();
+    let maze = editor.into_maze();
+
+    let everything = SightOptions { radius: None, line_of_sight: true };
+    let seen = maze.visible_from(point(3, 3), &everything);
+    assert!(!seen.contains(&point(4, 3)));
+    assert!(!seen.contains(&point(6, 3)));
+    assert!(seen.contains(&point(4, 2)));
+    assert!(seen.contains(&point(0, 0)));
+
+    //The wall's shadow widens behind it, but it's clear past either end of the wall
+    assert!(!seen.contains(&point(5, 2)));
+    assert!(seen.contains(&point(5, 1)));
+    assert!(seen.contains(&point(4, 4)));
+
+    //Without line of sight, the radius alone decides
+    let torch = SightOptions { radius: Some(2), line_of_sight: false };
+    let seen = maze.visible_from(point(3, 3), &torch);
+    assert_eq!(seen.len(), 13);
+    assert!(seen.contains(&point(5, 3)));
+    assert!(!seen.contains(&point(5, 4)));
+}
+
+#[test]
+fn fog_remembers_explored_cells() {
+    let maze = Maze3D::new(12, 12, 1, AlgorithmRegistry::with_builtins().create_default("prims").unwrap());
+    let mut game = MazeGame::with_fog(maze, SightOptions { radius: Some(2), line_of_sight: true });
+    assert_eq!(game.view(game.player()), CellView::Visible);
+    assert_eq!(game.view(game.goal()), CellView::Hidden);
+    assert!(game.to_string().contains("░░"));
+
+    //Cells go out of sight as the player walks around, but are never forgotten
+    let floor: Vec<Point3D> = (0..12).flat_map(|x| (0..12).map(move |y| Point3D { x, y, z: 0 })).collect();
+    let in_sight: Vec<Point3D> = floor.iter().copied().filter(|point| game.view(*point) == CellView::Visible).collect();
+
+    for step in 0..100 {
+        game.try_move([Direction3D::East, Direction3D::South, Direction3D::West, Direction3D::North][step * 7 % 4]);
+    }
+
+    assert!(in_sight.iter().all(|point| game.view(*point) != CellView::Hidden));
+    assert!(floor.iter().all(|point| (game.view(*point) == CellView::Visible) == game.maze().visible_from(game.player(), &game.sight().unwrap()).contains(point)));
+
+    let plain = MazeGame::new(Maze3D::new(12, 12, 1, AlgorithmRegistry::with_builtins().create_default("prims").unwrap()));
+    assert_eq!(plain.view(Point3D { x: 11, y: 11, z: 0 }), CellView::Visible);
+    assert!(!plain.to_string().contains("░░"));
+}
+
+proptest! {
+    //Seeing is mutual, stays within the radius, and always takes in the cells through an open wall
+    #[test]
+    fn sight_is_mutual(width in 1..12usize, height in 1..12usize, radius in prop::option::of(0..6usize), seed in any::<u64>(), x in 0..12usize, y in 0..12usize) {
+        let maze = maze(width, height, seed);
+        let options = SightOptions { radius, line_of_sight: true };
+        let origin = Point { x: x % width, y: y % height };
+        let seen = maze.visible_from(origin, &options);
+
+        prop_assert!(seen.contains(&origin));
+
+        for point in &seen {
+            let dx = point.x.abs_diff(origin.x);
+            let dy = point.y.abs_diff(origin.y);
+            prop_assert!(radius.is_none_or(|radius| dx * dx + dy * dy <= radius * radius));
+            prop_assert!(maze.visible_from(*point, &options).contains(&origin), "{:?} sees {:?} but not back", origin, point);
+        }
+
+        if radius != Some(0) {
+            let cell = &maze.cells()[origin.x][origin.y];
+            let right = Point { x: origin.x + 1, y: origin.y };
+            let below = Point { x: origin.x, y: origin.y + 1 };
+
+            if right.x < width && !cell.right_wall().active {
+                prop_assert!(seen.contains(&right));
+            }
+            if below.y < height && !cell.bottom_wall().active {
+                prop_assert!(seen.contains(&below));
+            }
+        }
+    }
+}
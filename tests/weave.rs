@@ -0,0 +1,104 @@
+extern crate maze_game;
+
+use std::rc::Rc;
+
+use maze_game::maze_grid::{MazeGrid, Point};
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_render::{MazeDrawing, Stroke};
+use maze_game::maze_solver;
+use maze_game::maze_weave::{Axis, WeaveMaze};
+
+//A 3x3 weave maze only has one inner cell, and with a density of 1 the one crossing tried on it is always placed
+fn crossed_maze(seed: u64) -> WeaveMaze {
+    let mut maze = WeaveMaze::with_density(3, 3, 1.0);
+    maze.regenerate_with_seed(seed);
+    maze
+}
+
+fn has_line(strokes: &[Stroke], from: (f64, f64), to: (f64, f64)) -> bool {
+    let same = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).abs() < 1e-9 && (a.1 - b.1).abs() < 1e-9;
+    strokes.iter().any(|stroke| matches!(stroke, Stroke::Line { from: one, to: two } if same(*one, from) && same(*two, to)))
+}
+
+#[test]
+fn crossings_pass_over_a_tunnel() {
+    let centre = Point { x: 1, y: 1 };
+
+    for seed in 0..16 {
+        let maze = crossed_maze(seed);
+        let crossing = maze.crossing(centre).expect("the only inner cell gets a crossing");
+        let cell = &maze.cells()[1][1];
+        assert!(crossing.tunnel_open());
+
+        //The passage on top goes straight through the crossing
+        let (over_open, under_ends) = match crossing.over() {
+            Axis::Vertical => (!cell.top_wall().active && !cell.bottom_wall().active, [Point { x: 0, y: 1 }, Point { x: 2, y: 1 }]),
+            Axis::Horizontal => (!cell.left_wall().active && !cell.right_wall().active, [Point { x: 1, y: 0 }, Point { x: 1, y: 2 }]),
+        };
+        assert!(over_open, "{}", seed);
+
+        //The cells on the under side are neighbors of each other rather than of the crossing,
+        //joined by the tunnel, which is a wall of its own and not one of any cell's
+        let one = maze.cell_id(under_ends[0]);
+        let two = maze.cell_id(under_ends[1]);
+        let (_, tunnel) = maze.neighbors(one).into_iter().find(|(neighbor, _)| *neighbor == two).unwrap();
+        assert!(!maze.neighbors(one).iter().any(|(neighbor, _)| *neighbor == maze.cell_id(centre)));
+        assert!(!maze.walls().iter().any(|wall| Rc::ptr_eq(wall, &tunnel)));
+        assert!(!tunnel.borrow().active);
+    }
+}
+
+#[test]
+fn way_through_goes_under_the_crossing() {
+    let mut seen = Vec::new();
+
+    for seed in 0..16 {
+        let maze = crossed_maze(seed);
+        let under = maze.crossing(Point { x: 1, y: 1 }).unwrap().under();
+        seen.push(under);
+
+        //Close every wall of the cells, then open a way in and out of the tunnel from the entrance and exit
+        for wall in maze.walls() {
+            wall.borrow_mut().active = true;
+        }
+        maze.open_exits();
+
+        let cells = maze.cells();
+        let path = match under {
+            Axis::Horizontal => {
+                cells[0][0].bottom_wall_mut().borrow_mut().active = false;
+                cells[2][1].bottom_wall_mut().borrow_mut().active = false;
+                vec![Point { x: 0, y: 0 }, Point { x: 0, y: 1 }, Point { x: 2, y: 1 }, Point { x: 2, y: 2 }]
+            }
+            Axis::Vertical => {
+                cells[0][0].right_wall_mut().borrow_mut().active = false;
+                cells[1][2].right_wall_mut().borrow_mut().active = false;
+                vec![Point { x: 0, y: 0 }, Point { x: 1, y: 0 }, Point { x: 1, y: 2 }, Point { x: 2, y: 2 }]
+            }
+        };
+
+        let solution: Vec<Point> = maze_solver::solve(&maze).unwrap().into_iter().map(|cell| maze.point(cell)).collect();
+        assert_eq!(solution, path, "{}", seed);
+
+        //The corridor on top runs the whole way through the crossing, and the one underneath stops at its sides.
+        //The cells at either end of the tunnel draw their corridor open towards it, even though their wall there is up.
+        let strokes = maze.wall_strokes();
+        match under {
+            Axis::Horizontal => {
+                assert!(has_line(&strokes, (1.2, 1.0), (1.2, 2.0)) && has_line(&strokes, (1.8, 1.0), (1.8, 2.0)));
+                assert!(has_line(&strokes, (1.0, 1.2), (1.2, 1.2)) && has_line(&strokes, (1.8, 1.2), (2.0, 1.2)));
+                assert!(has_line(&strokes, (0.8, 1.2), (1.0, 1.2)) && !has_line(&strokes, (0.8, 1.2), (0.8, 1.8)));
+                assert!(has_line(&strokes, (2.0, 1.2), (2.2, 1.2)) && !has_line(&strokes, (2.2, 1.2), (2.2, 1.8)));
+            }
+            Axis::Vertical => {
+                assert!(has_line(&strokes, (1.0, 1.2), (2.0, 1.2)) && has_line(&strokes, (1.0, 1.8), (2.0, 1.8)));
+                assert!(has_line(&strokes, (1.2, 1.0), (1.2, 1.2)) && has_line(&strokes, (1.2, 1.8), (1.2, 2.0)));
+                assert!(has_line(&strokes, (1.2, 0.8), (1.2, 1.0)) && !has_line(&strokes, (1.2, 0.8), (1.8, 0.8)));
+                assert!(has_line(&strokes, (1.2, 2.0), (1.2, 2.2)) && !has_line(&strokes, (1.2, 2.2), (1.8, 2.2)));
+            }
+        }
+    }
+
+    //Both ways of crossing over were tried
+    assert!(seen.contains(&Axis::Horizontal) && seen.contains(&Axis::Vertical));
+}
@@ -0,0 +1,98 @@
+extern crate maze_game;
+
+use std::rc::Rc;
+
+use maze_game::maze::{Maze, Wraparound};
+use maze_game::maze_grid::{Direction, MazeGrid, Point};
+use maze_game::maze_regenerate::Regenerate;
+use maze_game::maze_registry::AlgorithmRegistry;
+use maze_game::maze_render::{MazeDrawing, Stroke};
+
+fn wrapped_maze(width: usize, height: usize, wraparound: Wraparound) -> Maze {
+    Maze::with_wraparound(width, height, wraparound, AlgorithmRegistry::with_builtins().create_default("depth_first_search").unwrap())
+}
+
+//A maze with every wall up except the entrance and exit
+fn closed_maze(width: usize, height: usize, wraparound: Wraparound) -> Maze {
+    let mut maze = wrapped_maze(width, height, wraparound);
+    maze.reset();
+    maze.open_exits();
+    maze
+}
+
+fn neighbor_points(maze: &Maze, point: Point) -> Vec<Point> {
+    maze.neighbors(maze.cell_id(point)).into_iter().map(|(cell, _)| maze.point(cell)).collect()
+}
+
+#[test]
+fn joined_edges_share_one_wall() {
+    let cylinder = wrapped_maze(4, 3, Wraparound::Cylinder);
+    let torus = wrapped_maze(4, 3, Wraparound::Torus);
+
+    for y in 0..3 {
+        assert!(Rc::ptr_eq(cylinder.cells()[3][y].right_wall_mut(), cylinder.cells()[0][y].left_wall_mut()));
+        assert!(Rc::ptr_eq(torus.cells()[3][y].right_wall_mut(), torus.cells()[0][y].left_wall_mut()));
+    }
+    for x in 0..4 {
+        assert!(!Rc::ptr_eq(cylinder.cells()[x][2].bottom_wall_mut(), cylinder.cells()[x][0].top_wall_mut()));
+        assert!(Rc::ptr_eq(torus.cells()[x][2].bottom_wall_mut(), torus.cells()[x][0].top_wall_mut()));
+    }
+
+    //Every cell has a top and left wall of its own, and only the edges that aren't joined add border walls
+    assert_eq!(wrapped_maze(4, 3, Wraparound::None).walls().len(), 31);
+    assert_eq!(cylinder.walls().len(), 28);
+    assert_eq!(torus.walls().len(), 24);
+}
+
+#[test]
+fn cylinder_only_wraps_sideways() {
+    let maze = wrapped_maze(4, 3, Wraparound::Cylinder);
+    let corner = Point { x: 0, y: 0 };
+
+    //Left, right, up, down
+    assert_eq!(neighbor_points(&maze, corner), vec![Point { x: 3, y: 0 }, Point { x: 1, y: 0 }, Point { x: 0, y: 1 }]);
+    assert_eq!(maze.step(corner, Direction::West), Some(Point { x: 3, y: 0 }));
+    assert_eq!(maze.step(corner, Direction::North), None);
+    assert_eq!(maze.step(Point { x: 3, y: 2 }, Direction::East), Some(Point { x: 0, y: 2 }));
+    assert_eq!(maze.step(Point { x: 3, y: 2 }, Direction::South), None);
+}
+
+#[test]
+fn torus_wraps_both_ways() {
+    let maze = wrapped_maze(4, 3, Wraparound::Torus);
+    let corner = Point { x: 0, y: 0 };
+
+    assert_eq!(neighbor_points(&maze, corner), vec![Point { x: 3, y: 0 }, Point { x: 1, y: 0 }, Point { x: 0, y: 2 }, Point { x: 0, y: 1 }]);
+    assert_eq!(maze.step(corner, Direction::North), Some(Point { x: 0, y: 2 }));
+    assert_eq!(maze.step(Point { x: 3, y: 2 }, Direction::South), Some(Point { x: 3, y: 0 }));
+
+    //There's no border left to put the entrance and exit in
+    let closed = closed_maze(4, 3, Wraparound::Torus);
+    assert!(closed.walls().iter().all(|wall| wall.borrow().active));
+}
+
+#[test]
+fn wrapped_openings_are_drawn_on_both_edges() {
+    let maze = closed_maze(2, 2, Wraparound::Cylinder);
+    maze.cells()[0][1].left_wall_mut().borrow_mut().active = false;
+
+    //The entrance is in the top of the first cell and the exit in the bottom of the last one.
+    //The passage across the joined edge shows as a gap on both sides.
+    let expected = concat!(
+        "██  ██████\n",
+        "██  ██  ██\n",
+        "██████████\n",
+        "    ██    \n",
+        "██████  ██\n",
+    );
+    assert_eq!(maze.to_string(), expected);
+
+    let edge_strokes: Vec<Stroke> = maze.wall_strokes().into_iter().filter(|stroke| match stroke {
+        Stroke::Line { from, to } => from.0 == to.0 && (from.0 == 0.0 || from.0 == 2.0),
+        Stroke::Arc { .. } => false,
+    }).collect();
+    assert_eq!(edge_strokes, vec![
+        Stroke::Line { from: (0.0, 0.0), to: (0.0, 1.0) },
+        Stroke::Line { from: (2.0, 0.0), to: (2.0, 1.0) },
+    ]);
+}